@@ -1,26 +1,318 @@
-use crate::{math::mean_and_std_deviation, progress::ProgressUpdateCondition};
-use anyhow::{anyhow, Result};
+use crate::{
+    copc, crs, ept,
+    error::{self, AnalyzerError},
+    geojson, index, lax,
+    manifest::{self, ManifestEntry},
+    math::{gini_coefficient, mean_and_std_deviation, rounded_percentages, skewness_and_kurtosis},
+    node_key::NodeKey,
+    potree_v2::{self, LayoutCandidate, OctreeNode, PotreeV2Metadata, PotreeV2Node, RecordLayout},
+    progress::ProgressUpdateCondition,
+    quantiles::QuantileSketch,
+    remote,
+    status::StatusFile,
+    vpc::{self, VpcEntry},
+};
+use anyhow::{anyhow, Context, Result};
 use core::fmt::Display;
 use core::fmt::Formatter;
-use las::{Read, Reader};
+use glob::Pattern;
+use las::{Bounds, Read, Reader, Vector};
 use rayon::prelude::*;
+use regex::Regex;
+use serde::{Serialize, Serializer};
 use signifix::metric;
-use std::fs::File;
-use std::io::BufReader;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::{convert::TryFrom, ops::Range};
+use std::time::{Duration, Instant, SystemTime};
+use std::{
+    convert::{TryFrom, TryInto},
+    ops::Range,
+};
 use std::{
     path::{Path, PathBuf},
     sync::Mutex,
 };
-use walkdir::WalkDir;
+use walkdir::{DirEntry, WalkDir};
+
+use crate::progress::{self, ProgressFormat, ProgressTracker, Spinner};
+
+/// Prints a status message to stderr, unless the `quiet` flag of the given `AnalysisOptions` is set. Warnings and hints
+/// are printed with `eprintln!` instead, so that they are shown even with `--quiet`
+macro_rules! info {
+    ($options:expr, $($arg:tt)*) => {
+        if !$options.quiet {
+            eprintln!($($arg)*);
+        }
+    };
+}
 
-use crate::progress::ProgressTracker;
+/// Order of the nodes in the node dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpSort {
+    /// The order in which the nodes were found, e.g. the order of the files or of the records of the hierarchy
+    Discovery,
+    /// Ascending by point count. Nodes with the same point count keep the order in which they were found
+    PointCount,
+}
+
+impl Default for DumpSort {
+    fn default() -> Self {
+        DumpSort::Discovery
+    }
+}
 
-/// Generate histogram with logarithmic bucket size or linear bucket size?
+/// Generate histogram with logarithmic bucket size, linear bucket size or custom buckets?
+#[derive(Debug, Clone)]
 pub enum HistogramConfig {
     Logarithmic(usize),
     Linear(usize),
+    /// Buckets between the given boundaries, which must be strictly increasing. Point counts below the first
+    /// boundary go into an additional underflow bucket, point counts at or above the last boundary into an open-ended
+    /// overflow bucket that ends at `usize::MAX`
+    Custom(Vec<usize>),
+    /// The given number of buckets with approximately the same number of nodes each
+    Quantile(usize),
+}
+
+/// Which analyses an `Analyzer` should perform
+#[derive(Default)]
+pub struct AnalysisOptions {
+    /// Count the number of nodes
+    pub count_nodes: bool,
+    /// Calculate a histogram of the points per node
+    pub histogram_config: Option<HistogramConfig>,
+    /// Calculate a histogram of the sizes of the nodes in bytes, independent of the histogram of the points per
+    /// node. Not supported for manifests and virtual point clouds
+    pub size_histogram_config: Option<HistogramConfig>,
+    /// Render the histogram of the points per node as a single line of block characters. Requires
+    /// `histogram_config`
+    pub sparkline: bool,
+    /// Calculate the Gini coefficient of the points per node
+    pub gini: bool,
+    /// Calculate the skewness and kurtosis of the points per node
+    pub moments: bool,
+    /// Calculate the mean and standard deviation of the points per node
+    pub stats: bool,
+    /// Calculate these percentiles of the points per node, each in [0;100]
+    pub percentiles: Vec<f64>,
+    /// Sum up the points of all nodes
+    pub total_points: bool,
+    /// Only include the nodes whose point count is within this inclusive range in the histogram, the statistics and
+    /// all other analyses of the point counts
+    pub count_range: Option<(u64, u64)>,
+    /// Estimate quantiles of the points per node with this relative accuracy while reading the nodes, without
+    /// sorting the point counts. Not supported for manifests and virtual point clouds
+    pub approx_quantiles: Option<f64>,
+    /// Report the nodes that contain more than this number of points, which is more than renderers handle well
+    pub warn_above: Option<u64>,
+    /// Estimate how much the nodes would have to be downsampled so that no node contains more than this number of
+    /// points
+    pub downsample_target: Option<u64>,
+    /// Calculate the number of points in leaf nodes vs. inner nodes. Only supported for PotreeConverter v2
+    pub leaf_point_ratio: bool,
+    /// Output information about every single node
+    pub dump_nodes: bool,
+    /// Only dump the first N nodes in the order of `dump_sort`. Can be combined with `dump_tail`
+    pub dump_head: Option<usize>,
+    /// Only dump the last N nodes in the order of `dump_sort`. Can be combined with `dump_head`
+    pub dump_tail: Option<usize>,
+    /// Order of the dumped nodes, which also decides which nodes `dump_head` and `dump_tail` keep
+    pub dump_sort: DumpSort,
+    /// Estimate the size of the point data in uncompressed form. Only supported for formats where one node equals one file
+    pub estimate_uncompressed: bool,
+    /// Sum the sizes of all files of the dataset on disk, including the hierarchy and metadata files. Not supported
+    /// for manifests, virtual point clouds and Entwine datasets with binary or zstandard node files
+    pub disk_usage: bool,
+    /// Calculate the mean number of children of all inner nodes. Only supported for PotreeConverter v2
+    pub mean_fanout: bool,
+    /// Summarize the number of nodes and points of each octree level. Only supported for PotreeConverter v2
+    pub level_summary: bool,
+    /// Calculate the cumulative number of points when loading the octree down to each level. Only supported for
+    /// PotreeConverter v2
+    pub lod_curve: bool,
+    /// Count the number of distinct octree levels that contain nodes. Not supported for manifests and virtual point
+    /// clouds
+    pub level_count: bool,
+    /// Flag nodes that contain more points than their parent node. Only supported for PotreeConverter v2
+    pub check_hierarchy: bool,
+    /// Flag nodes that are referenced by the child mask of their parent or by a proxy node, but are missing from the
+    /// hierarchy. Only supported for PotreeConverter v2
+    pub check_children: bool,
+    /// Compare the byte size of each node with the size that is expected from its point count and the attribute
+    /// schema in the metadata. Only supported for PotreeConverter v2 datasets with uncompressed points
+    pub check_attributes: bool,
+    /// Count the nodes both by scanning all records of the hierarchy and by walking the hierarchy from the root node,
+    /// and compare the two counts. Only supported for PotreeConverter v2
+    pub round_trip: bool,
+    /// Check the bounding boxes of all nodes for overlaps that are not explained by the nesting of the octree. Only
+    /// supported for formats where one node equals one file
+    pub check_overlap: bool,
+    /// Check the dataset for inconsistencies. Only supported for formats where one node equals one file
+    pub check_integrity: bool,
+    /// Compare the effective point spacing of each octree level with the spacing that is expected from the root
+    /// spacing, flagging levels that deviate by more than the given relative tolerance. Only supported for
+    /// PotreeConverter v2
+    pub gsd_check: Option<f64>,
+    /// Print the first N raw node records of the hierarchy to stderr. Only supported for PotreeConverter v2
+    pub trace_nodes: Option<usize>,
+    /// Don't print any progress information or status messages, e.g. about analyses that are skipped. Warnings and
+    /// hints are still printed
+    pub quiet: bool,
+    /// Don't print hints on how to improve the analysis, such as a better bucketing for an imbalanced histogram
+    pub no_hints: bool,
+    /// Print progress updates as JSON lines instead of human-readable text
+    pub progress_json: bool,
+    /// Periodically write the progress and all results that are available so far to this status file. The progress
+    /// is only reported while reading the files of formats where one node equals one file
+    pub status_file: Option<Arc<StatusFile>>,
+    /// Setting this flag stops searching for and reading further node files, and skips the remaining hierarchy
+    /// checks of PotreeConverter v2 datasets. The analysis then finishes with partial results from the work that was
+    /// done so far
+    pub cancelled: Arc<AtomicBool>,
+    /// Set by the analyzer if it skipped any work because `cancelled` was set. Only then are the results partial, as
+    /// the analysis might have finished before the flag was set
+    pub incomplete: Arc<AtomicBool>,
+    /// Print how many files each worker thread read and how long it took. Only supported for formats where one node
+    /// equals one file
+    pub work_stats: bool,
+    /// Report the EPSG code of the coordinate reference system of the dataset
+    pub report_epsg: bool,
+    /// Fail the analysis if the coordinate reference system of the dataset does not have this EPSG code
+    pub require_epsg: Option<u32>,
+    /// Read the point count of each file from the extended VLR with this user ID instead of the header, falling back
+    /// to the header if the file has no such EVLR. Only supported for formats where one node equals one file
+    pub evlr_count: Option<String>,
+    /// Take the point count of each file from its file name instead of opening the file. The capture group `count` of
+    /// this regex supplies the point count. Files whose names don't match fall back to the header. Only supported for
+    /// formats where one node equals one file
+    pub count_from_name: Option<Regex>,
+    /// Skip files that are larger than this number of bytes without opening them. Only supported for formats where
+    /// one node equals one file
+    pub max_file_size: Option<u64>,
+    /// Count a node that has both a `.las` and a `.laz` file with the same name only once, using the `.laz` file.
+    /// Such pairs are left behind by conversions that are still running. Only supported for formats where one node
+    /// equals one file
+    pub dedup_extensions: bool,
+    /// For `.las` files whose header declares zero points, compute the point count from the file size and the point
+    /// record length instead, and report the affected files. Only supported for formats where one node equals one
+    /// file
+    pub trust_data: bool,
+    /// Summarize the colors of every n-th point of each file, as a fast estimate of whether the dataset has colors.
+    /// Only supported for formats where one node equals one file
+    pub color_sample: Option<u64>,
+    /// Calculate a histogram of the points per spatial cell from the `.lax` indexes next to the LAS/LAZ files,
+    /// without reading the point data. Uses the buckets of `histogram_config`, or 10 logarithmic buckets if there is
+    /// no histogram of the points per node. Only supported for formats where one node equals one file
+    pub use_lax: bool,
+    /// Report the oldest and newest modification time of all files, and flag the dataset as partially rewritten if
+    /// they are more than this many seconds apart. Only supported for formats where one node equals one file
+    pub mtime_stats: Option<u64>,
+    /// Report groups of files with byte-identical content. Only supported for formats where one node equals one file
+    pub find_duplicates: bool,
+    /// For COPC files, compare the point count of the LAS header with the sum of the point counts of the COPC
+    /// hierarchy. Only supported for formats where one node equals one file
+    pub check_copc: bool,
+    /// Only analyze the nodes whose name starts with this prefix, i.e. a single subtree of the octree. Node names
+    /// follow the naming convention of PotreeConverter, e.g. 'r0426'. For formats where one node equals one file,
+    /// the file names are matched against the prefix instead
+    pub node_prefix: Option<String>,
+    /// Skip all files whose path relative to the root directory matches one of these patterns. Only supported for
+    /// formats where one node equals one file
+    pub exclude: Vec<Pattern>,
+    /// Only search the top level of the root directories for files, without descending into subdirectories. Only
+    /// supported for formats where one node equals one file
+    pub no_recurse: bool,
+    /// Stop descending into subdirectories below this directory depth while searching for files, as a safeguard
+    /// against cyclic mounts. Defaults to `DEFAULT_WALK_MAX_DEPTH`. Only supported for formats where one node equals
+    /// one file
+    pub walk_max_depth: Option<usize>,
+    /// Only analyze nodes up to this octree level, excluding all deeper nodes. Only supported for PotreeConverter v2
+    pub max_depth: Option<u8>,
+    /// Decode the node records of the hierarchy with this layout instead of the one determined from the metadata.
+    /// Only supported for PotreeConverter v2
+    pub record_layout: Option<RecordLayout>,
+    /// Infer the layout of the node records from the hierarchy itself by trying the known layouts, for hierarchies
+    /// without a metadata.json. Ignored if `record_layout` is set. Only supported for PotreeConverter v2
+    pub infer_record_size: bool,
+    /// Only analyze files that were modified after this point in time. Only supported for formats where one node
+    /// equals one file
+    pub modified_since: Option<SystemTime>,
+    /// Write the point counts of all nodes to this index file, which can be analyzed later with
+    /// `ManifestAnalyzer::from_index`
+    pub write_index: Option<PathBuf>,
+    /// Write the XY footprint of the bounding box of each node as a GeoJSON polygon to this file, in the coordinate
+    /// reference system of the dataset. Only supported for formats where one node equals one file
+    pub geojson: Option<PathBuf>,
+    /// Merge the point counts of the analyzed files into this manifest and analyze the merged point counts. Only
+    /// supported for formats where one node equals one file
+    pub update_manifest: Option<PathBuf>,
+    /// The point counts are known to be sorted already, so sorting them can be skipped
+    pub assume_sorted: bool,
+    /// Number of progress entries that the ETA is calculated from. Uses the default of the `ProgressTracker` if `None`
+    pub eta_window: Option<usize>,
+    /// Number of threads that read the files in parallel. Uses the global thread pool of rayon, which has one thread
+    /// per CPU core, if `None`. Only supported for formats where one node equals one file
+    pub threads: Option<usize>,
+    /// Read the files in batches of this many files and only keep the point count and file size of each node, which
+    /// bounds the memory usage for datasets with millions of files. Can't be combined with the analyses that need all
+    /// node information, such as `dump_nodes`. With `warn_above`, only the number of oversized nodes is reported, not
+    /// their files. Only supported for formats where one node equals one file
+    pub batch_size: Option<usize>,
+}
+
+impl AnalysisOptions {
+    /// Do any of the analyses require the point counts of all nodes?
+    fn requires_point_counts(&self) -> bool {
+        self.histogram_config.is_some()
+            || self.gini
+            || self.moments
+            || self.stats
+            || !self.percentiles.is_empty()
+            || self.total_points
+            || self.warn_above.is_some()
+            || self.downsample_target.is_some()
+    }
+
+    /// Does the analysis require the EPSG code of the dataset?
+    fn requires_epsg(&self) -> bool {
+        self.report_epsg || self.require_epsg.is_some()
+    }
+
+    /// Should the analysis stop because `cancelled` was set? Call this only right before work that is skipped if it
+    /// returns true, since it marks the results as incomplete
+    fn stop_requested(&self) -> bool {
+        let cancelled = self.cancelled.load(Ordering::Relaxed);
+        if cancelled {
+            self.incomplete.store(true, Ordering::Relaxed);
+        }
+        cancelled
+    }
+}
+
+/// Formats `value` using a metric prefix, e.g. `1.234 k`. Integers below 1000 and values that can't be represented
+/// with a metric prefix are formatted as plain numbers. Values below 1 are formatted without a prefix as well, since
+/// e.g. `500 m` for a mean of half a point per node would be confusing
+fn format_metric(value: f64) -> String {
+    if value.abs() < 1000.0 && value.fract() == 0.0 {
+        return format!("{}", value);
+    }
+    if value.abs() < 1.0 {
+        return format!("{:.3}", value);
+    }
+    match metric::Signifix::try_from(value) {
+        Ok(formatted) => formatted.to_string(),
+        Err(_) => format!("{}", value),
+    }
+}
+
+/// Formats `value` as a plain number if `raw` is set, or using a metric prefix otherwise
+pub(crate) fn format_number(value: f64, raw: bool) -> String {
+    if raw {
+        format!("{}", value)
+    } else {
+        format_metric(value)
+    }
 }
 
 fn log_histogram(counts: &[usize], num_buckets: usize) -> Histogram {
@@ -34,10 +326,14 @@ fn log_histogram(counts: &[usize], num_buckets: usize) -> Histogram {
     // We use logarithmic bucket sizes based on the maximum number of points
     let mut buckets = vec![];
     for bucket_index in 0..num_buckets {
-        // Have to add 1 because log of 0 is -Inf
-        let bucket_start = (2.0_f64
-            .powf(log_max_points * (bucket_index as f64 / num_buckets as f64)))
-        .round() as usize;
+        // Have to add 1 because log of 0 is -Inf. The first bucket starts at 0 instead of 2^0, so that nodes without
+        // points are counted as well
+        let bucket_start = if bucket_index == 0 {
+            0
+        } else {
+            (2.0_f64.powf(log_max_points * (bucket_index as f64 / num_buckets as f64))).round()
+                as usize
+        };
         let bucket_end = (2.0_f64
             .powf(log_max_points * ((bucket_index + 1) as f64 / num_buckets as f64)))
         .round() as usize;
@@ -86,6 +382,598 @@ fn lin_histogram(counts: &[usize], num_buckets: usize) -> Histogram {
     Histogram::new(buckets, mean, stddev)
 }
 
+/// Calculates a histogram with one bucket per pair of adjacent `boundaries`. The overflow bucket is open-ended, so
+/// histograms of different datasets with the same boundaries have exactly the same buckets
+pub fn custom_histogram(counts: &[usize], boundaries: &[usize]) -> Histogram {
+    let (first_boundary, last_boundary) = match (boundaries.first(), boundaries.last()) {
+        (Some(&first_boundary), Some(&last_boundary)) => (first_boundary, last_boundary),
+        _ => return Histogram::new(vec![], 0.0, 0.0),
+    };
+
+    let mut bucket_bounds = vec![];
+    if first_boundary > 0 {
+        bucket_bounds.push(0..first_boundary);
+    }
+    bucket_bounds.extend(boundaries.windows(2).map(|pair| pair[0]..pair[1]));
+    bucket_bounds.push(last_boundary..usize::MAX);
+
+    let buckets = bucket_bounds
+        .into_iter()
+        .map(|range| {
+            let first_match_index = counts.partition_point(|&count| count < range.start);
+            let last_match_index = counts.partition_point(|&count| count < range.end);
+            HistogramBucket::new(last_match_index - first_match_index, range)
+        })
+        .collect();
+
+    let (mean, stddev) = mean_and_std_deviation(counts).unwrap_or_default();
+
+    Histogram::new(buckets, mean, stddev)
+}
+
+/// Calculates a histogram whose buckets contain approximately the same number of nodes, by splitting the sorted counts
+/// at the indices `i * len / num_buckets`. If many nodes have the same count, several split points have the same
+/// value. These buckets are merged, so there are no empty buckets, but fewer than `num_buckets` buckets
+pub fn quantile_histogram(sorted_counts: &[usize], num_buckets: usize) -> Histogram {
+    let max_points = match sorted_counts.last() {
+        Some(&max_points) if num_buckets > 0 => max_points,
+        _ => return Histogram::new(vec![], 0.0, 0.0),
+    };
+
+    let mut boundaries = vec![0];
+    boundaries.extend(
+        (1..num_buckets)
+            .map(|bucket_index| sorted_counts[bucket_index * sorted_counts.len() / num_buckets]),
+    );
+    boundaries.push(max_points + 1);
+    boundaries.dedup();
+    // If the first split point is the minimum count, no node is below it. The first bucket has to start at 0, so it
+    // is merged with the next one instead of staying empty
+    if boundaries.len() > 2 && boundaries[1] == sorted_counts[0] {
+        boundaries.remove(1);
+    }
+
+    let buckets = boundaries
+        .windows(2)
+        .map(|pair| {
+            let first_match_index = sorted_counts.partition_point(|&count| count < pair[0]);
+            let last_match_index = sorted_counts.partition_point(|&count| count < pair[1]);
+            HistogramBucket::new(last_match_index - first_match_index, pair[0]..pair[1])
+        })
+        .collect();
+
+    let (mean, stddev) = mean_and_std_deviation(sorted_counts).unwrap();
+
+    Histogram::new(buckets, mean, stddev)
+}
+
+/// Sorts the given point counts, unless they are known to be sorted already
+fn sort_point_counts(counts: &mut [usize], assume_sorted: bool) {
+    if assume_sorted {
+        debug_assert!(
+            counts.windows(2).all(|pair| pair[0] <= pair[1]),
+            "Point counts were assumed to be sorted, but they are not!"
+        );
+    } else {
+        counts.sort();
+    }
+}
+
+/// An option that an analyzer can't honor, with what the analyzer does instead
+struct UnsupportedOption {
+    is_set: fn(&AnalysisOptions) -> bool,
+    description: &'static str,
+    fallback: &'static str,
+}
+
+/// Prints a note for each of the `unsupported` options that is set in `options`, `context` names what the
+/// analyzer reads, e.g. "for PotreeConverter v2 datasets"
+fn print_unsupported_notes(
+    options: &AnalysisOptions,
+    unsupported: &[UnsupportedOption],
+    context: &str,
+) {
+    for option in unsupported.iter().filter(|option| (option.is_set)(options)) {
+        info!(
+            options,
+            "{} is not supported {}, {}", option.description, context, option.fallback
+        );
+    }
+}
+
+const fn skipped(
+    is_set: fn(&AnalysisOptions) -> bool,
+    description: &'static str,
+) -> UnsupportedOption {
+    UnsupportedOption {
+        is_set,
+        description,
+        fallback: "skipping",
+    }
+}
+
+/// Everything but the analyses of the point counts, which is all that a manifest contains
+const MANIFEST_UNSUPPORTED_OPTIONS: &[UnsupportedOption] = &[
+    skipped(
+        |options| options.leaf_point_ratio,
+        "Calculating the leaf point ratio",
+    ),
+    skipped(
+        |options| options.gsd_check.is_some(),
+        "Checking the ground sample distance",
+    ),
+    skipped(|options| options.dump_nodes, "Dumping the nodes"),
+    skipped(
+        |options| options.estimate_uncompressed,
+        "Estimating the uncompressed size",
+    ),
+    skipped(|options| options.disk_usage, "Measuring the disk usage"),
+    skipped(|options| options.check_integrity, "Checking the integrity"),
+    skipped(
+        |options| options.check_overlap,
+        "Checking for overlapping nodes",
+    ),
+    skipped(|options| options.check_hierarchy, "Checking the hierarchy"),
+    skipped(
+        |options| options.check_children,
+        "Checking the children of each node",
+    ),
+    skipped(
+        |options| options.round_trip,
+        "Checking the round trip of the hierarchy",
+    ),
+    skipped(
+        |options| options.check_attributes,
+        "Checking the attributes",
+    ),
+    skipped(|options| options.mean_fanout, "Calculating the mean fanout"),
+    skipped(|options| options.level_summary, "Summarizing the levels"),
+    skipped(|options| options.lod_curve, "Calculating the LOD curve"),
+    skipped(|options| options.level_count, "Counting the levels"),
+    skipped(|options| options.color_sample.is_some(), "Sampling colors"),
+    skipped(|options| options.use_lax, "Reading .lax indexes"),
+    skipped(
+        |options| options.geojson.is_some(),
+        "Writing node footprints as GeoJSON",
+    ),
+    skipped(
+        |options| options.mtime_stats.is_some(),
+        "Collecting modification time statistics",
+    ),
+    skipped(
+        |options| options.find_duplicates,
+        "Searching for duplicate files",
+    ),
+    skipped(|options| options.check_copc, "Checking COPC hierarchies"),
+    skipped(
+        |options| options.size_histogram_config.is_some(),
+        "Calculating the size histogram",
+    ),
+    skipped(
+        |options| options.approx_quantiles.is_some(),
+        "Estimating quantiles while reading",
+    ),
+    skipped(AnalysisOptions::requires_epsg, "Reading the EPSG code"),
+];
+
+/// Calculates all results that only require the sorted point counts of the nodes, i.e. the node count and the
+/// histogram
+fn analyze_point_counts(sorted_counts: &[usize], options: &AnalysisOptions) -> Vec<AnalyzerResult> {
+    let mut results = vec![];
+    if options.count_nodes {
+        results.push(AnalyzerResult::NodeCount(sorted_counts.len()));
+    }
+
+    results.extend(point_count_results(sorted_counts, options));
+
+    print_unsupported_notes(
+        options,
+        MANIFEST_UNSUPPORTED_OPTIONS,
+        "when analyzing point counts from a manifest",
+    );
+
+    results
+}
+
+/// Counts the distinct octree levels among the given node levels
+fn level_count_result<I: IntoIterator<Item = u32>>(levels: I) -> AnalyzerResult {
+    let distinct_levels = levels.into_iter().collect::<BTreeSet<_>>();
+    AnalyzerResult::LevelCount(u8::try_from(distinct_levels.len()).unwrap_or(u8::MAX))
+}
+
+/// Number of bytes at the start of each file that are hashed to rule out most files of the same size as duplicates
+/// before hashing their full content
+const DUPLICATE_FINGERPRINT_BYTES: u64 = 1024;
+
+/// Hashes the first `max_bytes` bytes of the file at `path`, or its full content if it is shorter
+fn hash_file(path: &Path, max_bytes: u64) -> Result<u64> {
+    use std::hash::Hasher;
+    use std::io::Read as _;
+
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Could not open {}", path.display()))?;
+    let mut reader = file.take(max_bytes);
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let bytes_read = reader
+            .read(&mut buffer)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+        if bytes_read == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.write(&buffer[..bytes_read]);
+    }
+}
+
+/// Hashes all files in parallel and returns the groups of at least two files with the same hash
+fn group_by_hash<'a, F: Fn(&Path) -> Result<u64> + Sync>(
+    files: Vec<&'a Path>,
+    hash: F,
+) -> Result<Vec<Vec<&'a Path>>> {
+    let hashes = files
+        .par_iter()
+        .map(|file| hash(file))
+        .collect::<Result<Vec<_>>>()?;
+    let mut files_by_hash = HashMap::<u64, Vec<&Path>>::new();
+    for (file, hash) in files.into_iter().zip(hashes) {
+        files_by_hash.entry(hash).or_default().push(file);
+    }
+    Ok(files_by_hash
+        .into_values()
+        .filter(|files| files.len() > 1)
+        .collect())
+}
+
+/// Sorts the nodes for the node dump and keeps only the first `dump_head` and the last `dump_tail` of them, if set.
+/// If the first and last nodes overlap, each node is only dumped once
+fn dumped_nodes(mut nodes: Vec<NodeInfo>, options: &AnalysisOptions) -> Vec<NodeInfo> {
+    if options.dump_sort == DumpSort::PointCount {
+        nodes.sort_by_key(|node| node.point_count);
+    }
+    if options.dump_head.is_none() && options.dump_tail.is_none() {
+        return nodes;
+    }
+
+    let head = options.dump_head.unwrap_or(0).min(nodes.len());
+    let tail_start = nodes.len() - options.dump_tail.unwrap_or(0).min(nodes.len());
+    let tail = nodes.split_off(tail_start.max(head));
+    nodes.truncate(head);
+    nodes.extend(tail);
+    nodes
+}
+
+/// Sums the sizes of all files below the given directories, splitting them into point data and metadata with
+/// `is_data_file`
+fn disk_usage_result<P: AsRef<Path>>(
+    root_dirs: &[P],
+    is_data_file: impl Fn(&Path) -> bool,
+) -> Result<AnalyzerResult> {
+    let mut data_bytes = 0;
+    let mut metadata_bytes = 0;
+    for root_dir in root_dirs.iter() {
+        for entry in WalkDir::new(root_dir) {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let file_size = entry.metadata()?.len();
+            if is_data_file(entry.path()) {
+                data_bytes += file_size;
+            } else {
+                metadata_bytes += file_size;
+            }
+        }
+    }
+    Ok(AnalyzerResult::DiskUsage {
+        data_bytes,
+        metadata_bytes,
+    })
+}
+
+/// Creates the result for the EPSG code of a dataset, failing if the dataset has no EPSG code or if it differs from
+/// the required one
+fn epsg_result(epsg: Option<u32>, options: &AnalysisOptions) -> error::Result<AnalyzerResult> {
+    let epsg = epsg.ok_or(AnalyzerError::MissingEpsg)?;
+    match options.require_epsg {
+        Some(required_epsg) if required_epsg != epsg => Err(AnalyzerError::EpsgMismatch {
+            found: epsg,
+            required: required_epsg,
+        }),
+        _ => Ok(AnalyzerResult::Epsg(epsg)),
+    }
+}
+
+/// Does the point count fall into the `count_range` of the options? Without a count range, all point counts do
+fn in_count_range(point_count: u64, options: &AnalysisOptions) -> bool {
+    options
+        .count_range
+        .map_or(true, |(min, max)| point_count >= min && point_count <= max)
+}
+
+/// Writes the point counts to the index file of the options, if there is one
+fn write_index(point_counts: impl Iterator<Item = u64>, options: &AnalysisOptions) -> Result<()> {
+    if let Some(index_file) = options.write_index.as_ref() {
+        let point_counts = point_counts.collect::<Vec<_>>();
+        index::write_point_counts(index_file, &point_counts)?;
+        info!(
+            options,
+            "Wrote point counts of {} nodes to index {}",
+            point_counts.len(),
+            index_file.display()
+        );
+    }
+    Ok(())
+}
+
+/// Calculates the results that are derived from the sorted point counts of all nodes
+fn point_count_results(sorted_counts: &[usize], options: &AnalysisOptions) -> Vec<AnalyzerResult> {
+    let mut results = vec![];
+    // The total is the size of the whole dataset, so it is not restricted to the count range. Summing as u64 doesn't
+    // overflow even for datasets with billions of points on 32-bit targets
+    if options.total_points {
+        results.push(AnalyzerResult::TotalPoints(
+            sorted_counts.iter().map(|&count| count as u64).sum(),
+        ));
+    }
+
+    // The counts are sorted, so the nodes within the count range are a contiguous part of them
+    let sorted_counts = match options.count_range {
+        Some((min, max)) => {
+            let start = sorted_counts.partition_point(|&count| (count as u64) < min);
+            let end = sorted_counts.partition_point(|&count| count as u64 <= max);
+            info!(
+                options,
+                "{} of {} nodes have between {} and {} points",
+                end - start,
+                sorted_counts.len(),
+                min,
+                max
+            );
+            &sorted_counts[start..end]
+        }
+        None => sorted_counts,
+    };
+
+    if let Some(histogram_config) = options.histogram_config.as_ref() {
+        let histogram = make_histogram(sorted_counts, histogram_config);
+        if !options.no_hints {
+            print_imbalance_hint(&histogram, histogram_config);
+        }
+        if options.sparkline {
+            results.push(AnalyzerResult::Sparkline(sparkline(histogram.buckets())));
+        }
+        results.push(AnalyzerResult::Histogram(histogram));
+    }
+
+    if options.gini {
+        results.push(AnalyzerResult::Gini(
+            gini_coefficient(sorted_counts).unwrap_or_default(),
+        ));
+    }
+
+    if options.moments {
+        let (skewness, kurtosis) = skewness_and_kurtosis(sorted_counts).unwrap_or_default();
+        results.push(AnalyzerResult::Moments { skewness, kurtosis });
+    }
+
+    // Without any nodes, the mean and standard deviation are undefined
+    if options.stats {
+        if let Some((mean, std_dev)) = mean_and_std_deviation(sorted_counts) {
+            results.push(AnalyzerResult::Statistics {
+                mean,
+                std_dev,
+                count: sorted_counts.len(),
+            });
+        }
+    }
+
+    if !options.percentiles.is_empty() && !sorted_counts.is_empty() {
+        results.push(AnalyzerResult::Percentiles(percentiles(
+            sorted_counts,
+            &options.percentiles,
+        )));
+    }
+
+    if let Some(target) = options.downsample_target {
+        results.push(downsample_recommendation(sorted_counts, target));
+    }
+
+    if let Some(threshold) = options.warn_above {
+        let first_oversized_index =
+            sorted_counts.partition_point(|&count| count as u64 <= threshold);
+        results.push(AnalyzerResult::OversizedNodes {
+            threshold,
+            count: sorted_counts.len() - first_oversized_index,
+            node_ids: vec![],
+        });
+    }
+
+    results
+}
+
+/// Calculates the point count at each of the percentiles `ps` (in [0;100]) of the sorted point counts. A percentile
+/// that falls between two nodes is linearly interpolated between their point counts and rounded, so that e.g. the
+/// P99 of a dataset with only a few nodes still lies between its two largest nodes. Returns nothing for no nodes
+fn percentiles(sorted_counts: &[usize], ps: &[f64]) -> Vec<(f64, usize)> {
+    let last_index = match sorted_counts.len().checked_sub(1) {
+        Some(last_index) => last_index,
+        None => return vec![],
+    };
+    ps.iter()
+        .map(|&p| {
+            let rank = (p / 100.0).clamp(0.0, 1.0) * last_index as f64;
+            let lower = sorted_counts[rank.floor() as usize] as f64;
+            let upper = sorted_counts[rank.ceil() as usize] as f64;
+            let point_count = lower + (upper - lower) * rank.fract();
+            (p, point_count.round() as usize)
+        })
+        .collect()
+}
+
+/// Calculates how much the nodes with more than `target` points would have to be downsampled
+fn downsample_recommendation(sorted_counts: &[usize], target: u64) -> AnalyzerResult {
+    let oversized_counts =
+        &sorted_counts[sorted_counts.partition_point(|&count| count as u64 <= target)..];
+    let reduction = |count: usize| count as f64 / target as f64;
+    AnalyzerResult::DownsampleRecommendation {
+        target,
+        count: oversized_counts.len(),
+        share: if sorted_counts.is_empty() {
+            0.0
+        } else {
+            oversized_counts.len() as f64 / sorted_counts.len() as f64
+        },
+        mean_reduction: if oversized_counts.is_empty() {
+            1.0
+        } else {
+            oversized_counts
+                .iter()
+                .map(|&count| reduction(count))
+                .sum::<f64>()
+                / oversized_counts.len() as f64
+        },
+        max_reduction: oversized_counts
+            .last()
+            .map_or(1.0, |&count| reduction(count)),
+    }
+}
+
+/// Directory depth up to which files are searched if no other limit is given. Far deeper than the directory structure
+/// of any real dataset, but low enough to stop quickly on self-referential mounts
+pub const DEFAULT_WALK_MAX_DEPTH: usize = 64;
+
+/// Share of all nodes in a single bucket above which a histogram is considered imbalanced
+const DOMINANT_BUCKET_SHARE: f64 = 0.95;
+
+/// Prints a hint to stderr if almost all nodes are in a single bucket of the histogram, which usually means that the
+/// bucketing does not fit the distribution of the point counts, e.g. linear buckets for log-distributed counts
+fn print_imbalance_hint(histogram: &Histogram, config: &HistogramConfig) {
+    let (bucket, share) = match histogram.dominant_bucket() {
+        Some((bucket, share)) if share > DOMINANT_BUCKET_SHARE && histogram.buckets().len() > 1 => {
+            (bucket, share)
+        }
+        _ => return,
+    };
+    let suggestion = match config {
+        HistogramConfig::Linear(_) => "--histogram-log for logarithmic buckets",
+        HistogramConfig::Logarithmic(_)
+        | HistogramConfig::Custom(_)
+        | HistogramConfig::Quantile(_) => {
+            "--histogram-step or --histogram-bins with a narrower range"
+        }
+    };
+    eprintln!(
+        "Hint: {:.1}% of all nodes are in the bucket [{};{}), so the histogram shows little detail. Try {}, or --count-range to restrict the histogram to a band of nodes. Pass --no-hints to hide hints like this one",
+        100.0 * share,
+        bucket.range().start,
+        format_bucket_end(bucket.range().end, true),
+        suggestion
+    );
+}
+
+/// Quantiles of the points per node that are estimated with `--approx-quantiles`
+const APPROX_QUANTILES: [f64; 5] = [0.5, 0.75, 0.9, 0.95, 0.99];
+
+fn approx_quantiles_result(sketch: &QuantileSketch) -> AnalyzerResult {
+    AnalyzerResult::ApproxQuantiles {
+        relative_accuracy: sketch.relative_accuracy(),
+        quantiles: APPROX_QUANTILES
+            .iter()
+            .filter_map(|&q| Some((q, sketch.quantile(q)?)))
+            .collect(),
+    }
+}
+
+/// Calculates the histogram of the sizes of all nodes in bytes
+fn size_histogram_result(
+    sizes: impl Iterator<Item = u64>,
+    config: &HistogramConfig,
+) -> AnalyzerResult {
+    let mut sorted_sizes = sizes.map(|size| size as usize).collect::<Vec<_>>();
+    sorted_sizes.sort();
+    AnalyzerResult::SizeHistogram(make_histogram(&sorted_sizes, config))
+}
+
+/// Renders the counts of the buckets as a line of block characters, one per bucket, whose height is relative to the
+/// largest bucket. Empty buckets are shown as the lowest block, so that the number of buckets stays visible
+fn sparkline(buckets: &[HistogramBucket]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max_count = buckets
+        .iter()
+        .map(|bucket| bucket.count())
+        .max()
+        .unwrap_or_default();
+    buckets
+        .iter()
+        .map(|bucket| {
+            if max_count == 0 {
+                return BLOCKS[0];
+            }
+            let level = (bucket.count() as f64 / max_count as f64 * (BLOCKS.len() - 1) as f64)
+                .round() as usize;
+            BLOCKS[level]
+        })
+        .collect()
+}
+
+fn make_histogram(sorted_counts: &[usize], config: &HistogramConfig) -> Histogram {
+    let histogram = match config {
+        HistogramConfig::Linear(buckets) => lin_histogram(sorted_counts, *buckets),
+        HistogramConfig::Logarithmic(buckets) => log_histogram(sorted_counts, *buckets),
+        HistogramConfig::Custom(boundaries) => custom_histogram(sorted_counts, boundaries),
+        HistogramConfig::Quantile(buckets) => quantile_histogram(sorted_counts, *buckets),
+    };
+    if cfg!(debug_assertions) {
+        if let Err(error) = histogram.validate() {
+            panic!("Invalid histogram: {}", error);
+        }
+        let covered_end = histogram
+            .buckets()
+            .last()
+            .map_or(0, |bucket| bucket.range().end);
+        if let Some(&max_count) = sorted_counts.last() {
+            assert!(
+                max_count < covered_end,
+                "Histogram buckets end at {}, but the maximum value is {}",
+                covered_end,
+                max_count
+            );
+        }
+    }
+    histogram
+}
+
+/// Calculates how much two bounding boxes overlap, relative to the smaller box. Along each axis, the length of the
+/// intersection is divided by the smaller extent of both boxes, so flat boxes (e.g. of 2D tiles) are handled as well.
+/// Returns a value in [0;1], where 0 means that the boxes are disjoint or only touch
+fn bounds_overlap(a: &Bounds, b: &Bounds) -> f64 {
+    let axis_overlap = |min_a: f64, max_a: f64, min_b: f64, max_b: f64| {
+        let intersection = max_a.min(max_b) - min_a.max(min_b);
+        let smaller_extent = (max_a - min_a).min(max_b - min_b);
+        if intersection < 0.0 {
+            0.0
+        } else if smaller_extent <= 0.0 {
+            1.0
+        } else {
+            (intersection / smaller_extent).min(1.0)
+        }
+    };
+    axis_overlap(a.min.x, a.max.x, b.min.x, b.max.x)
+        * axis_overlap(a.min.y, a.max.y, b.min.y, b.max.y)
+        * axis_overlap(a.min.z, a.max.z, b.min.z, b.max.z)
+}
+
+/// Is `inner` completely contained in `outer`?
+fn bounds_contain(outer: &Bounds, inner: &Bounds) -> bool {
+    outer.min.x <= inner.min.x
+        && outer.min.y <= inner.min.y
+        && outer.min.z <= inner.min.z
+        && outer.max.x >= inner.max.x
+        && outer.max.y >= inner.max.y
+        && outer.max.z >= inner.max.z
+}
+
 /// Bucket within a Histogram containing the number of nodes whose point counts fall within `range`
 #[derive(Debug)]
 pub struct HistogramBucket {
@@ -93,10 +981,29 @@ pub struct HistogramBucket {
     range: Range<usize>,
 }
 
+/// Serializes the bucket as a flat object `{count, range_start, range_end}`, which is easier to consume from other
+/// tools than a nested range
+impl Serialize for HistogramBucket {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct FlatBucket {
+            count: usize,
+            range_start: usize,
+            range_end: usize,
+        }
+        FlatBucket {
+            count: self.count,
+            range_start: self.range.start,
+            range_end: self.range.end,
+        }
+        .serialize(serializer)
+    }
+}
+
 impl HistogramBucket {
     /// Creates a new `HistogramBucket` with the given data
     /// ```
-    /// # use crate::analyzer::*;
+    /// # use point_cloud_tiles_analyzer::analyzer::*;
     /// // Create a new bucket containing 1024 entries that contain at least 50 and less than 100 points
     /// let bucket = HistogramBucket::new(1024, 50..100);
     /// ```
@@ -115,17 +1022,31 @@ impl HistogramBucket {
     }
 }
 
+/// Formats the bucket with metric prefixes, e.g. `12.35 M in [1.000 M;2.000 M)`. The alternate flag (`{:#}`)
+/// formats all numbers as plain integers instead. The end of an open-ended bucket is shown as `∞`
 impl Display for HistogramBucket {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        let raw = fmt.alternate();
         write!(
             fmt,
             "{} in [{};{})",
-            self.count, self.range.start, self.range.end
+            format_number(self.count as f64, raw),
+            format_number(self.range.start as f64, raw),
+            format_bucket_end(self.range.end, raw)
         )
     }
 }
 
-#[derive(Debug)]
+/// Formats the end of a histogram bucket like `format_number`, or as `∞` for open-ended buckets
+pub(crate) fn format_bucket_end(end: usize, raw: bool) -> String {
+    if end == usize::MAX {
+        "∞".to_owned()
+    } else {
+        format_number(end as f64, raw)
+    }
+}
+
+#[derive(Debug, Serialize)]
 pub struct Histogram {
     buckets: Vec<HistogramBucket>,
     mean: f64,
@@ -140,250 +1061,3921 @@ impl Histogram {
             stddev,
         }
     }
+
+    /// Returns the buckets of the associated `Histogram`
+    pub fn buckets(&self) -> &[HistogramBucket] {
+        &self.buckets
+    }
+
+    /// Returns the mean of all entries of the associated `Histogram`
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Returns the bucket with the most entries together with its share of all entries, or `None` if the histogram
+    /// has no entries
+    pub fn dominant_bucket(&self) -> Option<(&HistogramBucket, f64)> {
+        let total: usize = self.buckets.iter().map(|bucket| bucket.count).sum();
+        if total == 0 {
+            return None;
+        }
+        let bucket = self.buckets.iter().max_by_key(|bucket| bucket.count)?;
+        Some((bucket, bucket.count as f64 / total as f64))
+    }
+
+    /// Checks that the buckets are contiguous, i.e. each bucket starts where the previous bucket ends, and that
+    /// they jointly cover the range from 0 to the end of the last bucket without gaps
+    /// ```
+    /// # use point_cloud_tiles_analyzer::analyzer::*;
+    /// let histogram = Histogram::new(
+    ///     vec![HistogramBucket::new(3, 0..10), HistogramBucket::new(1, 10..100)],
+    ///     20.0,
+    ///     5.0,
+    /// );
+    /// assert!(histogram.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        let first_bucket = match self.buckets.first() {
+            Some(first_bucket) => first_bucket,
+            None => return Ok(()),
+        };
+        if first_bucket.range.start != 0 {
+            return Err(anyhow!(
+                "First bucket starts at {} instead of 0",
+                first_bucket.range.start
+            ));
+        }
+        for (idx, bucket) in self.buckets.iter().enumerate() {
+            if bucket.range.start > bucket.range.end {
+                return Err(anyhow!(
+                    "Bucket {} ends at {} before it starts at {}",
+                    idx,
+                    bucket.range.end,
+                    bucket.range.start
+                ));
+            }
+        }
+        for (idx, pair) in self.buckets.windows(2).enumerate() {
+            if pair[0].range.end != pair[1].range.start {
+                return Err(anyhow!(
+                    "Bucket {} ends at {}, but bucket {} starts at {}",
+                    idx,
+                    pair[0].range.end,
+                    idx + 1,
+                    pair[1].range.start
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Display for Histogram {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
         writeln!(fmt, "Buckets:")?;
         for bucket in self.buckets.iter() {
-            writeln!(fmt, "{}", bucket)?;
+            if fmt.alternate() {
+                writeln!(fmt, "{:#}", bucket)?;
+            } else {
+                writeln!(fmt, "{}", bucket)?;
+            }
         }
         writeln!(fmt, "Mean: {:.2}", self.mean)?;
         writeln!(fmt, "σ: {:.2}", self.stddev)
     }
 }
 
-/// Result of the `Analyzer`
-pub enum AnalyzerResult {
-    /// The number of nodes in the dataset
-    NodeCount(usize),
-    /// A histogram of the point counts for each node
-    Histogram(Histogram),
+/// The bounds of the las crate can't be serialized, so they are serialized as `min` and `max` arrays instead
+fn serialize_bounds<S: Serializer>(
+    bounds: &Option<Bounds>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct SerializableBounds {
+        min: [f64; 3],
+        max: [f64; 3],
+    }
+
+    bounds
+        .map(|bounds| SerializableBounds {
+            min: [bounds.min.x, bounds.min.y, bounds.min.z],
+            max: [bounds.max.x, bounds.max.y, bounds.max.z],
+        })
+        .serialize(serializer)
 }
 
-impl Display for AnalyzerResult {
+/// Information about a single node of a tiled point cloud
+#[derive(Debug, Serialize)]
+pub struct NodeInfo {
+    /// Identifier of the node. For formats where one node equals one file, this is the file path
+    pub id: String,
+    /// The number of points in the node
+    pub point_count: u64,
+    /// The size of the node's point data in bytes
+    pub file_size: u64,
+    /// The bounding box of the node, if it is known
+    #[serde(serialize_with = "serialize_bounds")]
+    pub bounds: Option<Bounds>,
+    /// The size of a single point record in bytes, if it is known
+    pub point_record_length: Option<u16>,
+    /// The LAS point data record format, if it is known
+    pub point_format: Option<u8>,
+    /// The size of the LAS header in bytes, if it is known. Local files only report it with `check_integrity` or
+    /// `trust_data`, since it requires a copy of the raw header
+    pub header_size: Option<u16>,
+    /// The offset from the start of the file to the first point record in bytes, if it is known. Like `header_size`
+    pub point_data_offset: Option<u32>,
+}
+
+impl Display for NodeInfo {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        match self {
-            AnalyzerResult::Histogram(histogram) => write!(fmt, "{}", histogram),
-            AnalyzerResult::NodeCount(node_count) => {
-                writeln!(fmt, "Number of nodes: {}", node_count)
-            }
+        write!(
+            fmt,
+            "{}: {} points, {} bytes",
+            self.id, self.point_count, self.file_size
+        )?;
+        if let Some(bounds) = &self.bounds {
+            write!(
+                fmt,
+                ", bounds [{};{};{}]-[{};{};{}]",
+                bounds.min.x, bounds.min.y, bounds.min.z, bounds.max.x, bounds.max.y, bounds.max.z
+            )?;
         }
+        Ok(())
     }
 }
 
-/// Trait for analyzing a point cloud
-pub trait Analyzer {
-    /// Runs the analyzer, returning the results of the analysis on success
-    fn run(&self) -> Result<Vec<AnalyzerResult>>;
+/// Expected and estimated point spacing of a single octree level
+#[derive(Debug, Serialize)]
+pub struct GsdLevel {
+    pub level: u8,
+    /// The spacing that is expected from the root spacing, i.e. `spacing / 2^level`
+    pub expected_spacing: f64,
+    /// The spacing estimated from the number of points per node and the size of the nodes at this level
+    pub estimated_spacing: f64,
+    /// Does the estimated spacing deviate from the expected spacing by more than the tolerance?
+    pub deviates: bool,
 }
 
-/// Analyzer for tiling formats where one node equals one file
-pub struct MultiFileAnalyzer {
-    files: Vec<PathBuf>,
-    count_nodes: bool,
-    histogram_config: Option<HistogramConfig>,
+/// Files with byte-identical content
+#[derive(Debug, Serialize)]
+pub struct DuplicateGroup {
+    /// Size of each of the files in bytes
+    pub file_size: u64,
+    /// Paths of the files, sorted
+    pub files: Vec<String>,
 }
 
-impl MultiFileAnalyzer {
-    /// Creates a new `MultiFileAnalyzer` for the data in the given directory
-    pub fn new<P: AsRef<Path>>(
-        root_dir: P,
-        count_nodes: bool,
-        histogram_config: Option<HistogramConfig>,
-    ) -> Result<Self> {
-        if !root_dir.as_ref().exists() {
-            return Err(anyhow!(
-                "root directory {} does not exist!",
-                root_dir.as_ref().display()
-            ));
-        }
+impl DuplicateGroup {
+    /// Number of bytes that deleting all but one of the files would free
+    pub fn wasted_bytes(&self) -> u64 {
+        self.file_size * (self.files.len() as u64).saturating_sub(1)
+    }
+}
 
-        let files = WalkDir::new(root_dir)
-            .into_iter()
-            .filter_map(|entry| entry.ok())
-            .filter(|entry| Self::is_supported_format(entry.path()))
-            .map(|entry| entry.into_path())
-            .collect::<Vec<_>>();
+/// Summary of the colors of every `stride`-th point of each file of a dataset
+#[derive(Debug, Default, Serialize)]
+pub struct ColorSample {
+    pub stride: u64,
+    /// Number of files whose point format has colors
+    pub files_with_color: usize,
+    /// Number of files whose point format has no colors. No points of these files are read
+    pub files_without_color: usize,
+    pub sampled_points: u64,
+    /// Number of sampled points whose color is not black, i.e. not (0, 0, 0)
+    pub colored_points: u64,
+    /// Minimum red, green and blue values of the sampled points, or `None` if no points were sampled
+    pub min: Option<[u16; 3]>,
+    /// Maximum red, green and blue values of the sampled points, or `None` if no points were sampled
+    pub max: Option<[u16; 3]>,
+}
 
-        Ok(MultiFileAnalyzer {
-            files,
-            count_nodes,
-            histogram_config,
-        })
+impl ColorSample {
+    fn insert(&mut self, rgb: [u16; 3]) {
+        self.sampled_points += 1;
+        if rgb != [0, 0, 0] {
+            self.colored_points += 1;
+        }
+        let min = self.min.get_or_insert(rgb);
+        let max = self.max.get_or_insert(rgb);
+        for channel in 0..3 {
+            min[channel] = min[channel].min(rgb[channel]);
+            max[channel] = max[channel].max(rgb[channel]);
+        }
     }
 
-    fn is_supported_format<P: AsRef<Path>>(path: P) -> bool {
-        match path.as_ref().extension() {
-            Some(extension) => extension == "las" || extension == "laz",
-            None => false,
+    fn merge(self, other: ColorSample) -> ColorSample {
+        let combine =
+            |a: Option<[u16; 3]>, b: Option<[u16; 3]>, f: fn(u16, u16) -> u16| match (a, b) {
+                (Some(a), Some(b)) => Some([f(a[0], b[0]), f(a[1], b[1]), f(a[2], b[2])]),
+                (a, b) => a.or(b),
+            };
+        ColorSample {
+            stride: self.stride,
+            files_with_color: self.files_with_color + other.files_with_color,
+            files_without_color: self.files_without_color + other.files_without_color,
+            sampled_points: self.sampled_points + other.sampled_points,
+            colored_points: self.colored_points + other.colored_points,
+            min: combine(self.min, other.min, u16::min),
+            max: combine(self.max, other.max, u16::max),
         }
     }
+}
 
-    fn calculate_histogram(&self) -> Result<AnalyzerResult> {
-        let chunk_size = 128;
-        let mut progress_tracker = Arc::new(Mutex::new(ProgressTracker::new(
-            (self.files.len() - 1) as f64,
-            ProgressUpdateCondition::OnProgressChanged(1000.0),
-        )));
+/// Number of nodes and points of a single octree level
+#[derive(Debug, Serialize)]
+pub struct LevelRow {
+    pub level: u8,
+    pub node_count: usize,
+    pub point_count: u64,
+    pub mean_points_per_node: f64,
+    /// Fraction of all points of the dataset that are stored in this level, in [0;1]
+    pub point_share: f64,
+}
 
-        let num_points_per_node_nested = self
-            .files
-            .par_iter()
-            .chunks(chunk_size)
-            .map(move |files| -> Result<Vec<usize>> {
-                let num_points = files
-                    .iter()
-                    .map(|&file| -> Result<usize> {
-                        let mut reader = Reader::from_path(file)?;
-                        let header = reader.header();
-                        Ok(header.number_of_points() as usize)
-                    })
-                    .collect::<Result<Vec<_>, _>>()?;
+/// Result of the `Analyzer`
+#[derive(Debug, Serialize)]
+pub enum AnalyzerResult {
+    /// The number of nodes in the dataset
+    NodeCount(usize),
+    /// A histogram of the point counts for each node
+    Histogram(Histogram),
+    /// Estimated quantiles of the points per node, as pairs of quantile in [0;1] and point count. Each estimate is
+    /// within `relative_accuracy` of the exact point count
+    ApproxQuantiles {
+        relative_accuracy: f64,
+        quantiles: Vec<(f64, f64)>,
+    },
+    /// Skewness and excess kurtosis of the points per node
+    Moments { skewness: f64, kurtosis: f64 },
+    /// Number of points of all nodes
+    TotalPoints(u64),
+    /// Pairs of percentile in [0;100] and the point count at this percentile, interpolated between the nodes
+    Percentiles(Vec<(f64, usize)>),
+    /// Mean and standard deviation of the points per node, over `count` nodes
+    Statistics {
+        mean: f64,
+        std_dev: f64,
+        count: usize,
+    },
+    /// The histogram of the points per node as a single line of block characters, one per bucket
+    Sparkline(String),
+    /// Histogram of the sizes of the nodes in bytes
+    SizeHistogram(Histogram),
+    /// The Gini coefficient of the points per node. 0 means that all nodes contain the same number of points,
+    /// values close to 1 mean that almost all points are in a single node
+    Gini(f64),
+    /// The number of points in leaf nodes (nodes without children) and in inner nodes
+    LeafPointRatio { leaf_points: u64, inner_points: u64 },
+    /// Information about every node in the dataset
+    Nodes(Vec<NodeInfo>),
+    /// Nodes whose point format or point record length differs from the one used by most nodes
+    PointFormatMismatches {
+        modal_point_format: u8,
+        modal_record_length: u16,
+        /// Identifier, point format and point record length of each deviating node
+        mismatches: Vec<(String, u8, u16)>,
+    },
+    /// Nodes whose byte size differs from the size that is expected from the attribute schema
+    AttributeMismatches {
+        /// Size of a single point in bytes according to the attribute schema
+        bytes_per_point: u64,
+        /// Name, actual byte size and expected byte size of each mismatching node
+        mismatches: Vec<(String, u64, u64)>,
+    },
+    /// Number of nodes found by scanning all records of the hierarchy vs. by walking the hierarchy from the root node
+    CountCrossCheck { flat: usize, walked: usize },
+    /// Layout of the node records that was inferred from hierarchy.bin, see `AnalysisOptions::infer_record_size`
+    InferredRecordLayout {
+        record_size: usize,
+        point_count_offset: usize,
+        point_count_bytes: usize,
+        /// Record size of each rejected candidate layout and the reason why it was rejected
+        rejected: Vec<(usize, String)>,
+    },
+    /// Number of nodes, number of points, mean points per node and share of all points for each octree level
+    LevelSummary(Vec<LevelRow>),
+    /// Cumulative number of points of all levels up to and including each octree level, i.e. the number of points
+    /// that are loaded when rendering the octree down to this level
+    LodCurve(Vec<(u8, u64)>),
+    /// Distribution of the header sizes and point data offsets of all files, most common first
+    HeaderLayouts {
+        /// Header size, point data offset and number of files for each combination
+        distribution: Vec<(u16, u32, usize)>,
+        /// Identifier, header size and point data offset of each file that deviates from the most common combination
+        outliers: Vec<(String, u16, u32)>,
+    },
+    /// Point spacing of each octree level compared to the spacing that is expected from the root spacing
+    GsdConsistency {
+        spacing: f64,
+        tolerance: f64,
+        levels: Vec<GsdLevel>,
+    },
+    /// Oldest and newest modification time of all node files, in seconds since the Unix epoch
+    MtimeStats {
+        /// Number of files whose modification time is known
+        files: usize,
+        oldest: u64,
+        newest: u64,
+        /// If the modification times are more than `threshold` seconds apart, only some of the files were probably
+        /// rewritten after the dataset was created
+        threshold: u64,
+        partial_rewrite: bool,
+    },
+    /// COPC files whose LAS header declares a different number of points than the sum of the point counts of their
+    /// COPC hierarchy
+    CopcPointCountMismatches {
+        /// Number of files that are COPC files
+        copc_files: usize,
+        /// Path, point count of the header and point count of the hierarchy of each mismatching file
+        mismatches: Vec<(String, u64, u64)>,
+    },
+    /// Groups of node files with byte-identical content, the groups that waste the most bytes first
+    DuplicateGroups(Vec<DuplicateGroup>),
+    /// Size of all files of the dataset on disk in bytes, split into the node files with the point data and all other
+    /// files, such as the hierarchy and metadata
+    DiskUsage {
+        data_bytes: u64,
+        metadata_bytes: u64,
+    },
+    /// Estimated size of the point data in uncompressed form
+    UncompressedEstimate {
+        total_bytes: u64,
+        mean_bytes_per_node: f64,
+        max_bytes_per_node: u64,
+    },
+    /// Number of nodes that contain more than `threshold` points. Identifiers of these nodes are only known for
+    /// formats where one node equals one file, otherwise `node_ids` is empty
+    OversizedNodes {
+        threshold: u64,
+        count: usize,
+        node_ids: Vec<String>,
+    },
+    /// How much the nodes with more than `target` points would have to be downsampled to contain at most `target`
+    /// points. The reduction factor of a node is its point count divided by `target`
+    DownsampleRecommendation {
+        target: u64,
+        /// Number of nodes with more than `target` points
+        count: usize,
+        /// Share of the nodes with more than `target` points among all nodes
+        share: f64,
+        mean_reduction: f64,
+        max_reduction: f64,
+    },
+    /// Mean number of children of all inner nodes. Values close to 8 indicate a dense subdivision, values well below
+    /// 8 a sparse octree
+    MeanFanout(f64),
+    /// Number of distinct octree levels that contain nodes
+    LevelCount(u8),
+    /// Colors of a sample of the points, read with `color_sample`
+    SampledColors(ColorSample),
+    /// Histogram of the points per cell of the quadtrees of the `.lax` spatial indexes next to the LAS/LAZ files
+    LaxCells {
+        /// Number of files with a `.lax` index
+        indexed_files: usize,
+        /// Number of files without a `.lax` index, which are not included in the histogram
+        unindexed_files: usize,
+        /// Number of non-empty cells of all indexes
+        cells: usize,
+        histogram: Histogram,
+    },
+    /// Nodes that contain more points than their parent node
+    HierarchyAnomalies {
+        /// Name and point count of each node together with the point count of its parent
+        anomalies: Vec<(String, u64, u64)>,
+    },
+    /// Nodes that are referenced by the child mask of their parent or by a proxy node, but are missing from the
+    /// hierarchy, which indicates a truncated or corrupt hierarchy
+    MissingChildren {
+        /// Name of each missing node, e.g. 'r0426' for child 6 of node 'r042'
+        missing: Vec<String>,
+    },
+    /// Pairs of nodes whose bounding boxes overlap although neither node is an ancestor of the other
+    OverlappingNodes {
+        count: usize,
+        /// Identifiers of both nodes and their overlap relative to the smaller node, for the first few pairs
+        examples: Vec<(String, String, f64)>,
+    },
+    /// EPSG code of the coordinate reference system of the dataset
+    Epsg(u32),
+    /// A result together with a note on how it was computed
+    Explained {
+        result: Box<AnalyzerResult>,
+        explanation: String,
+    },
+    /// Files that were not analyzed because they are larger than `max_file_size` bytes
+    SkippedFiles {
+        max_file_size: u64,
+        /// Path and size in bytes of each skipped file
+        files: Vec<(String, u64)>,
+    },
+    /// Nodes with both a `.las` and a `.laz` file that were counted only once, using the `.laz` file
+    CollapsedExtensionPairs {
+        pairs: usize,
+        /// The `.las` files that were not analyzed, for the first few pairs
+        examples: Vec<String>,
+    },
+    /// Files whose header declares zero points, but that contain point data. Their point count was computed from the
+    /// file size and the point record length
+    RecoveredPointCounts {
+        /// Path and computed point count of each affected file
+        files: Vec<(String, u64)>,
+    },
+}
 
-                let mut progress = progress_tracker.lock().unwrap();
-                progress.inc_progress(chunk_size as f64);
+impl AnalyzerResult {
+    /// Returns a short human-readable title for the kind of the result
+    pub fn title(&self) -> &'static str {
+        match self {
+            AnalyzerResult::NodeCount(_) => "Node count",
+            AnalyzerResult::Histogram(_) => "Histogram",
+            AnalyzerResult::SizeHistogram(_) => "Node size histogram",
+            AnalyzerResult::Sparkline(_) => "Histogram sparkline",
+            AnalyzerResult::Gini(_) => "Gini coefficient",
+            AnalyzerResult::Moments { .. } => "Skewness and kurtosis",
+            AnalyzerResult::Statistics { .. } => "Mean and standard deviation",
+            AnalyzerResult::Percentiles(_) => "Percentiles",
+            AnalyzerResult::TotalPoints(_) => "Total points",
+            AnalyzerResult::ApproxQuantiles { .. } => "Approximate quantiles",
+            AnalyzerResult::LeafPointRatio { .. } => "Leaf point ratio",
+            AnalyzerResult::Nodes(_) => "Nodes",
+            AnalyzerResult::PointFormatMismatches { .. } => "Point format mismatches",
+            AnalyzerResult::HeaderLayouts { .. } => "Header layouts",
+            AnalyzerResult::GsdConsistency { .. } => "Point spacing per level",
+            AnalyzerResult::UncompressedEstimate { .. } => "Uncompressed size estimate",
+            AnalyzerResult::DiskUsage { .. } => "Disk usage",
+            AnalyzerResult::MtimeStats { .. } => "Modification times",
+            AnalyzerResult::DuplicateGroups(_) => "Duplicate files",
+            AnalyzerResult::CopcPointCountMismatches { .. } => "COPC point count mismatches",
+            AnalyzerResult::OversizedNodes { .. } => "Oversized nodes",
+            AnalyzerResult::DownsampleRecommendation { .. } => "Downsampling recommendation",
+            AnalyzerResult::MeanFanout(_) => "Mean fan-out",
+            AnalyzerResult::LevelCount(_) => "Level count",
+            AnalyzerResult::LevelSummary(_) => "Level summary",
+            AnalyzerResult::LodCurve(_) => "LOD curve",
+            AnalyzerResult::SampledColors(_) => "Sampled colors",
+            AnalyzerResult::LaxCells { .. } => "Points per .lax cell",
+            AnalyzerResult::CountCrossCheck { .. } => "Node count cross-check",
+            AnalyzerResult::InferredRecordLayout { .. } => "Inferred record layout",
+            AnalyzerResult::AttributeMismatches { .. } => "Attribute mismatches",
+            AnalyzerResult::HierarchyAnomalies { .. } => "Hierarchy anomalies",
+            AnalyzerResult::MissingChildren { .. } => "Missing children",
+            AnalyzerResult::OverlappingNodes { .. } => "Overlapping nodes",
+            AnalyzerResult::Epsg(_) => "EPSG code",
+            AnalyzerResult::SkippedFiles { .. } => "Skipped files",
+            AnalyzerResult::CollapsedExtensionPairs { .. } => "Collapsed .las/.laz pairs",
+            AnalyzerResult::RecoveredPointCounts { .. } => "Recovered point counts",
+            AnalyzerResult::Explained { result, .. } => result.title(),
+        }
+    }
 
-                Ok(num_points)
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+    /// Returns the result without the note on how it was computed
+    pub fn inner(&self) -> &AnalyzerResult {
+        match self {
+            AnalyzerResult::Explained { result, .. } => result.inner(),
+            _ => self,
+        }
+    }
+
+    /// Returns the position of the result in the canonical order of all results: The node count first, then totals,
+    /// statistics, histograms and warnings, and the per-node information last. Sorting by this key makes the output
+    /// independent of the order in which the analyses were run
+    pub fn order_key(&self) -> u8 {
+        match self {
+            AnalyzerResult::NodeCount(_) => 0,
+            AnalyzerResult::Epsg(_) => 1,
+            AnalyzerResult::TotalPoints(_) => 1,
+            AnalyzerResult::LeafPointRatio { .. } => 2,
+            AnalyzerResult::UncompressedEstimate { .. } => 2,
+            AnalyzerResult::DiskUsage { .. } => 2,
+            AnalyzerResult::MtimeStats { .. } => 3,
+            AnalyzerResult::Gini(_) => 3,
+            AnalyzerResult::Moments { .. } => 3,
+            AnalyzerResult::Statistics { .. } => 3,
+            AnalyzerResult::Percentiles(_) => 3,
+            AnalyzerResult::ApproxQuantiles { .. } => 3,
+            AnalyzerResult::MeanFanout(_) => 3,
+            AnalyzerResult::LevelCount(_) => 3,
+            AnalyzerResult::GsdConsistency { .. } => 3,
+            AnalyzerResult::LevelSummary(_) => 3,
+            AnalyzerResult::LodCurve(_) => 3,
+            AnalyzerResult::SampledColors(_) => 3,
+            AnalyzerResult::Histogram(_) => 4,
+            AnalyzerResult::SizeHistogram(_) => 4,
+            AnalyzerResult::LaxCells { .. } => 4,
+            AnalyzerResult::Sparkline(_) => 4,
+            AnalyzerResult::OversizedNodes { .. } => 5,
+            AnalyzerResult::DownsampleRecommendation { .. } => 3,
+            AnalyzerResult::PointFormatMismatches { .. } => 5,
+            AnalyzerResult::HeaderLayouts { .. } => 5,
+            AnalyzerResult::OverlappingNodes { .. } => 5,
+            AnalyzerResult::DuplicateGroups(_) => 5,
+            AnalyzerResult::CopcPointCountMismatches { .. } => 5,
+            AnalyzerResult::HierarchyAnomalies { .. } => 5,
+            AnalyzerResult::MissingChildren { .. } => 5,
+            AnalyzerResult::CountCrossCheck { .. } => 5,
+            AnalyzerResult::InferredRecordLayout { .. } => 5,
+            AnalyzerResult::AttributeMismatches { .. } => 5,
+            AnalyzerResult::SkippedFiles { .. } => 5,
+            AnalyzerResult::CollapsedExtensionPairs { .. } => 5,
+            AnalyzerResult::RecoveredPointCounts { .. } => 5,
+            AnalyzerResult::Nodes(_) => 6,
+            AnalyzerResult::Explained { result, .. } => result.order_key(),
+        }
+    }
+}
+
+/// Formats the result in human-readable form. The alternate flag (`{:#}`) formats all numbers as plain numbers
+/// instead of using metric prefixes
+impl Display for AnalyzerResult {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        let raw = fmt.alternate();
+        match self {
+            AnalyzerResult::Explained {
+                result,
+                explanation,
+            } => {
+                if raw {
+                    write!(fmt, "{:#}", result)?;
+                } else {
+                    write!(fmt, "{}", result)?;
+                }
+                writeln!(fmt, "  [{}]", explanation)
+            }
+            AnalyzerResult::Histogram(histogram) => {
+                if raw {
+                    write!(fmt, "{:#}", histogram)
+                } else {
+                    write!(fmt, "{}", histogram)
+                }
+            }
+            AnalyzerResult::Sparkline(sparkline) => writeln!(fmt, "Histogram: {}", sparkline),
+            AnalyzerResult::SizeHistogram(histogram) => {
+                writeln!(fmt, "Node sizes in bytes:")?;
+                if raw {
+                    write!(fmt, "{:#}", histogram)
+                } else {
+                    write!(fmt, "{}", histogram)
+                }
+            }
+            AnalyzerResult::NodeCount(node_count) => {
+                writeln!(fmt, "Number of nodes: {}", node_count)
+            }
+            AnalyzerResult::Gini(gini) => {
+                writeln!(fmt, "Gini coefficient of points per node: {:.4}", gini)
+            }
+            AnalyzerResult::TotalPoints(total_points) => writeln!(
+                fmt,
+                "Total number of points: {}",
+                format_number(*total_points as f64, raw)
+            ),
+            AnalyzerResult::Percentiles(percentiles) => {
+                writeln!(fmt, "Percentiles of points per node:")?;
+                for (p, point_count) in percentiles.iter() {
+                    writeln!(fmt, "P{}: {}", p, format_number(*point_count as f64, raw))?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::ApproxQuantiles {
+                relative_accuracy,
+                quantiles,
+            } => {
+                writeln!(
+                    fmt,
+                    "Approximate quantiles of points per node (within {:.2}%):",
+                    100.0 * relative_accuracy
+                )?;
+                for (q, point_count) in quantiles.iter() {
+                    writeln!(
+                        fmt,
+                        "P{}: ~{}",
+                        100.0 * q,
+                        format_number(point_count.round(), raw)
+                    )?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::Moments { skewness, kurtosis } => {
+                writeln!(fmt, "Skewness of points per node: {:.4}", skewness)?;
+                writeln!(fmt, "Excess kurtosis of points per node: {:.4}", kurtosis)
+            }
+            AnalyzerResult::Statistics {
+                mean,
+                std_dev,
+                count,
+            } => writeln!(
+                fmt,
+                "Mean points/node: {}, Std dev: {} (over {} nodes)",
+                format_number(*mean, raw),
+                format_number(*std_dev, raw),
+                format_number(*count as f64, raw)
+            ),
+            AnalyzerResult::LeafPointRatio {
+                leaf_points,
+                inner_points,
+            } => {
+                let total_points = leaf_points + inner_points;
+                let leaf_percentage = if total_points == 0 {
+                    0.0
+                } else {
+                    100.0 * *leaf_points as f64 / total_points as f64
+                };
+                writeln!(
+                    fmt,
+                    "Points in leaf nodes: {} ({:.2}%)",
+                    leaf_points, leaf_percentage
+                )?;
+                writeln!(
+                    fmt,
+                    "Points in inner nodes: {} ({:.2}%)",
+                    inner_points,
+                    100.0 - leaf_percentage
+                )
+            }
+            AnalyzerResult::Nodes(nodes) => {
+                writeln!(fmt, "Nodes:")?;
+                for node in nodes.iter() {
+                    writeln!(fmt, "{}", node)?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::PointFormatMismatches {
+                modal_point_format,
+                modal_record_length,
+                mismatches,
+            } => {
+                writeln!(
+                    fmt,
+                    "Nodes deviating from point format {} with {} bytes per point: {}",
+                    modal_point_format,
+                    modal_record_length,
+                    mismatches.len()
+                )?;
+                for (id, point_format, record_length) in mismatches.iter() {
+                    writeln!(
+                        fmt,
+                        "{}: point format {} with {} bytes per point",
+                        id, point_format, record_length
+                    )?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::HeaderLayouts {
+                distribution,
+                outliers,
+            } => {
+                writeln!(fmt, "Header sizes and point data offsets:")?;
+                for (header_size, point_data_offset, count) in distribution.iter() {
+                    writeln!(
+                        fmt,
+                        "header size {} bytes, point data offset {} bytes: {} files",
+                        header_size, point_data_offset, count
+                    )?;
+                }
+                writeln!(
+                    fmt,
+                    "Files deviating from the most common layout: {}",
+                    outliers.len()
+                )?;
+                for (id, header_size, point_data_offset) in outliers.iter() {
+                    writeln!(
+                        fmt,
+                        "{}: header size {} bytes, point data offset {} bytes",
+                        id, header_size, point_data_offset
+                    )?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::GsdConsistency {
+                spacing,
+                tolerance,
+                levels,
+            } => {
+                writeln!(
+                    fmt,
+                    "Point spacing per level (root spacing {}, tolerance {:.0}%):",
+                    spacing,
+                    100.0 * tolerance
+                )?;
+                for level in levels.iter() {
+                    writeln!(
+                        fmt,
+                        "level {}: expected {:.4}, estimated {:.4}{}",
+                        level.level,
+                        level.expected_spacing,
+                        level.estimated_spacing,
+                        if level.deviates { " (deviates)" } else { "" }
+                    )?;
+                }
+                let deviating_levels = levels.iter().filter(|level| level.deviates).count();
+                writeln!(fmt, "Deviating levels: {}", deviating_levels)
+            }
+            AnalyzerResult::DiskUsage {
+                data_bytes,
+                metadata_bytes,
+            } => {
+                writeln!(
+                    fmt,
+                    "Disk usage: {}B",
+                    format_number((data_bytes + metadata_bytes) as f64, raw)
+                )?;
+                writeln!(
+                    fmt,
+                    "Point data: {}B",
+                    format_number(*data_bytes as f64, raw)
+                )?;
+                writeln!(
+                    fmt,
+                    "Hierarchy and metadata: {}B",
+                    format_number(*metadata_bytes as f64, raw)
+                )
+            }
+            AnalyzerResult::MtimeStats {
+                files,
+                oldest,
+                newest,
+                threshold,
+                partial_rewrite,
+            } => {
+                let format_time = |seconds: u64| {
+                    humantime::format_rfc3339_seconds(
+                        SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+                    )
+                };
+                writeln!(
+                    fmt,
+                    "Modification times of {} files: oldest {}, newest {}",
+                    files,
+                    format_time(*oldest),
+                    format_time(*newest)
+                )?;
+                writeln!(
+                    fmt,
+                    "Span: {}",
+                    humantime::format_duration(Duration::from_secs(newest - oldest))
+                )?;
+                if *partial_rewrite {
+                    writeln!(
+                        fmt,
+                        "Warning: The modification times span more than {}, some files were probably rewritten after the dataset was created",
+                        humantime::format_duration(Duration::from_secs(*threshold))
+                    )?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::UncompressedEstimate {
+                total_bytes,
+                mean_bytes_per_node,
+                max_bytes_per_node,
+            } => {
+                writeln!(
+                    fmt,
+                    "Estimated uncompressed size: {}B",
+                    format_number(*total_bytes as f64, raw)
+                )?;
+                writeln!(
+                    fmt,
+                    "Mean uncompressed size per node: {}B",
+                    format_number(*mean_bytes_per_node, raw)
+                )?;
+                writeln!(
+                    fmt,
+                    "Max uncompressed size per node: {}B",
+                    format_number(*max_bytes_per_node as f64, raw)
+                )
+            }
+            AnalyzerResult::OversizedNodes {
+                threshold,
+                count,
+                node_ids,
+            } => {
+                writeln!(
+                    fmt,
+                    "Nodes with more than {} points: {}",
+                    format_number(*threshold as f64, raw),
+                    count
+                )?;
+                for node_id in node_ids.iter() {
+                    writeln!(fmt, "{}", node_id)?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::DownsampleRecommendation {
+                target,
+                count,
+                share,
+                mean_reduction,
+                max_reduction,
+            } => {
+                writeln!(
+                    fmt,
+                    "Nodes that need downsampling to {} points: {} ({:.2}%)",
+                    format_number(*target as f64, raw),
+                    count,
+                    100.0 * share
+                )?;
+                writeln!(fmt, "Mean reduction factor: {:.2}x", mean_reduction)?;
+                writeln!(fmt, "Max reduction factor: {:.2}x", max_reduction)
+            }
+            AnalyzerResult::AttributeMismatches {
+                bytes_per_point,
+                mismatches,
+            } => {
+                writeln!(
+                    fmt,
+                    "Nodes whose size doesn't match {} bytes per point: {}",
+                    bytes_per_point,
+                    mismatches.len()
+                )?;
+                for (name, byte_size, expected_size) in mismatches.iter() {
+                    writeln!(
+                        fmt,
+                        "{}: {}B instead of {}B",
+                        name,
+                        format_number(*byte_size as f64, raw),
+                        format_number(*expected_size as f64, raw)
+                    )?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::InferredRecordLayout {
+                record_size,
+                point_count_offset,
+                point_count_bytes,
+                rejected,
+            } => {
+                writeln!(
+                    fmt,
+                    "Inferred node record size: {} bytes, with a {} byte point count at offset {}",
+                    record_size, point_count_bytes, point_count_offset
+                )?;
+                for (rejected_size, reason) in rejected.iter() {
+                    writeln!(fmt, "Rejected {} bytes: {}", rejected_size, reason)?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::CountCrossCheck { flat, walked } => {
+                writeln!(fmt, "Nodes found by scanning the hierarchy: {}", flat)?;
+                writeln!(fmt, "Nodes found by walking the hierarchy: {}", walked)?;
+                if flat == walked {
+                    writeln!(fmt, "Node counts agree")
+                } else {
+                    writeln!(
+                        fmt,
+                        "Warning: Node counts differ by {}, the hierarchy contains nodes that are not reachable from the root node or reachable records that are not valid",
+                        (*flat as i64 - *walked as i64).abs()
+                    )
+                }
+            }
+            AnalyzerResult::LevelSummary(levels) => {
+                let node_counts = levels.iter().map(|row| row.node_count).collect::<Vec<_>>();
+                let node_shares = rounded_percentages(&node_counts, 1);
+                writeln!(
+                    fmt,
+                    "{:>5} {:>18} {:>12} {:>16} {:>8}",
+                    "level", "nodes", "points", "points/node", "share"
+                )?;
+                for (row, node_share) in levels.iter().zip(node_shares) {
+                    writeln!(
+                        fmt,
+                        "{:>5} {:>18} {:>12} {:>16} {:>7.2}%",
+                        row.level,
+                        format!("{} ({:.1}%)", row.node_count, node_share),
+                        format_number(row.point_count as f64, raw),
+                        format_number(row.mean_points_per_node.round(), raw),
+                        100.0 * row.point_share
+                    )?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::LodCurve(levels) => {
+                let total_points = levels.last().map_or(0, |(_, points)| *points);
+                writeln!(fmt, "{:>5} {:>16} {:>8}", "level", "points up to", "share")?;
+                for (level, points) in levels.iter() {
+                    let share = if total_points > 0 {
+                        *points as f64 / total_points as f64
+                    } else {
+                        0.0
+                    };
+                    writeln!(
+                        fmt,
+                        "{:>5} {:>16} {:>7.2}%",
+                        level,
+                        format_number(*points as f64, raw),
+                        100.0 * share
+                    )?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::SampledColors(sample) => {
+                writeln!(
+                    fmt,
+                    "Colors (sampled every {}. point): {} of {} sampled points are not black",
+                    sample.stride,
+                    format_number(sample.colored_points as f64, raw),
+                    format_number(sample.sampled_points as f64, raw)
+                )?;
+                writeln!(
+                    fmt,
+                    "Files with colors: {}, without colors: {}",
+                    sample.files_with_color, sample.files_without_color
+                )?;
+                if let (Some(min), Some(max)) = (sample.min, sample.max) {
+                    writeln!(
+                        fmt,
+                        "Color range: R {}-{}, G {}-{}, B {}-{}",
+                        min[0], max[0], min[1], max[1], min[2], max[2]
+                    )?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::LaxCells {
+                indexed_files,
+                unindexed_files,
+                cells,
+                histogram,
+            } => {
+                writeln!(
+                    fmt,
+                    "Points per spatial cell of the .lax indexes ({} cells in {} indexed files, {} files without index):",
+                    format_number(*cells as f64, raw),
+                    indexed_files,
+                    unindexed_files
+                )?;
+                if raw {
+                    write!(fmt, "{:#}", histogram)
+                } else {
+                    write!(fmt, "{}", histogram)
+                }
+            }
+            AnalyzerResult::LevelCount(level_count) => {
+                writeln!(fmt, "Populated octree levels: {}", level_count)
+            }
+            AnalyzerResult::MeanFanout(mean_fanout) => {
+                writeln!(fmt, "Mean children per inner node: {:.2}", mean_fanout)
+            }
+            AnalyzerResult::MissingChildren { missing } => {
+                writeln!(
+                    fmt,
+                    "Nodes referenced by their parent but missing from the hierarchy: {}",
+                    missing.len()
+                )?;
+                for name in missing.iter() {
+                    let (parent_name, child_index) = name.split_at(name.len() - 1);
+                    if parent_name.is_empty() {
+                        writeln!(fmt, "{}", name)?;
+                    } else {
+                        writeln!(fmt, "{} (child {} of {})", name, child_index, parent_name)?;
+                    }
+                }
+                Ok(())
+            }
+            AnalyzerResult::HierarchyAnomalies { anomalies } => {
+                writeln!(
+                    fmt,
+                    "Nodes with more points than their parent: {}",
+                    anomalies.len()
+                )?;
+                for (name, points, parent_points) in anomalies.iter() {
+                    writeln!(
+                        fmt,
+                        "{}: {} points, parent has {} points",
+                        name,
+                        format_number(*points as f64, raw),
+                        format_number(*parent_points as f64, raw)
+                    )?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::OverlappingNodes { count, examples } => {
+                writeln!(fmt, "Pairs of overlapping nodes: {}", count)?;
+                for (id, other_id, overlap) in examples.iter() {
+                    writeln!(
+                        fmt,
+                        "{} and {}: {:.2}% overlap",
+                        id,
+                        other_id,
+                        100.0 * overlap
+                    )?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::CopcPointCountMismatches {
+                copc_files,
+                mismatches,
+            } => {
+                writeln!(
+                    fmt,
+                    "COPC files whose header and hierarchy point counts disagree: {} of {}",
+                    mismatches.len(),
+                    copc_files
+                )?;
+                for (file, header, hierarchy) in mismatches.iter() {
+                    write!(
+                        fmt,
+                        "{}: header declares {} points, hierarchy contains {}",
+                        file,
+                        format_number(*header as f64, raw),
+                        format_number(*hierarchy as f64, raw)
+                    )?;
+                    if *header > 0 {
+                        write!(
+                            fmt,
+                            " ({:.2}% of the header)",
+                            100.0 * *hierarchy as f64 / *header as f64
+                        )?;
+                    }
+                    writeln!(fmt)?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::DuplicateGroups(groups) => {
+                writeln!(
+                    fmt,
+                    "Groups of duplicate files: {}, wasting {}B",
+                    groups.len(),
+                    format_number(
+                        groups.iter().map(DuplicateGroup::wasted_bytes).sum::<u64>() as f64,
+                        raw
+                    )
+                )?;
+                for group in groups.iter() {
+                    writeln!(
+                        fmt,
+                        "{} files of {}B: {}",
+                        group.files.len(),
+                        format_number(group.file_size as f64, raw),
+                        group.files.join(", ")
+                    )?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::Epsg(epsg) => writeln!(fmt, "EPSG code: {}", epsg),
+            AnalyzerResult::SkippedFiles {
+                max_file_size,
+                files,
+            } => {
+                writeln!(
+                    fmt,
+                    "Files skipped for being larger than {}B: {}",
+                    format_number(*max_file_size as f64, raw),
+                    files.len()
+                )?;
+                for (file, file_size) in files.iter() {
+                    writeln!(fmt, "{}: {}B", file, format_number(*file_size as f64, raw))?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::CollapsedExtensionPairs { pairs, examples } => {
+                writeln!(
+                    fmt,
+                    "Nodes with both a .las and a .laz file, counted once: {}",
+                    format_number(*pairs as f64, raw)
+                )?;
+                for file in examples.iter() {
+                    writeln!(fmt, "Ignored {}", file)?;
+                }
+                Ok(())
+            }
+            AnalyzerResult::RecoveredPointCounts { files } => {
+                writeln!(
+                    fmt,
+                    "Files with zero points in the header, but with point data: {}",
+                    files.len()
+                )?;
+                for (file, point_count) in files.iter() {
+                    writeln!(
+                        fmt,
+                        "{}: {} points",
+                        file,
+                        format_number(*point_count as f64, raw)
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Describes where an `Analyzer` takes the information about the nodes from, for explaining how its results were
+/// computed
+#[derive(Debug, Clone, Copy)]
+pub struct Provenance {
+    /// How the nodes are found and counted
+    pub nodes: &'static str,
+    /// Where the point counts of the nodes come from
+    pub point_counts: &'static str,
+    /// Where the sizes of the nodes come from
+    pub node_sizes: &'static str,
+    /// Where the EPSG code of the dataset comes from
+    pub epsg: &'static str,
+}
+
+/// Trait for analyzing a point cloud
+pub trait Analyzer {
+    /// Runs the analyzer, passing the results to `emit` in the order of their `order_key`. Each result is passed as
+    /// soon as it and all results before it are available, which allows showing the first results while the remaining
+    /// analyses are still running
+    fn run_with(&self, emit: &mut dyn FnMut(AnalyzerResult)) -> error::Result<()>;
+
+    /// Runs the analyzer, returning the results of the analysis on success
+    fn run(&self) -> error::Result<Vec<AnalyzerResult>> {
+        let mut results = vec![];
+        self.run_with(&mut |result| results.push(result))?;
+        Ok(results)
+    }
+
+    /// Describes where the analyzer takes the information about the nodes from
+    fn provenance(&self) -> Provenance;
+}
+
+/// Passes the results of an analyzer to `emit` in the order of their `order_key`, independent of the order in which
+/// the analyses finish. Results are held back until the analyzer releases them
+struct OrderedEmit<'a> {
+    emit: &'a mut dyn FnMut(AnalyzerResult),
+    pending: Vec<AnalyzerResult>,
+}
+
+impl OrderedEmit<'_> {
+    fn push(&mut self, result: AnalyzerResult) {
+        self.pending.push(result);
+    }
+
+    /// Passes all pending results with a key up to `key` to `emit`. Call this only once no more results with a lower
+    /// key than `key` will be pushed
+    fn release_up_to(&mut self, key: u8) {
+        // Stable sort, so results of the same kind (e.g. multiple histograms) keep their order
+        self.pending.sort_by_key(|result| result.order_key());
+        let released = self
+            .pending
+            .partition_point(|result| result.order_key() <= key);
+        for result in self.pending.drain(..released) {
+            (self.emit)(result);
+        }
+    }
+}
+
+/// Runs `analyze` with results in the order of their `order_key`. All results that are still pending afterwards are
+/// passed to `emit`, even if `analyze` failed, like the results that were passed before the failure
+fn emit_in_order<F: FnOnce(&mut OrderedEmit) -> error::Result<()>>(
+    emit: &mut dyn FnMut(AnalyzerResult),
+    analyze: F,
+) -> error::Result<()> {
+    let mut ordered = OrderedEmit {
+        emit,
+        pending: vec![],
+    };
+    let outcome = analyze(&mut ordered);
+    ordered.release_up_to(u8::MAX);
+    outcome
+}
+
+/// Returns a short note on how the result was computed, or `None` if the result needs no explanation
+fn explanation(result: &AnalyzerResult, provenance: &Provenance) -> Option<String> {
+    let from_point_counts =
+        |what: &str| format!("{}, point counts {}", what, provenance.point_counts);
+    let note = match result {
+        AnalyzerResult::NodeCount(_) => format!("Nodes: {}", provenance.nodes),
+        AnalyzerResult::Epsg(_) => format!("EPSG code {}", provenance.epsg),
+        AnalyzerResult::Histogram(_) | AnalyzerResult::Sparkline(_) => {
+            from_point_counts("Buckets over the sorted point counts of all nodes")
+        }
+        AnalyzerResult::Gini(_) => from_point_counts("Gini coefficient of the sorted point counts"),
+        AnalyzerResult::Moments { .. } => {
+            from_point_counts("Population skewness and excess kurtosis of the point counts")
+        }
+        AnalyzerResult::Statistics { .. } => {
+            from_point_counts("Mean and population standard deviation of the point counts")
+        }
+        AnalyzerResult::TotalPoints(_) => from_point_counts("Sum of the point counts of all nodes"),
+        AnalyzerResult::Percentiles(_) => from_point_counts(
+            "Linear interpolation between the two nodes closest to each percentile of the sorted point counts",
+        ),
+        AnalyzerResult::ApproxQuantiles { .. } => from_point_counts(
+            "Streaming estimate from logarithmic bins, without sorting the point counts",
+        ),
+        AnalyzerResult::DownsampleRecommendation { .. } => from_point_counts(
+            "Point counts above the target divided by the target, averaged over the nodes above the target",
+        ),
+        AnalyzerResult::OversizedNodes { .. } => {
+            from_point_counts("Nodes whose point count exceeds the threshold")
+        }
+        AnalyzerResult::SizeHistogram(_) => {
+            format!("Buckets over the node sizes, sizes {}", provenance.node_sizes)
+        }
+        AnalyzerResult::LeafPointRatio { .. } => from_point_counts(
+            "Leaf nodes are the nodes without children according to their child mask",
+        ),
+        AnalyzerResult::GsdConsistency { .. } => from_point_counts(
+            "Spacing estimated per level as the side length of its nodes divided by the square root of the mean points per node",
+        ),
+        AnalyzerResult::LevelSummary(_) => {
+            from_point_counts("Levels from walking the hierarchy from the root node")
+        }
+        AnalyzerResult::LodCurve(_) => from_point_counts(
+            "Running sum of the points of each level, levels from walking the hierarchy from the root node",
+        ),
+        AnalyzerResult::SampledColors(sample) => format!(
+            "Colors of every {}. point of each LAS/LAZ file, files without colors according to their point format are not read",
+            sample.stride
+        ),
+        AnalyzerResult::LaxCells { .. } => "Point counts of the quadtree cells of the .lax index next to each LAS/LAZ file, the point data is not read".to_owned(),
+        AnalyzerResult::LevelCount(_) => format!(
+            "Distinct octree levels of the nodes, nodes: {}",
+            provenance.nodes
+        ),
+        AnalyzerResult::MeanFanout(_) => {
+            "Number of set bits in the child masks of all inner nodes of hierarchy.bin".to_owned()
+        }
+        AnalyzerResult::HierarchyAnomalies { .. } => {
+            from_point_counts("Point count of each node compared with the one of its parent node")
+        }
+        AnalyzerResult::MissingChildren { .. } => "Child masks and proxy nodes of hierarchy.bin compared with the node records that are stored for them".to_owned(),
+        AnalyzerResult::InferredRecordLayout { .. } => "Known node record layouts checked against the size of hierarchy.bin and the node types, point counts and proxy nodes decoded with them".to_owned(),
+        AnalyzerResult::CountCrossCheck { .. } => "Valid records of hierarchy.bin (type != 2 or child mask == 0) compared with the nodes reachable from the root node through the child masks".to_owned(),
+        AnalyzerResult::AttributeMismatches { .. } => "Byte size of each node record in hierarchy.bin compared with its point count times the sum of the attribute sizes in metadata.json".to_owned(),
+        AnalyzerResult::DiskUsage { .. } => {
+            "Sizes of all files of the dataset on disk, the node files count as point data".to_owned()
+        }
+        AnalyzerResult::MtimeStats { .. } => {
+            "Modification times of the node files from the file system".to_owned()
+        }
+        AnalyzerResult::CopcPointCountMismatches { .. } => "Number of point records of the LAS header compared with the sum of the point counts of all pages of the COPC hierarchy".to_owned(),
+        AnalyzerResult::DuplicateGroups(_) => "Node files of the same size compared by a hash of their first kilobyte and then by a hash of their full content".to_owned(),
+        AnalyzerResult::UncompressedEstimate { .. } => {
+            from_point_counts("Point count times point record length from the LAS headers")
+        }
+        AnalyzerResult::PointFormatMismatches { .. } | AnalyzerResult::HeaderLayouts { .. } => {
+            "Values from the LAS headers, compared with the most common values".to_owned()
+        }
+        AnalyzerResult::OverlappingNodes { .. } => "Bounding boxes from the LAS headers, ancestors from the node keys in the file names or from the nesting of the bounding boxes".to_owned(),
+        AnalyzerResult::SkippedFiles { .. } => {
+            format!("Files larger than the limit, sizes {}", provenance.node_sizes)
+        }
+        AnalyzerResult::CollapsedExtensionPairs { .. } => "Files with the same path apart from the .las and .laz extension, the .las file is ignored".to_owned(),
+        AnalyzerResult::RecoveredPointCounts { .. } => "Size of the point data (file size minus point data offset, up to the first EVLR) divided by the point record length from the LAS header".to_owned(),
+        AnalyzerResult::Nodes(_) | AnalyzerResult::Explained { .. } => return None,
+    };
+    Some(note)
+}
+
+/// Annotates the result with a short note on how it was computed
+pub fn explain_result(result: AnalyzerResult, provenance: &Provenance) -> AnalyzerResult {
+    match explanation(&result, provenance) {
+        Some(explanation) => AnalyzerResult::Explained {
+            result: Box::new(result),
+            explanation,
+        },
+        None => result,
+    }
+}
+
+/// Annotates each result with a short note on how it was computed
+pub fn explain_results(
+    results: Vec<AnalyzerResult>,
+    provenance: &Provenance,
+) -> Vec<AnalyzerResult> {
+    results
+        .into_iter()
+        .map(|result| explain_result(result, provenance))
+        .collect()
+}
+
+/// Summary of a read throughput benchmark over all files of a dataset
+#[derive(Debug)]
+pub struct BenchmarkSummary {
+    pub files: usize,
+    pub bytes: u64,
+    /// Number of points that were read, if the points were read and not only the headers
+    pub points: Option<u64>,
+    pub elapsed: Duration,
+}
+
+impl Display for BenchmarkSummary {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+        let raw = fmt.alternate();
+        let per_second = |value: f64| {
+            progress::throughput(value, self.elapsed).map_or_else(
+                || "-".to_owned(),
+                |throughput| format_number(throughput, raw),
+            )
+        };
+        writeln!(
+            fmt,
+            "Read {} files ({} bytes) in {:.3}s",
+            format_number(self.files as f64, raw),
+            format_number(self.bytes as f64, raw),
+            self.elapsed.as_secs_f64()
+        )?;
+        writeln!(fmt, "Files/s: {}", per_second(self.files as f64))?;
+        writeln!(fmt, "Bytes/s: {}", per_second(self.bytes as f64))?;
+        if let Some(points) = self.points {
+            writeln!(fmt, "Points/s: {}", per_second(points as f64))?;
+        }
+        Ok(())
+    }
+}
+
+/// Analyzer for tiling formats where one node equals one file
+pub struct MultiFileAnalyzer {
+    files: Vec<PathBuf>,
+    /// The directories that were searched for files, empty if the files were given explicitly
+    root_dirs: Vec<PathBuf>,
+    /// Files that were skipped because they exceed the maximum file size, together with their size in bytes
+    skipped_files: Vec<(PathBuf, u64)>,
+    /// `.las` files that were ignored because a `.laz` file for the same node exists
+    collapsed_files: Vec<PathBuf>,
+    options: AnalysisOptions,
+}
+
+impl MultiFileAnalyzer {
+    /// Returns a builder for configuring a `MultiFileAnalyzer` step by step
+    pub fn builder() -> MultiFileAnalyzerBuilder {
+        MultiFileAnalyzerBuilder::default()
+    }
+
+    /// Creates a new `MultiFileAnalyzer` for the data in the given directory
+    pub fn new<P: AsRef<Path>>(root_dir: P, options: AnalysisOptions) -> error::Result<Self> {
+        Self::from_roots(&[root_dir], options)
+    }
+
+    /// Creates a new `MultiFileAnalyzer` for a single dataset whose data is spread over the given directories
+    pub fn from_roots<P: AsRef<Path>>(
+        root_dirs: &[P],
+        options: AnalysisOptions,
+    ) -> error::Result<Self> {
+        let mut files = vec![];
+        let mut excluded_files = 0;
+        // Walking the directories has no known target, so a spinner shows that the discovery is still running
+        let mut spinner = if options.quiet || options.progress_json {
+            None
+        } else {
+            Some(Spinner::new("Discovering files"))
+        };
+        // A depth of 1 means the entries of the root directory itself, without any nested entries
+        let max_depth = if options.no_recurse {
+            1
+        } else {
+            options.walk_max_depth.unwrap_or(DEFAULT_WALK_MAX_DEPTH)
+        };
+        if options.no_recurse || options.walk_max_depth.is_some() {
+            info!(
+                options,
+                "Searching for files up to directory depth {}", max_depth
+            );
+        }
+        // Directories at the maximum depth are not descended into, so any files below them are missed
+        let mut depth_limit_reached = false;
+        for root_dir in root_dirs.iter() {
+            if !root_dir.as_ref().exists() {
+                return Err(AnalyzerError::RootDirNotFound(root_dir.as_ref().to_owned()));
+            }
+
+            files.extend(
+                WalkDir::new(root_dir)
+                    .max_depth(max_depth)
+                    .into_iter()
+                    .take_while(|_| !options.stop_requested())
+                    .filter_map(|entry| entry.ok())
+                    .inspect(|entry| {
+                        if entry.depth() == max_depth && entry.file_type().is_dir() {
+                            depth_limit_reached = true;
+                        }
+                    })
+                    .filter(|entry| Self::is_supported_format(entry.path()))
+                    .filter(|entry| {
+                        let excluded =
+                            Self::is_excluded(entry.path(), root_dir.as_ref(), &options.exclude);
+                        if excluded {
+                            excluded_files += 1;
+                        }
+                        !excluded
+                    })
+                    .inspect(|_| {
+                        if let Some(spinner) = spinner.as_mut() {
+                            spinner.inc();
+                        }
+                    })
+                    .filter(|entry| match options.node_prefix.as_ref() {
+                        Some(node_prefix) => Self::has_name_prefix(entry.path(), node_prefix),
+                        None => true,
+                    })
+                    .filter(|entry| match options.modified_since {
+                        Some(modified_since) => Self::is_modified_since(entry, modified_since),
+                        None => true,
+                    })
+                    .map(|entry| entry.into_path()),
+            );
+        }
+
+        if let Some(spinner) = spinner {
+            spinner.finish();
+        }
+
+        if depth_limit_reached && !options.no_recurse {
+            eprintln!(
+                "Warning: Reached the maximum directory depth of {} while searching for files, the dataset may be incomplete. Use --walk-max-depth to search deeper",
+                max_depth
+            );
+        }
+
+        if !options.exclude.is_empty() {
+            info!(options, "Excluded {} files", excluded_files);
+        }
+
+        if let Some(node_prefix) = options.node_prefix.as_ref() {
+            info!(
+                options,
+                "{} files match the node prefix {}",
+                files.len(),
+                node_prefix
+            );
+        }
+
+        // The same root might be given more than once, which must not count its files twice
+        if root_dirs.len() > 1 {
+            files.sort();
+            files.dedup();
+        }
+
+        let mut analyzer = Self::with_files(files, options);
+        analyzer.root_dirs = root_dirs
+            .iter()
+            .map(|root_dir| root_dir.as_ref().to_owned())
+            .collect();
+        Ok(analyzer)
+    }
+
+    /// Creates a new `MultiFileAnalyzer` for exactly the given files, without searching for files or checking their
+    /// format. Files that don't exist are reported and skipped
+    pub fn from_files(files: Vec<PathBuf>, options: AnalysisOptions) -> Self {
+        let (existing_files, missing_files): (Vec<_>, Vec<_>) = files
+            .into_iter()
+            .partition(|file| file.exists() || remote::as_url(file).is_some());
+        if !missing_files.is_empty() {
+            eprintln!("Skipping {} files that don't exist:", missing_files.len());
+            for file in missing_files.iter() {
+                eprintln!("{}", file.display());
+            }
+        }
+
+        Self::with_files(existing_files, options)
+    }
+
+    fn with_files(mut files: Vec<PathBuf>, options: AnalysisOptions) -> Self {
+        let mut collapsed_files = vec![];
+        if options.dedup_extensions {
+            collapsed_files = Self::collapse_extension_pairs(&mut files);
+            if !collapsed_files.is_empty() {
+                info!(
+                    options,
+                    "Counting {} nodes with both a .las and a .laz file only once, using the .laz file",
+                    collapsed_files.len()
+                );
+            }
+        }
+
+        let mut skipped_files = vec![];
+        if let Some(max_file_size) = options.max_file_size {
+            let (accepted_files, oversized_files): (Vec<_>, Vec<_>) = files
+                .into_iter()
+                .map(|file| {
+                    let file_size = Self::file_size(&file);
+                    (file, file_size)
+                })
+                .partition(|(_, file_size)| file_size.map_or(true, |size| size <= max_file_size));
+            files = accepted_files.into_iter().map(|(file, _)| file).collect();
+            skipped_files = oversized_files
+                .into_iter()
+                .map(|(file, file_size)| (file, file_size.unwrap_or_default()))
+                .collect();
+            if !skipped_files.is_empty() {
+                info!(
+                    options,
+                    "Skipping {} files that are larger than {} bytes",
+                    skipped_files.len(),
+                    max_file_size
+                );
+            }
+        }
+
+        MultiFileAnalyzer {
+            files,
+            root_dirs: vec![],
+            skipped_files,
+            collapsed_files,
+            options,
+        }
+    }
+
+    /// Removes all `.las` files that have a `.laz` file with the same path apart from the extension and returns them
+    fn collapse_extension_pairs(files: &mut Vec<PathBuf>) -> Vec<PathBuf> {
+        let has_extension =
+            |file: &Path, extension: &str| file.extension().map_or(false, |ext| ext == extension);
+        let laz_files = files
+            .iter()
+            .filter(|file| has_extension(file, "laz"))
+            .map(|file| file.with_extension(""))
+            .collect::<HashSet<_>>();
+        let (collapsed_files, kept_files): (Vec<_>, Vec<_>) = files.drain(..).partition(|file| {
+            has_extension(file, "las") && laz_files.contains(&file.with_extension(""))
+        });
+        *files = kept_files;
+        collapsed_files
+    }
+
+    /// Files whose size can't be determined are not skipped, reading them reports the actual error
+    fn file_size(file: &Path) -> Option<u64> {
+        std::fs::metadata(file).ok().map(|meta| meta.len())
+    }
+
+    /// LAS and LAZ files are always supported, gzip- or zstd-compressed LAS files ('.las.gz' and '.las.zst') only
+    /// with the 'compressed-las' feature
+    fn is_supported_format<P: AsRef<Path>>(path: P) -> bool {
+        match path.as_ref().extension() {
+            Some(extension) if extension == "las" || extension == "laz" => true,
+            Some(extension) if extension == "gz" || extension == "zst" => {
+                cfg!(feature = "compressed-las")
+                    && path.as_ref().file_stem().map_or(false, |file_stem| {
+                        Path::new(file_stem)
+                            .extension()
+                            .map_or(false, |extension| extension == "las")
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    /// Patterns are matched against the path relative to `root_dir`, so that they don't depend on where the dataset
+    /// is located
+    fn is_excluded(path: &Path, root_dir: &Path, exclude: &[Pattern]) -> bool {
+        let relative_path = path.strip_prefix(root_dir).unwrap_or(path);
+        exclude
+            .iter()
+            .any(|pattern| pattern.matches_path(relative_path))
+    }
+
+    fn has_name_prefix(path: &Path, prefix: &str) -> bool {
+        path.file_name()
+            .and_then(|file_name| file_name.to_str())
+            .map_or(false, |file_name| file_name.starts_with(prefix))
+    }
+
+    /// Files whose modification time can't be determined are treated as modified
+    fn is_modified_since(entry: &DirEntry, modified_since: SystemTime) -> bool {
+        match entry.metadata().ok().and_then(|meta| meta.modified().ok()) {
+            Some(modified) => modified > modified_since,
+            None => true,
+        }
+    }
+
+    /// Without the 'laz' feature, the las crate can't open compressed files. Checking this once up front gives a
+    /// clear error instead of a cryptic one for every single file
+    fn check_laz_support(&self) -> error::Result<()> {
+        if cfg!(feature = "laz") {
+            return Ok(());
+        }
+
+        match self.files.iter().find(|file| {
+            file.extension()
+                .map_or(false, |extension| extension == "laz")
+        }) {
+            Some(laz_file) => Err(AnalyzerError::MissingFeature {
+                feature: "laz",
+                found: format!("compressed file {}", laz_file.display()),
+                purpose: "analyze compressed files",
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Without the 'http' feature, files can't be read over HTTP(S). Like for LAZ support, this is checked once up
+    /// front
+    fn check_http_support(&self) -> error::Result<()> {
+        if cfg!(feature = "http") {
+            return Ok(());
+        }
+
+        match self.files.iter().find_map(|file| remote::as_url(file)) {
+            Some(url) => Err(AnalyzerError::MissingFeature {
+                feature: "http",
+                found: format!("URL {}", url),
+                purpose: "read files over HTTP(S)",
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Sums the sizes of all files in the root directories. For Entwine datasets, the root directory is the `ept-data`
+    /// directory, so its parent directory with the hierarchy and metadata is used instead. Without root directories,
+    /// only the given files are counted
+    fn disk_usage(&self) -> Result<AnalyzerResult> {
+        if self.root_dirs.is_empty() {
+            return Ok(AnalyzerResult::DiskUsage {
+                data_bytes: self
+                    .files
+                    .iter()
+                    .filter_map(|file| Self::file_size(file))
+                    .sum(),
+                metadata_bytes: 0,
+            });
+        }
+
+        let mut dataset_dirs = self
+            .root_dirs
+            .iter()
+            .map(|root_dir| match root_dir.file_name() {
+                Some(name) if name == "ept-data" => root_dir
+                    .parent()
+                    .map_or_else(|| root_dir.clone(), Path::to_owned),
+                _ => root_dir.clone(),
+            })
+            .collect::<Vec<_>>();
+        dataset_dirs.sort();
+        dataset_dirs.dedup();
+        disk_usage_result(&dataset_dirs, |path| Self::is_supported_format(path))
+    }
+
+    /// Collects the modification times of all files from the file system. Files whose modification time is unknown,
+    /// e.g. remote files, are ignored
+    fn mtime_stats(&self, threshold: u64) -> AnalyzerResult {
+        let modification_times = self
+            .files
+            .iter()
+            .filter_map(|file| {
+                std::fs::metadata(file)
+                    .and_then(|meta| meta.modified())
+                    .ok()
+            })
+            .filter_map(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|since_epoch| since_epoch.as_secs())
+            .collect::<Vec<_>>();
+        let oldest = modification_times.iter().copied().min().unwrap_or_default();
+        let newest = modification_times.iter().copied().max().unwrap_or_default();
+        AnalyzerResult::MtimeStats {
+            files: modification_times.len(),
+            oldest,
+            newest,
+            threshold,
+            partial_rewrite: newest - oldest > threshold,
+        }
+    }
+
+    /// Compares the point count of the header of each local COPC file with the sum of the point counts of its
+    /// hierarchy. Files that are not COPC files are ignored
+    fn check_copc(&self) -> Result<AnalyzerResult> {
+        let point_counts = self
+            .files
+            .par_iter()
+            .filter(|file| remote::as_url(file).is_none())
+            .map(
+                |file| -> Result<Option<(&PathBuf, copc::CopcPointCounts)>> {
+                    Ok(copc::read_copc_point_counts(file)?.map(|counts| (file, counts)))
+                },
+            )
+            .filter_map(|counts| counts.transpose())
+            .collect::<Result<Vec<_>>>()?;
+        let mut mismatches = point_counts
+            .iter()
+            .filter(|(_, counts)| !counts.matches())
+            .map(|(file, counts)| (file.display().to_string(), counts.header, counts.hierarchy))
+            .collect::<Vec<_>>();
+        mismatches.sort();
+        Ok(AnalyzerResult::CopcPointCountMismatches {
+            copc_files: point_counts.len(),
+            mismatches,
+        })
+    }
+
+    /// Groups the local files by their content. Only files of the same size can be duplicates, so files with a unique
+    /// size are never read. Files of the same size are compared by a hash of their first kilobyte first, and only
+    /// the files whose first kilobyte matches are hashed completely. The hashes are 64 bits wide, so files are
+    /// reported as duplicates if their hashes match, without comparing their bytes
+    fn find_duplicates(&self) -> Result<AnalyzerResult> {
+        let mut files_by_size = HashMap::<u64, Vec<&Path>>::new();
+        for file in self.files.iter() {
+            if let Some(file_size) = Self::file_size(file) {
+                files_by_size.entry(file_size).or_default().push(file);
+            }
+        }
+
+        let mut groups = vec![];
+        for (file_size, files) in files_by_size {
+            if files.len() < 2 {
+                continue;
+            }
+            for files in group_by_hash(files, |file| hash_file(file, DUPLICATE_FINGERPRINT_BYTES))?
+            {
+                // The fingerprint already covers small files completely
+                let files = if file_size <= DUPLICATE_FINGERPRINT_BYTES {
+                    vec![files]
+                } else {
+                    group_by_hash(files, |file| hash_file(file, u64::MAX))?
+                };
+                groups.extend(files.into_iter().map(|files| {
+                    let mut files = files
+                        .iter()
+                        .map(|file| file.display().to_string())
+                        .collect::<Vec<_>>();
+                    files.sort();
+                    DuplicateGroup { file_size, files }
+                }));
+            }
+        }
+        groups.sort_by(|a, b| {
+            b.wasted_bytes()
+                .cmp(&a.wasted_bytes())
+                .then_with(|| a.files.cmp(&b.files))
+        });
+        Ok(AnalyzerResult::DuplicateGroups(groups))
+    }
+
+    /// Reads the node information of the file at `url` from its header, which is fetched with an HTTP range request.
+    /// EVLRs are at the end of the file, so the point count is always the one of the header
+    #[cfg(feature = "http")]
+    fn read_remote_node(url: &str) -> Result<NodeInfo> {
+        let (header_bytes, file_size) = remote::read_header_bytes(url)?;
+        let raw_header = las::raw::Header::read_from(std::io::Cursor::new(header_bytes))
+            .with_context(|| format!("Could not read the header of {}", url))?;
+        let header = las::Header::from_raw(raw_header.clone())
+            .with_context(|| format!("Could not read the header of {}", url))?;
+        Ok(NodeInfo {
+            id: url.to_owned(),
+            point_count: header.number_of_points(),
+            file_size,
+            bounds: Some(header.bounds()),
+            point_record_length: Some(header.point_format().len()),
+            point_format: Some(header.point_format().to_u8()?),
+            header_size: Some(raw_header.header_size),
+            point_data_offset: Some(raw_header.offset_to_point_data),
+        })
+    }
+
+    /// Reads the headers of all files. If `quantile_sketch` is given, the point counts are inserted into it while
+    /// reading. With `trust_data`, the point counts of files that declare zero points are computed from their size,
+    /// and the affected files are added to `recovered_counts` if it is given
+    fn read_nodes(
+        &self,
+        quantile_sketch: Option<&Mutex<QuantileSketch>>,
+        recovered_counts: Option<&Mutex<Vec<(String, u64)>>>,
+    ) -> Result<Vec<NodeInfo>> {
+        let mut nodes = vec![];
+        self.read_node_batches(
+            self.files.len(),
+            quantile_sketch,
+            recovered_counts,
+            &mut |batch| nodes.extend(batch),
+        )?;
+        Ok(nodes)
+    }
+
+    /// Reads the nodes of `batch_size` files at a time and passes the nodes of each batch to `on_batch`, so that
+    /// only the nodes of a single batch are held in memory at once
+    fn read_node_batches(
+        &self,
+        batch_size: usize,
+        quantile_sketch: Option<&Mutex<QuantileSketch>>,
+        recovered_counts: Option<&Mutex<Vec<(String, u64)>>>,
+        on_batch: &mut dyn FnMut(Vec<NodeInfo>),
+    ) -> Result<()> {
+        self.check_laz_support()?;
+        self.check_http_support()?;
+
+        let chunk_size = 128;
+        let mut progress_tracker = ProgressTracker::new(
+            self.files.len() as f64,
+            ProgressUpdateCondition::OnProgressChanged(1000.0),
+        );
+        if let Some(eta_window) = self.options.eta_window {
+            progress_tracker = progress_tracker.with_throughput_window(eta_window);
+        }
+        if self.options.progress_json {
+            progress_tracker = progress_tracker.with_format(ProgressFormat::Json);
+        }
+        if self.options.quiet {
+            progress_tracker = progress_tracker.silent();
+        }
+        if let Some(status_file) = self.options.status_file.as_ref() {
+            progress_tracker = progress_tracker.with_status_file(status_file.clone());
+        }
+        let progress_tracker = Arc::new(Mutex::new(progress_tracker));
+        // Number of files and time spent reading them for each worker thread
+        let work_stats = Arc::new(Mutex::new(vec![
+            (0_usize, Duration::default());
+            rayon::current_num_threads()
+        ]));
+
+        let num_evlr_counts = AtomicUsize::new(0);
+        let num_name_counts = AtomicUsize::new(0);
+        let mut num_nodes = 0;
+
+        for batch in self.files.chunks(batch_size.max(1)) {
+            let nodes_nested = batch
+                .par_iter()
+                .chunks(chunk_size)
+                .map(|files| -> Result<Vec<NodeInfo>> {
+                    if self.options.stop_requested() {
+                        return Ok(vec![]);
+                    }
+
+                    let start = Instant::now();
+                    let nodes = files
+                        .iter()
+                        .map(|&file| -> Result<NodeInfo> {
+                            #[cfg(feature = "http")]
+                            {
+                                if let Some(url) = remote::as_url(file) {
+                                    return Self::read_remote_node(url);
+                                }
+                            }
+
+                            if let Some(point_count) = self
+                                .options
+                                .count_from_name
+                                .as_ref()
+                                .and_then(|regex| Self::read_name_count(file, regex))
+                            {
+                                num_name_counts.fetch_add(1, Ordering::Relaxed);
+                                return Ok(NodeInfo {
+                                    id: file.display().to_string(),
+                                    point_count,
+                                    file_size: std::fs::metadata(file)?.len(),
+                                    bounds: None,
+                                    point_record_length: None,
+                                    point_format: None,
+                                    header_size: None,
+                                    point_data_offset: None,
+                                });
+                            }
+
+                            let reader = open_las_file(file)?;
+                            let header = reader.header();
+                            // Converting the header back into its raw form copies all (E)VLRs, so it is only
+                            // done for the analyses that need the raw fields
+                            let raw_header =
+                                if self.options.check_integrity || self.options.trust_data {
+                                    Some(header.clone().into_raw()?)
+                                } else {
+                                    None
+                                };
+                            let evlr_count = self
+                                .options
+                                .evlr_count
+                                .as_ref()
+                                .and_then(|user_id| Self::read_evlr_count(header, user_id));
+                            if evlr_count.is_some() {
+                                num_evlr_counts.fetch_add(1, Ordering::Relaxed);
+                            }
+                            let id = file.display().to_string();
+                            let file_size = std::fs::metadata(file)?.len();
+                            let mut point_count =
+                                evlr_count.unwrap_or_else(|| header.number_of_points());
+                            if point_count == 0 && self.options.trust_data {
+                                if let Some(recovered_count) =
+                                    raw_header.as_ref().and_then(|raw_header| {
+                                        Self::recover_point_count(file, raw_header, file_size)
+                                    })
+                                {
+                                    point_count = recovered_count;
+                                    if let Some(recovered_counts) = recovered_counts {
+                                        recovered_counts
+                                            .lock()
+                                            .unwrap()
+                                            .push((id.clone(), recovered_count));
+                                    }
+                                }
+                            }
+                            Ok(NodeInfo {
+                                id,
+                                point_count,
+                                file_size,
+                                bounds: Some(header.bounds()),
+                                point_record_length: Some(header.point_format().len()),
+                                point_format: Some(header.point_format().to_u8()?),
+                                header_size: raw_header
+                                    .as_ref()
+                                    .map(|raw_header| raw_header.header_size),
+                                point_data_offset: raw_header
+                                    .as_ref()
+                                    .map(|raw_header| raw_header.offset_to_point_data),
+                            })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    if let Some(quantile_sketch) = quantile_sketch {
+                        let mut quantile_sketch = quantile_sketch.lock().unwrap();
+                        for node in nodes.iter() {
+                            if in_count_range(node.point_count, &self.options) {
+                                quantile_sketch.insert(node.point_count);
+                            }
+                        }
+                    }
+
+                    if self.options.work_stats {
+                        if let Some(worker_index) = rayon::current_thread_index() {
+                            let mut work_stats = work_stats.lock().unwrap();
+                            work_stats[worker_index].0 += files.len();
+                            work_stats[worker_index].1 += start.elapsed();
+                        }
+                    }
+
+                    if !self.options.quiet || self.options.status_file.is_some() {
+                        let mut progress = progress_tracker.lock().unwrap();
+                        progress.inc_progress(files.len() as f64);
+                    }
+
+                    Ok(nodes)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let nodes = nodes_nested.into_iter().flatten().collect::<Vec<_>>();
+            num_nodes += nodes.len();
+            on_batch(nodes);
+        }
+
+        if self.options.work_stats {
+            Self::print_work_stats(&work_stats.lock().unwrap());
+        }
+
+        if self.options.evlr_count.is_some() {
+            let num_evlr_counts = num_evlr_counts.into_inner();
+            info!(
+                self.options,
+                "Point counts read from EVLRs: {}, from headers: {}",
+                num_evlr_counts,
+                num_nodes - num_evlr_counts
+            );
+        }
+        if self.options.count_from_name.is_some() {
+            let num_name_counts = num_name_counts.into_inner();
+            info!(
+                self.options,
+                "Point counts read from file names: {}, from headers: {}",
+                num_name_counts,
+                num_nodes - num_name_counts
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Measures how fast the headers of all files can be read, or all points if `read_points` is set. The file sizes
+    /// are taken from the file system, so `bytes` is the amount of data stored, not the amount of data read
+    pub fn benchmark(&self, read_points: bool) -> error::Result<BenchmarkSummary> {
+        if self.files.is_empty() {
+            return Err(AnalyzerError::EmptyDataset(
+                "No LAS/LAZ files found, benchmarking is only supported for datasets with one file per node"
+                    .to_owned(),
+            ));
+        }
+        self.check_laz_support()?;
+
+        let chunk_size = 128;
+        let mut progress_tracker = ProgressTracker::new(
+            self.files.len() as f64,
+            ProgressUpdateCondition::OnProgressChanged(1000.0),
+        );
+        if let Some(eta_window) = self.options.eta_window {
+            progress_tracker = progress_tracker.with_throughput_window(eta_window);
+        }
+        let progress_tracker = Mutex::new(progress_tracker);
+
+        let start = Instant::now();
+        let (bytes, points) = self
+            .files
+            .par_iter()
+            .chunks(chunk_size)
+            .map(|files| -> Result<(u64, u64)> {
+                let mut bytes = 0;
+                let mut points = 0;
+                for file in files.iter() {
+                    let mut reader = open_las_file(file)?;
+                    bytes += std::fs::metadata(file)?.len();
+                    if read_points {
+                        for point in reader.points() {
+                            point.with_context(|| format!("Could not read {}", file.display()))?;
+                            points += 1;
+                        }
+                    }
+                }
+
+                if !self.options.quiet {
+                    progress_tracker
+                        .lock()
+                        .unwrap()
+                        .inc_progress(files.len() as f64);
+                }
+                Ok((bytes, points))
+            })
+            .try_reduce(|| (0, 0), |a, b| Ok((a.0 + b.0, a.1 + b.1)))?;
+
+        Ok(BenchmarkSummary {
+            files: self.files.len(),
+            bytes,
+            points: if read_points { Some(points) } else { None },
+            elapsed: start.elapsed(),
+        })
+    }
+
+    /// Reads every `stride`-th point of each file, starting with the first point, and summarizes their colors. The
+    /// same points are sampled in every file, independent of the order in which the files are read
+    fn sample_colors(&self, stride: u64) -> Result<AnalyzerResult> {
+        self.check_laz_support()?;
+
+        let sample =
+            self.files
+                .par_iter()
+                .map(|file| -> Result<ColorSample> {
+                    let mut reader = open_las_file(file)?;
+                    if !reader.header().point_format().has_color {
+                        return Ok(ColorSample {
+                            files_without_color: 1,
+                            ..Default::default()
+                        });
+                    }
+
+                    let mut sample = ColorSample {
+                        files_with_color: 1,
+                        ..Default::default()
+                    };
+                    let point_count = reader.header().number_of_points();
+                    let mut index = 0;
+                    while index < point_count {
+                        // Seeking is only necessary if points are skipped
+                        if stride > 1 {
+                            reader.seek(index)?;
+                        }
+                        let point = match reader.read() {
+                            Some(point) => point
+                                .with_context(|| format!("Could not read {}", file.display()))?,
+                            None => break,
+                        };
+                        if let Some(color) = point.color {
+                            sample.insert([color.red, color.green, color.blue]);
+                        }
+                        index += stride;
+                    }
+                    Ok(sample)
+                })
+                .try_reduce(ColorSample::default, |a, b| Ok(a.merge(b)))?;
+        Ok(AnalyzerResult::SampledColors(ColorSample {
+            stride,
+            ..sample
+        }))
+    }
+
+    /// Reads the point counts of all quadtree cells from the `.lax` indexes next to the files and calculates a
+    /// histogram of them. Files without an index are counted, but otherwise ignored
+    fn lax_cells(&self) -> Result<AnalyzerResult> {
+        let indexes = self
+            .files
+            .par_iter()
+            .map(|file| {
+                let lax_file = lax::lax_path(file);
+                if !lax_file.exists() {
+                    return Ok(None);
+                }
+                lax::read_lax_file(lax_file).map(Some)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut sorted_counts = indexes
+            .iter()
+            .flatten()
+            .flat_map(|index| index.cells.iter().map(|cell| cell.point_count as usize))
+            .collect::<Vec<_>>();
+        sorted_counts.sort();
+        let indexed_files = indexes.iter().flatten().count();
+        let histogram_config = self
+            .options
+            .histogram_config
+            .clone()
+            .unwrap_or(HistogramConfig::Logarithmic(10));
+        Ok(AnalyzerResult::LaxCells {
+            indexed_files,
+            unindexed_files: indexes.len() - indexed_files,
+            cells: sorted_counts.len(),
+            histogram: make_histogram(&sorted_counts, &histogram_config),
+        })
+    }
+
+    /// Counts the files that have a `.lax` index next to them
+    fn count_lax_files(&self) -> usize {
+        self.files
+            .par_iter()
+            .filter(|file| lax::lax_path(file).exists())
+            .count()
+    }
+
+    /// Counts the distinct octree levels of all files, parsing the level of each node from its file name. Files whose
+    /// names follow neither the Entwine nor the Potree naming convention are ignored
+    fn count_levels(&self) -> AnalyzerResult {
+        let keys = self
+            .files
+            .iter()
+            .filter_map(NodeKey::from_path)
+            .collect::<Vec<_>>();
+        if keys.len() < self.files.len() {
+            eprintln!(
+                "Could not determine the octree level of {} files from their names, ignoring them for the level count",
+                self.files.len() - keys.len()
+            );
+        }
+        level_count_result(keys.iter().map(NodeKey::depth))
+    }
+
+    /// Reads a precomputed point count from the EVLR with the given user ID, which stores the count as a 64-bit
+    /// little-endian integer. Returns `None` if there is no such EVLR or its data is not a valid count
+    fn read_evlr_count(header: &las::Header, user_id: &str) -> Option<u64> {
+        let evlr = header.evlrs().iter().find(|evlr| evlr.user_id == user_id)?;
+        let bytes = evlr.data.get(..8)?;
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Parses the point count from the capture group `count` of `regex` in the file name. Returns `None` if the name
+    /// does not match or the captured text is not a valid count
+    fn read_name_count(file: &Path, regex: &Regex) -> Option<u64> {
+        let file_name = file.file_name()?.to_str()?;
+        regex
+            .captures(file_name)?
+            .name("count")?
+            .as_str()
+            .parse()
+            .ok()
+    }
+
+    /// Computes the point count of an uncompressed `.las` file from the size of its point data, which ends at the
+    /// first EVLR or at the end of the file. Returns `None` for other files or if the file contains no point data
+    fn recover_point_count(
+        file: &Path,
+        raw_header: &las::raw::Header,
+        file_size: u64,
+    ) -> Option<u64> {
+        let is_las = file
+            .extension()
+            .map_or(false, |extension| extension.eq_ignore_ascii_case("las"));
+        if !is_las || raw_header.point_data_record_length == 0 {
+            return None;
+        }
+        let point_data_end = raw_header
+            .evlr
+            .map(|evlr| evlr.start_of_first_evlr)
+            .filter(|&start| start > 0)
+            .unwrap_or(file_size);
+        let point_count = point_data_end.saturating_sub(raw_header.offset_to_point_data as u64)
+            / raw_header.point_data_record_length as u64;
+        Some(point_count).filter(|&count| count > 0)
+    }
+
+    /// Prints the number of files and the time spent for each worker thread, together with the imbalance between
+    /// the busiest and the average worker. A large imbalance suggests adjusting the chunk size or thread count
+    fn print_work_stats(work_stats: &[(usize, Duration)]) {
+        eprintln!("Worker | Files | Time [s]");
+        for (worker_index, (files, duration)) in work_stats.iter().enumerate() {
+            eprintln!(
+                "{:>6} | {:>5} | {:>8.3}",
+                worker_index,
+                files,
+                duration.as_secs_f64()
+            );
+        }
+
+        let max_files = work_stats.iter().map(|(files, _)| *files).max();
+        let mean_files = work_stats.iter().map(|(files, _)| *files).sum::<usize>() as f64
+            / work_stats.len() as f64;
+        if let Some(max_files) = max_files {
+            if mean_files > 0.0 {
+                eprintln!(
+                    "Imbalance (max / mean files per worker): {:.2}",
+                    max_files as f64 / mean_files
+                );
+            }
+        }
+    }
+
+    /// All nodes of a dataset share the same coordinate reference system, so it is read from the first file only
+    fn read_epsg(&self) -> Result<Option<u32>> {
+        read_file_epsg(&self.files[0])
+    }
+
+    /// Analyzes the point counts of all nodes. `nodes` is only used to list the oversized files and may be empty if
+    /// only the point counts were kept, e.g. when reading the files in batches
+    fn analyze_point_counts(
+        &self,
+        point_counts: &[u64],
+        nodes: &[NodeInfo],
+    ) -> Vec<AnalyzerResult> {
+        let mut num_points_per_node = point_counts
+            .iter()
+            .map(|&point_count| point_count as usize)
+            .collect::<Vec<_>>();
+        sort_point_counts(&mut num_points_per_node, self.options.assume_sorted);
+
+        let mut results = point_count_results(num_points_per_node.as_slice(), &self.options);
+        // The sorted point counts don't know which file they belong to, so the oversized files are added here
+        for result in results.iter_mut() {
+            if let AnalyzerResult::OversizedNodes {
+                threshold,
+                node_ids,
+                ..
+            } = result
+            {
+                *node_ids = nodes
+                    .iter()
+                    .filter(|node| {
+                        node.point_count > *threshold
+                            && in_count_range(node.point_count, &self.options)
+                    })
+                    .map(|node| node.id.clone())
+                    .collect();
+            }
+        }
+        results
+    }
+
+    fn check_point_formats(&self, nodes: &[NodeInfo]) -> AnalyzerResult {
+        let formats = nodes
+            .iter()
+            .map(|node| {
+                (
+                    node.point_format.unwrap_or_default(),
+                    node.point_record_length.unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut format_counts = HashMap::new();
+        for format in formats.iter() {
+            *format_counts.entry(*format).or_insert(0_usize) += 1;
+        }
+        // Break ties by the smaller format so that the result is deterministic
+        let (modal_point_format, modal_record_length) = format_counts
+            .into_iter()
+            .max_by(|(format_a, count_a), (format_b, count_b)| {
+                count_a.cmp(count_b).then(format_b.cmp(format_a))
+            })
+            .map(|(format, _)| format)
+            .unwrap_or_default();
+
+        let mismatches = nodes
+            .iter()
+            .zip(formats.iter())
+            .filter(|(_, &format)| format != (modal_point_format, modal_record_length))
+            .map(|(node, &(point_format, record_length))| {
+                (node.id.clone(), point_format, record_length)
+            })
+            .collect();
+
+        AnalyzerResult::PointFormatMismatches {
+            modal_point_format,
+            modal_record_length,
+            mismatches,
+        }
+    }
+
+    /// Calculates how many files use each combination of header size and point data offset. Files whose
+    /// combination differs from the most common one are reported as outliers, as an unusual point data offset
+    /// indicates additional VLRs or a malformed header
+    fn check_header_layouts(&self, nodes: &[NodeInfo]) -> AnalyzerResult {
+        let layouts = nodes
+            .iter()
+            .map(|node| {
+                (
+                    node.header_size.unwrap_or_default(),
+                    node.point_data_offset.unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut layout_counts = HashMap::new();
+        for layout in layouts.iter() {
+            *layout_counts.entry(*layout).or_insert(0_usize) += 1;
+        }
+        let mut distribution = layout_counts
+            .into_iter()
+            .map(|((header_size, point_data_offset), count)| {
+                (header_size, point_data_offset, count)
+            })
+            .collect::<Vec<_>>();
+        // Most common layout first, ties are broken by the smaller layout so that the result is deterministic
+        distribution.sort_by(|a, b| b.2.cmp(&a.2).then((a.0, a.1).cmp(&(b.0, b.1))));
+        let modal_layout = distribution
+            .first()
+            .map(|&(header_size, point_data_offset, _)| (header_size, point_data_offset))
+            .unwrap_or_default();
+
+        let outliers = nodes
+            .iter()
+            .zip(layouts.iter())
+            .filter(|(_, &layout)| layout != modal_layout)
+            .map(|(node, &(header_size, point_data_offset))| {
+                (node.id.clone(), header_size, point_data_offset)
+            })
+            .collect();
+
+        AnalyzerResult::HeaderLayouts {
+            distribution,
+            outliers,
+        }
+    }
+
+    /// Finds all pairs of nodes whose bounding boxes overlap by more than `MIN_OVERLAP`, unless one node is an
+    /// ancestor of the other. Ancestors are determined from the file names if possible, otherwise from the nesting of
+    /// the bounding boxes. Sorting the nodes by their minimum x coordinate means only nodes whose x ranges intersect
+    /// have to be compared, but the check is still quadratic in the worst case
+    fn check_overlap(&self, nodes: &[NodeInfo]) -> AnalyzerResult {
+        const MIN_OVERLAP: f64 = 0.5;
+        const MAX_EXAMPLES: usize = 10;
+
+        if nodes.len() > 50_000 {
+            info!(
+                self.options,
+                "Checking {} nodes for overlaps, this might take a while",
+                nodes.len()
+            );
+        }
+
+        let mut nodes_with_bounds = nodes
+            .iter()
+            .filter_map(|node| {
+                node.bounds
+                    .map(|bounds| (node, bounds, NodeKey::from_path(&node.id)))
+            })
+            .collect::<Vec<_>>();
+        nodes_with_bounds.sort_by(|(_, a, _), (_, b, _)| a.min.x.total_cmp(&b.min.x));
+
+        let mut count = 0;
+        let mut examples = vec![];
+        for (idx, (node, bounds, key)) in nodes_with_bounds.iter().enumerate() {
+            for (other_node, other_bounds, other_key) in nodes_with_bounds[idx + 1..]
+                .iter()
+                .take_while(|(_, other_bounds, _)| other_bounds.min.x < bounds.max.x)
+            {
+                let overlap = bounds_overlap(bounds, other_bounds);
+                if overlap <= MIN_OVERLAP {
+                    continue;
+                }
+
+                let nested = match (key, other_key) {
+                    (Some(key), Some(other_key)) => {
+                        key.is_ancestor_of(other_key) || other_key.is_ancestor_of(key)
+                    }
+                    _ => {
+                        bounds_contain(bounds, other_bounds) || bounds_contain(other_bounds, bounds)
+                    }
+                };
+                if nested {
+                    continue;
+                }
+
+                count += 1;
+                if examples.len() < MAX_EXAMPLES {
+                    examples.push((node.id.clone(), other_node.id.clone(), overlap));
+                }
+            }
+        }
+
+        AnalyzerResult::OverlappingNodes { count, examples }
+    }
+
+    fn estimate_uncompressed_size(&self, nodes: &[NodeInfo]) -> AnalyzerResult {
+        let uncompressed_sizes = nodes
+            .iter()
+            .map(|node| node.point_count * node.point_record_length.unwrap_or_default() as u64)
+            .collect::<Vec<_>>();
+        let total_bytes = uncompressed_sizes.iter().sum::<u64>();
+
+        AnalyzerResult::UncompressedEstimate {
+            total_bytes,
+            mean_bytes_per_node: total_bytes as f64 / nodes.len() as f64,
+            max_bytes_per_node: uncompressed_sizes.iter().copied().max().unwrap_or_default(),
+        }
+    }
+
+    /// Merges the point counts of all files into the given manifest and analyzes the merged point counts
+    fn update_manifest(&self, manifest_file: &Path) -> Result<Vec<AnalyzerResult>> {
+        info!(
+            self.options,
+            "Merging {} files into manifest {}",
+            self.files.len(),
+            manifest_file.display()
+        );
+
+        let nodes = if self.files.is_empty() {
+            vec![]
+        } else {
+            self.read_nodes(None, None)?
+        };
+
+        let mut entries = if manifest_file.exists() {
+            manifest::read_entries(manifest_file)?
+        } else {
+            vec![]
+        };
+        manifest::merge_entries(
+            &mut entries,
+            nodes.into_iter().map(|node| ManifestEntry {
+                id: Some(node.id),
+                point_count: node.point_count as usize,
+            }),
+        );
+        manifest::write_entries(manifest_file, &entries)?;
+
+        let mut points_per_node = entries
+            .iter()
+            .map(|entry| entry.point_count)
+            .collect::<Vec<_>>();
+        points_per_node.sort();
+        Ok(analyze_point_counts(&points_per_node, &self.options))
+    }
+}
+
+/// Builder for a `MultiFileAnalyzer`, as an alternative to filling in `AnalysisOptions` directly:
+/// ```no_run
+/// # use point_cloud_tiles_analyzer::analyzer::*;
+/// # fn main() -> anyhow::Result<()> {
+/// let analyzer = MultiFileAnalyzer::builder()
+///     .root_dir("path/to/ept-data")
+///     .count_nodes(true)
+///     .histogram(HistogramConfig::Linear(10))
+///     .threads(4)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct MultiFileAnalyzerBuilder {
+    root_dirs: Vec<PathBuf>,
+    files: Option<Vec<PathBuf>>,
+    options: AnalysisOptions,
+}
+
+impl MultiFileAnalyzerBuilder {
+    /// Adds a directory that is searched for files. Multiple directories are analyzed as a single dataset
+    pub fn root_dir<P: AsRef<Path>>(mut self, root_dir: P) -> Self {
+        self.root_dirs.push(root_dir.as_ref().to_owned());
+        self
+    }
+
+    /// Analyzes exactly the given files instead of searching directories for files
+    pub fn files(mut self, files: Vec<PathBuf>) -> Self {
+        self.files = Some(files);
+        self
+    }
+
+    /// Replaces all options, e.g. to start from options that were built elsewhere. Options that are set
+    /// afterwards override the corresponding field
+    pub fn options(mut self, options: AnalysisOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Count the number of nodes
+    pub fn count_nodes(mut self, count_nodes: bool) -> Self {
+        self.options.count_nodes = count_nodes;
+        self
+    }
+
+    /// Calculate a histogram of the points per node
+    pub fn histogram(mut self, histogram_config: HistogramConfig) -> Self {
+        self.options.histogram_config = Some(histogram_config);
+        self
+    }
+
+    /// Calculate a histogram of the sizes of the nodes in bytes
+    pub fn size_histogram(mut self, histogram_config: HistogramConfig) -> Self {
+        self.options.size_histogram_config = Some(histogram_config);
+        self
+    }
+
+    /// Estimate quantiles of the points per node with the given relative accuracy
+    pub fn approx_quantiles(mut self, relative_accuracy: f64) -> Self {
+        self.options.approx_quantiles = Some(relative_accuracy);
+        self
+    }
+
+    /// Calculate the Gini coefficient of the points per node
+    pub fn gini(mut self, gini: bool) -> Self {
+        self.options.gini = gini;
+        self
+    }
+
+    /// Calculate the skewness and kurtosis of the points per node
+    pub fn moments(mut self, moments: bool) -> Self {
+        self.options.moments = moments;
+        self
+    }
+
+    /// Calculate the mean and standard deviation of the points per node
+    pub fn stats(mut self, stats: bool) -> Self {
+        self.options.stats = stats;
+        self
+    }
+
+    /// Include information about every node in the results
+    pub fn dump_nodes(mut self, dump_nodes: bool) -> Self {
+        self.options.dump_nodes = dump_nodes;
+        self
+    }
+
+    /// Don't print any progress information
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.options.quiet = quiet;
+        self
+    }
+
+    /// Read the files with the given number of threads instead of one thread per CPU core
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.options.threads = Some(threads);
+        self
+    }
+
+    /// Creates the `MultiFileAnalyzer`, searching the root directories for files unless the files were given
+    /// explicitly
+    pub fn build(self) -> error::Result<MultiFileAnalyzer> {
+        if self.options.threads == Some(0) {
+            return Err(AnalyzerError::InvalidOptions(
+                "The number of threads must be greater than 0!".to_owned(),
+            ));
+        }
+        match self.files {
+            Some(_) if !self.root_dirs.is_empty() => Err(AnalyzerError::InvalidOptions(
+                "Either root directories or files can be analyzed, not both!".to_owned(),
+            )),
+            Some(files) => Ok(MultiFileAnalyzer::from_files(files, self.options)),
+            None if self.root_dirs.is_empty() => Err(AnalyzerError::InvalidOptions(
+                "No root directory or files to analyze!".to_owned(),
+            )),
+            None => MultiFileAnalyzer::from_roots(&self.root_dirs, self.options),
+        }
+    }
+}
+
+impl Analyzer for MultiFileAnalyzer {
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            nodes: "number of LAS/LAZ files found",
+            point_counts: "from the number of point records in the LAS headers (or from EVLRs with --evlr-count)",
+            node_sizes: "from the file sizes on disk",
+            epsg: "from the WKT or GeoTIFF VLRs of the first file",
+        }
+    }
+
+    fn run_with(&self, emit: &mut dyn FnMut(AnalyzerResult)) -> error::Result<()> {
+        let threads = match self.options.threads {
+            Some(threads) => threads,
+            None => return emit_in_order(emit, |emit| self.run_all(emit)),
+        };
+
+        // The analysis runs in a dedicated thread pool, whose threads can't call `emit` directly, so the results are
+        // sent back to the calling thread as soon as they are available
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .context("Could not create the thread pool")?;
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::scope(|scope| {
+            let worker = scope.spawn(move || {
+                pool.install(|| {
+                    let mut send = |result| {
+                        // The receiver lives until the worker is joined, so sending can't fail
+                        let _ = sender.send(result);
+                    };
+                    emit_in_order(&mut send, |emit| self.run_all(emit))
+                })
+            });
+            for result in receiver {
+                emit(result);
+            }
+            worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    }
+}
+
+impl MultiFileAnalyzer {
+    fn run_all(&self, emit: &mut OrderedEmit) -> error::Result<()> {
+        if let Some(manifest_file) = self.options.update_manifest.as_ref() {
+            for result in self.update_manifest(manifest_file)? {
+                emit.push(result);
+            }
+            return Ok(());
+        }
+
+        if self.files.is_empty() {
+            // The search for files was cancelled before it found any, so there are no partial results
+            if self.options.incomplete.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            return Err(AnalyzerError::EmptyDataset(
+                "Found zero files to analyze! Make sure the target directory is not empty!"
+                    .to_owned(),
+            ));
+        }
+
+        // Format-neutral, since the files may come from any dataset or file list. Analyzers for a specific format
+        // print their own banner before
+        info!(self.options, "Analyzing {} files", self.files.len());
+
+        if self.options.requires_epsg() {
+            info!(self.options, "Reading coordinate reference system");
+            emit.push(epsg_result(self.read_epsg()?, &self.options)?);
+        }
+
+        if self.options.count_nodes {
+            info!(self.options, "Counting nodes");
+            emit.push(AnalyzerResult::NodeCount(self.files.len()));
+        }
+        // No later analysis produces a result that comes before the node count or the EPSG code, so both are shown
+        // while the files are read
+        emit.release_up_to(1);
+
+        if self.options.level_count {
+            emit.push(self.count_levels());
+        }
+
+        if self.options.leaf_point_ratio {
+            info!(
+                self.options,
+                "Leaf point ratio is only supported for PotreeConverter v2 datasets, skipping"
+            );
+        }
+
+        if self.options.gsd_check.is_some() {
+            info!(self.options, "Checking the point spacing is only supported for PotreeConverter v2 datasets, skipping");
+        }
+
+        if self.options.max_depth.is_some() {
+            info!(self.options, "Limiting the octree depth is only supported for PotreeConverter v2 datasets, analyzing all nodes");
+        }
+
+        if self.options.record_layout.is_some() {
+            info!(self.options, "Overriding the node record layout is only supported for PotreeConverter v2 datasets, ignoring it");
+        }
+
+        if self.options.infer_record_size {
+            info!(self.options, "Inferring the node record size is only supported for PotreeConverter v2 datasets, skipping");
+        }
+
+        if self.options.trace_nodes.is_some() {
+            info!(
+                self.options,
+                "Tracing node records is only supported for PotreeConverter v2 datasets, skipping"
+            );
+        }
+
+        if self.options.check_hierarchy {
+            info!(
+                self.options,
+                "Checking the hierarchy is only supported for PotreeConverter v2 datasets, skipping"
+            );
+        }
+
+        if self.options.check_children {
+            info!(self.options, "Checking for missing children is only supported for PotreeConverter v2 datasets, skipping");
+        }
+
+        if self.options.round_trip {
+            info!(self.options, "Cross-checking the node count is only supported for PotreeConverter v2 datasets, skipping");
+        }
+
+        if self.options.check_attributes {
+            info!(self.options, "Checking the attributes is only supported for PotreeConverter v2 datasets, skipping");
+        }
+
+        if self.options.mean_fanout {
+            info!(self.options, "Calculating the mean fan-out is only supported for PotreeConverter v2 datasets, skipping");
+        }
+
+        if self.options.level_summary {
+            info!(
+                self.options,
+                "Level summaries are only supported for PotreeConverter v2 datasets, skipping"
+            );
+        }
+
+        if self.options.lod_curve {
+            info!(
+                self.options,
+                "LOD curves are only supported for PotreeConverter v2 datasets, skipping"
+            );
+        }
+
+        if self.options.disk_usage {
+            info!(self.options, "Summing file sizes");
+            emit.push(self.disk_usage()?);
+        }
+
+        if let Some(threshold) = self.options.mtime_stats {
+            info!(self.options, "Collecting modification times");
+            emit.push(self.mtime_stats(threshold));
+        }
+
+        if self.options.find_duplicates {
+            info!(self.options, "Searching for duplicate files");
+            emit.push(self.find_duplicates()?);
+        }
+
+        if self.options.check_copc {
+            info!(self.options, "Checking COPC hierarchies");
+            emit.push(self.check_copc()?);
+        }
+
+        if let Some(stride) = self.options.color_sample {
+            info!(self.options, "Sampling colors of every {}. point", stride);
+            emit.push(self.sample_colors(stride)?);
+        }
 
-        let mut num_points_per_node = num_points_per_node_nested
-            .into_iter()
-            .flatten()
-            .collect::<Vec<_>>();
+        if self.options.use_lax {
+            info!(self.options, "Reading .lax indexes");
+            emit.push(self.lax_cells()?);
+        } else if !self.options.no_hints {
+            let lax_files = self.count_lax_files();
+            if lax_files > 0 {
+                eprintln!(
+                    "Hint: {} of {} files have a .lax spatial index, --use-lax calculates a histogram of the points per spatial cell from them without reading the point data",
+                    lax_files,
+                    self.files.len()
+                );
+            }
+        }
+
+        if self.options.dedup_extensions {
+            const MAX_EXAMPLES: usize = 10;
+            emit.push(AnalyzerResult::CollapsedExtensionPairs {
+                pairs: self.collapsed_files.len(),
+                examples: self
+                    .collapsed_files
+                    .iter()
+                    .take(MAX_EXAMPLES)
+                    .map(|file| file.display().to_string())
+                    .collect(),
+            });
+        }
 
-        // Generate histogram from num_points_per_node
-        num_points_per_node.sort();
+        if let Some(max_file_size) = self.options.max_file_size {
+            emit.push(AnalyzerResult::SkippedFiles {
+                max_file_size,
+                files: self
+                    .skipped_files
+                    .iter()
+                    .map(|(file, file_size)| (file.display().to_string(), *file_size))
+                    .collect(),
+            });
+        }
+
+        if !self.options.requires_point_counts()
+            && !self.options.dump_nodes
+            && !self.options.estimate_uncompressed
+            && !self.options.check_integrity
+            && !self.options.check_overlap
+            && self.options.size_histogram_config.is_none()
+            && self.options.approx_quantiles.is_none()
+            && self.options.write_index.is_none()
+            && self.options.geojson.is_none()
+            && !self.options.trust_data
+        {
+            return Ok(());
+        }
 
-        let histogram = match self.histogram_config.as_ref().unwrap() {
-            HistogramConfig::Linear(buckets) => {
-                lin_histogram(num_points_per_node.as_slice(), *buckets)
+        info!(self.options, "Reading node headers");
+        let quantile_sketch = self
+            .options
+            .approx_quantiles
+            .map(|relative_accuracy| Mutex::new(QuantileSketch::new(relative_accuracy)));
+        let recovered_counts = Mutex::new(vec![]);
+        let (nodes, point_counts, file_sizes) = match self.options.batch_size {
+            Some(batch_size) => {
+                if self.options.dump_nodes
+                    || self.options.check_integrity
+                    || self.options.check_overlap
+                    || self.options.estimate_uncompressed
+                    || self.options.geojson.is_some()
+                {
+                    return Err(AnalyzerError::InvalidOptions("Reading the files in batches only keeps the point count and file size of each node, which is not enough for dumping the nodes, checking the integrity or overlap, estimating the uncompressed size or writing GeoJSON!".to_owned()));
+                }
+                if self.options.warn_above.is_some() {
+                    info!(self.options, "Note: Reading the files in batches doesn't keep the file names, so only the number of oversized nodes is reported, not their files");
+                }
+                // Only the point count and file size of each node are kept, everything else is dropped after each
+                // batch, which bounds the memory usage of large datasets
+                let mut point_counts = vec![];
+                let mut file_sizes = vec![];
+                self.read_node_batches(
+                    batch_size,
+                    quantile_sketch.as_ref(),
+                    Some(&recovered_counts),
+                    &mut |batch| {
+                        point_counts.extend(batch.iter().map(|node| node.point_count));
+                        file_sizes.extend(batch.iter().map(|node| node.file_size));
+                    },
+                )?;
+                (vec![], point_counts, file_sizes)
             }
-            HistogramConfig::Logarithmic(buckets) => {
-                log_histogram(num_points_per_node.as_slice(), *buckets)
+            None => {
+                let nodes = self.read_nodes(quantile_sketch.as_ref(), Some(&recovered_counts))?;
+                let point_counts = nodes.iter().map(|node| node.point_count).collect();
+                let file_sizes = nodes.iter().map(|node| node.file_size).collect();
+                (nodes, point_counts, file_sizes)
             }
         };
+        write_index(point_counts.iter().copied(), &self.options)?;
+
+        if let Some(geojson_file) = self.options.geojson.as_ref() {
+            let epsg = self.read_epsg()?;
+            let feature_count = geojson::write_coverage(geojson_file, &nodes, epsg)?;
+            info!(
+                self.options,
+                "Wrote the footprints of {} nodes to {}",
+                feature_count,
+                geojson_file.display()
+            );
+            if epsg.is_none() {
+                info!(self.options, "Note: The dataset has no EPSG code, so the GeoJSON coordinates are in the native coordinates of the dataset without a CRS");
+            }
+        }
+
+        if self.options.trust_data {
+            let mut files = recovered_counts.into_inner().unwrap();
+            files.sort();
+            emit.push(AnalyzerResult::RecoveredPointCounts { files });
+        }
+
+        if let Some(quantile_sketch) = quantile_sketch {
+            emit.push(approx_quantiles_result(
+                &quantile_sketch.into_inner().unwrap(),
+            ));
+        }
+
+        if self.options.requires_point_counts() {
+            info!(self.options, "Analyzing point counts");
+            for result in self.analyze_point_counts(&point_counts, &nodes) {
+                emit.push(result);
+            }
+        }
+
+        if let Some(size_histogram_config) = self.options.size_histogram_config.as_ref() {
+            info!(self.options, "Analyzing file sizes");
+            emit.push(size_histogram_result(
+                file_sizes.into_iter(),
+                size_histogram_config,
+            ));
+        }
+
+        if self.options.check_integrity {
+            info!(self.options, "Checking point formats");
+            emit.push(self.check_point_formats(&nodes));
+            info!(self.options, "Checking header layouts");
+            emit.push(self.check_header_layouts(&nodes));
+        }
+
+        if self.options.check_overlap {
+            info!(self.options, "Checking for overlapping nodes");
+            emit.push(self.check_overlap(&nodes));
+        }
 
-        Ok(AnalyzerResult::Histogram(histogram))
+        if self.options.estimate_uncompressed {
+            info!(self.options, "Estimating uncompressed size");
+            emit.push(self.estimate_uncompressed_size(&nodes));
+        }
+
+        if self.options.dump_nodes {
+            emit.push(AnalyzerResult::Nodes(dumped_nodes(nodes, &self.options)));
+        }
+
+        Ok(())
     }
 }
 
-impl Analyzer for MultiFileAnalyzer {
-    fn run(&self) -> Result<Vec<AnalyzerResult>> {
-        if self.files.is_empty() {
-            return Err(anyhow!(
-                "Found zero files to analyze! Make sure the target directory is not empty!"
-            ));
+/// Opens the LAS/LAZ file at `file`. With the 'compressed-las' feature, gzip- and zstd-compressed LAS files are
+/// decompressed into memory first, as the LAS reader needs to seek within the file
+fn open_las_file(file: &Path) -> Result<Reader<'static>> {
+    #[cfg(feature = "compressed-las")]
+    {
+        if let Some(decompressed) = decompress_las_file(file)? {
+            return Reader::new(std::io::Cursor::new(decompressed))
+                .with_context(|| format!("Could not read {}", file.display()));
         }
+    }
+    Reader::from_path(file).with_context(|| format!("Could not read {}", file.display()))
+}
 
-        eprintln!("Analyzing {} files in Entwine format", self.files.len());
+/// Returns the decompressed contents of `file` if it is gzip- or zstd-compressed, or `None` if it is not
+#[cfg(feature = "compressed-las")]
+fn decompress_las_file(file: &Path) -> Result<Option<Vec<u8>>> {
+    use std::io::Read as _;
 
-        let mut results = vec![];
-        if self.count_nodes {
-            eprintln!("Counting nodes");
-            results.push(AnalyzerResult::NodeCount(self.files.len()));
+    let open =
+        || std::fs::File::open(file).with_context(|| format!("Could not open {}", file.display()));
+    let mut decoder: Box<dyn std::io::Read> = match file.extension() {
+        Some(extension) if extension == "gz" => Box::new(flate2::read::GzDecoder::new(open()?)),
+        Some(extension) if extension == "zst" => Box::new(zstd::Decoder::new(open()?)?),
+        _ => return Ok(None),
+    };
+    let mut decompressed = vec![];
+    decoder
+        .read_to_end(&mut decompressed)
+        .with_context(|| format!("Could not decompress {}", file.display()))?;
+    Ok(Some(decompressed))
+}
+
+/// Reads the EPSG code of the coordinate reference system from the header of the LAS/LAZ file at `file`
+fn read_file_epsg(file: &Path) -> Result<Option<u32>> {
+    let reader = open_las_file(file)?;
+    let header = reader.header();
+    Ok(crs::epsg_from_vlrs(
+        header.vlrs().iter().chain(header.evlrs().iter()),
+    ))
+}
+
+/// Analyzer for point counts that were read from a manifest or an index instead of the dataset itself
+pub struct ManifestAnalyzer {
+    /// Sorted point counts of all nodes
+    points_per_node: Vec<usize>,
+    options: AnalysisOptions,
+}
+
+impl ManifestAnalyzer {
+    /// Creates a new `ManifestAnalyzer` for the manifest file at `manifest_file`
+    pub fn new<P: AsRef<Path>>(manifest_file: P, options: AnalysisOptions) -> error::Result<Self> {
+        let mut points_per_node = manifest::read_point_counts(manifest_file)?;
+        sort_point_counts(&mut points_per_node, options.assume_sorted);
+        Ok(Self {
+            points_per_node,
+            options,
+        })
+    }
+
+    /// Creates a new `ManifestAnalyzer` for the point counts in the index file at `index_file`, which was written
+    /// with the `write_index` option
+    pub fn from_index<P: AsRef<Path>>(
+        index_file: P,
+        options: AnalysisOptions,
+    ) -> error::Result<Self> {
+        let mut points_per_node = index::read_point_counts(index_file)?;
+        sort_point_counts(&mut points_per_node, options.assume_sorted);
+        Ok(Self {
+            points_per_node,
+            options,
+        })
+    }
+}
+
+impl Analyzer for ManifestAnalyzer {
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            nodes: "number of entries in the manifest or index",
+            point_counts: "from the manifest or index",
+            node_sizes: "unknown",
+            epsg: "unknown",
+        }
+    }
+
+    fn run_with(&self, emit: &mut dyn FnMut(AnalyzerResult)) -> error::Result<()> {
+        emit_in_order(emit, |emit| {
+            info!(
+                self.options,
+                "Analyzing manifest with {} nodes",
+                self.points_per_node.len()
+            );
+            write_index(
+                self.points_per_node.iter().map(|&count| count as u64),
+                &self.options,
+            )?;
+
+            for result in analyze_point_counts(self.points_per_node.as_slice(), &self.options) {
+                emit.push(result);
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Analyzer for Entwine datasets whose node files are not LAS/LAZ files but binary or zstandard-compressed chunks.
+/// The point counts are read from the hierarchy of the dataset, so the node files themselves are never opened
+pub struct EptHierarchyAnalyzer {
+    /// Key ('D-X-Y-Z') and point count of each node
+    nodes: Vec<(String, u64)>,
+    options: AnalysisOptions,
+}
+
+impl EptHierarchyAnalyzer {
+    /// Creates a new `EptHierarchyAnalyzer` for the Entwine dataset in `root_dir`
+    pub fn new<P: AsRef<Path>>(root_dir: P, options: AnalysisOptions) -> error::Result<Self> {
+        let nodes = ept::read_hierarchy_point_counts(root_dir)?;
+        Ok(Self { nodes, options })
+    }
+}
+
+impl Analyzer for EptHierarchyAnalyzer {
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            nodes: "number of nodes with points in the JSON files of ept-hierarchy",
+            point_counts: "from the JSON files of ept-hierarchy",
+            node_sizes: "unknown",
+            epsg: "unknown",
+        }
+    }
+
+    fn run_with(&self, emit: &mut dyn FnMut(AnalyzerResult)) -> error::Result<()> {
+        emit_in_order(emit, |emit| {
+            info!(
+                self.options,
+                "Analyzing Entwine hierarchy with {} nodes",
+                self.nodes.len()
+            );
+
+            if self.options.count_nodes {
+                emit.push(AnalyzerResult::NodeCount(self.nodes.len()));
+            }
+            emit.release_up_to(1);
+
+            if self.options.level_count {
+                emit.push(level_count_result(
+                    self.nodes
+                        .iter()
+                        .filter_map(|(key, _)| NodeKey::from_path(key))
+                        .map(|key| key.depth()),
+                ));
+            }
+
+            if self.options.leaf_point_ratio
+                || self.options.gsd_check.is_some()
+                || self.options.estimate_uncompressed
+                || self.options.disk_usage
+                || self.options.check_integrity
+                || self.options.check_overlap
+                || self.options.check_hierarchy
+                || self.options.check_children
+                || self.options.round_trip
+                || self.options.check_attributes
+                || self.options.mean_fanout
+                || self.options.level_summary
+                || self.options.lod_curve
+                || self.options.color_sample.is_some()
+                || self.options.use_lax
+                || self.options.geojson.is_some()
+                || self.options.mtime_stats.is_some()
+                || self.options.find_duplicates
+                || self.options.check_copc
+                || self.options.size_histogram_config.is_some()
+                || self.options.approx_quantiles.is_some()
+                || self.options.requires_epsg()
+            {
+                info!(self.options, "Only node counts, analyses of the point counts and node dumps are supported for Entwine datasets with binary or zstandard node files, skipping all other analyses");
+            }
+
+            write_index(
+                self.nodes.iter().map(|(_, point_count)| *point_count),
+                &self.options,
+            )?;
+
+            if self.options.requires_point_counts() {
+                let mut points_per_node = self
+                    .nodes
+                    .iter()
+                    .map(|(_, point_count)| *point_count as usize)
+                    .collect::<Vec<_>>();
+                sort_point_counts(&mut points_per_node, self.options.assume_sorted);
+                for result in point_count_results(points_per_node.as_slice(), &self.options) {
+                    emit.push(result);
+                }
+            }
+
+            if self.options.dump_nodes {
+                let nodes = self
+                    .nodes
+                    .iter()
+                    .map(|(key, point_count)| NodeInfo {
+                        id: key.clone(),
+                        point_count: *point_count,
+                        file_size: 0,
+                        bounds: None,
+                        point_record_length: None,
+                        point_format: None,
+                        header_size: None,
+                        point_data_offset: None,
+                    })
+                    .collect();
+                emit.push(AnalyzerResult::Nodes(dumped_nodes(nodes, &self.options)));
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Analyzer for virtual point clouds (.vpc files) of PDAL, which list the point count and bounds of all files of a
+/// dataset in a single index file
+pub struct VpcAnalyzer {
+    entries: Vec<VpcEntry>,
+    options: AnalysisOptions,
+}
+
+impl VpcAnalyzer {
+    /// Creates a new `VpcAnalyzer` for the VPC file at `vpc_file`
+    pub fn new<P: AsRef<Path>>(vpc_file: P, options: AnalysisOptions) -> error::Result<Self> {
+        let entries = vpc::read_entries(vpc_file)?;
+        Ok(Self { entries, options })
+    }
+
+    /// Takes the point counts from the index. Only the files for which the index has no point count are opened
+    fn read_nodes(&self) -> Result<Vec<NodeInfo>> {
+        self.entries
+            .par_iter()
+            .map(|entry| -> Result<NodeInfo> {
+                let point_count = match entry.point_count {
+                    Some(point_count) => point_count,
+                    None => open_las_file(&entry.file)?.header().number_of_points(),
+                };
+                let to_vector = |coordinates: [f64; 3]| Vector {
+                    x: coordinates[0],
+                    y: coordinates[1],
+                    z: coordinates[2],
+                };
+                Ok(NodeInfo {
+                    id: entry.file.display().to_string(),
+                    point_count,
+                    file_size: std::fs::metadata(&entry.file)
+                        .map(|meta| meta.len())
+                        .unwrap_or_default(),
+                    bounds: entry.bounds.map(|(min, max)| Bounds {
+                        min: to_vector(min),
+                        max: to_vector(max),
+                    }),
+                    point_record_length: None,
+                    point_format: None,
+                    header_size: None,
+                    point_data_offset: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Takes the EPSG code from the index if possible, otherwise reads it from the first file
+    fn read_epsg(&self) -> Result<Option<u32>> {
+        match self.entries.iter().find_map(|entry| entry.epsg) {
+            Some(epsg) => Ok(Some(epsg)),
+            None => read_file_epsg(&self.entries[0].file),
+        }
+    }
+}
+
+impl Analyzer for VpcAnalyzer {
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            nodes: "number of files listed in the .vpc file",
+            point_counts:
+                "from 'pc:count' in the .vpc file, or from the LAS headers if it is missing",
+            node_sizes: "from the file sizes on disk",
+            epsg: "from 'proj:epsg' in the .vpc file, or from the VLRs of the first file",
+        }
+    }
+
+    fn run_with(&self, emit: &mut dyn FnMut(AnalyzerResult)) -> error::Result<()> {
+        emit_in_order(emit, |emit| {
+            if self.entries.is_empty() {
+                return Err(AnalyzerError::EmptyDataset(
+                    "Virtual point cloud contains zero files!".to_owned(),
+                ));
+            }
+
+            info!(
+                self.options,
+                "Analyzing virtual point cloud with {} files",
+                self.entries.len()
+            );
+
+            if self.options.requires_epsg() {
+                emit.push(epsg_result(self.read_epsg()?, &self.options)?);
+            }
+
+            if self.options.count_nodes {
+                emit.push(AnalyzerResult::NodeCount(self.entries.len()));
+            }
+            emit.release_up_to(1);
+
+            if self.options.leaf_point_ratio
+                || self.options.gsd_check.is_some()
+                || self.options.estimate_uncompressed
+                || self.options.disk_usage
+                || self.options.check_integrity
+                || self.options.check_overlap
+                || self.options.check_hierarchy
+                || self.options.check_children
+                || self.options.round_trip
+                || self.options.check_attributes
+                || self.options.mean_fanout
+                || self.options.level_summary
+                || self.options.lod_curve
+                || self.options.level_count
+                || self.options.color_sample.is_some()
+                || self.options.use_lax
+                || self.options.geojson.is_some()
+                || self.options.mtime_stats.is_some()
+                || self.options.find_duplicates
+                || self.options.check_copc
+                || self.options.size_histogram_config.is_some()
+                || self.options.approx_quantiles.is_some()
+            {
+                info!(self.options, "Only node counts, analyses of the point counts, EPSG codes and node dumps are supported for virtual point clouds, skipping all other analyses");
+            }
+
+            if !self.options.requires_point_counts()
+                && !self.options.dump_nodes
+                && self.options.write_index.is_none()
+            {
+                return Ok(());
+            }
+
+            let nodes = self.read_nodes()?;
+            write_index(nodes.iter().map(|node| node.point_count), &self.options)?;
+            if self.options.requires_point_counts() {
+                let mut points_per_node = nodes
+                    .iter()
+                    .map(|node| node.point_count as usize)
+                    .collect::<Vec<_>>();
+                sort_point_counts(&mut points_per_node, self.options.assume_sorted);
+                for result in point_count_results(points_per_node.as_slice(), &self.options) {
+                    emit.push(result);
+                }
+            }
+
+            if self.options.dump_nodes {
+                emit.push(AnalyzerResult::Nodes(dumped_nodes(nodes, &self.options)));
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// A node of a Schwarzwald dataset, with its octree level parsed from the name of its file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchwarzwaldNode {
+    /// Name of the node, e.g. 'r0426'
+    pub name: String,
+    /// Octree level of the node, 0 for the root node
+    pub level: u32,
+    /// Path of the LAS/LAZ file of the node
+    pub path: PathBuf,
+}
+
+/// Analyzer for datasets of Schwarzwald, which writes one LAS/LAZ file per node next to a metadata JSON file in the
+/// root directory. Unlike the flat `ept-data` directory of Entwine, the node files are spread over nested
+/// directories and named after the node like by PotreeConverter ('r' for the root, followed by the index of the
+/// child on each level), so the level of each node is taken from the name of its file. The node files themselves are
+/// analyzed like those of any other format with one file per node
+pub struct SchwarzwaldAnalyzer {
+    files: MultiFileAnalyzer,
+    nodes: Vec<SchwarzwaldNode>,
+}
+
+impl SchwarzwaldAnalyzer {
+    /// Creates a new `SchwarzwaldAnalyzer` for the dataset in `root_dir`, searching all of its subdirectories for
+    /// node files
+    pub fn new<P: AsRef<Path>>(root_dir: P, options: AnalysisOptions) -> error::Result<Self> {
+        let files = MultiFileAnalyzer::new(root_dir, options)?;
+        let nodes = files
+            .files
+            .iter()
+            .filter_map(|path| match NodeKey::from_path(path)? {
+                NodeKey::Potree(name) => Some(SchwarzwaldNode {
+                    level: (name.len() - 1) as u32,
+                    name,
+                    path: path.clone(),
+                }),
+                NodeKey::Entwine { .. } => None,
+            })
+            .collect::<Vec<_>>();
+        if nodes.len() < files.files.len() {
+            eprintln!(
+                "{} files are not named after an octree node, their level is unknown",
+                files.files.len() - nodes.len()
+            );
         }
+        Ok(Self { files, nodes })
+    }
+
+    /// All node files whose name follows the node naming convention, together with their level
+    pub fn nodes(&self) -> &[SchwarzwaldNode] {
+        &self.nodes
+    }
+}
 
-        if self.histogram_config.is_some() {
-            eprintln!("Calculating histogram");
-            let histogram = self.calculate_histogram()?;
-            results.push(histogram);
+impl Analyzer for SchwarzwaldAnalyzer {
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            nodes: "number of LAS/LAZ files in the root directory and all of its subdirectories",
+            ..self.files.provenance()
         }
+    }
 
-        return Ok(results);
+    fn run_with(&self, emit: &mut dyn FnMut(AnalyzerResult)) -> error::Result<()> {
+        info!(
+            self.files.options,
+            "Analyzing Schwarzwald dataset with {} nodes on {} levels",
+            self.files.files.len(),
+            self.nodes
+                .iter()
+                .map(|node| node.level)
+                .collect::<BTreeSet<_>>()
+                .len()
+        );
+        self.files.run_with(emit)
     }
 }
 
 /// Analyzer for the file format of PotreeConverter v2
 pub struct PotreeV2FormatAnalyzer {
     hierarchy_file: PathBuf,
-    count_nodes: bool,
-    histogram_config: Option<HistogramConfig>,
+    metadata_file: PathBuf,
+    record_layout: RecordLayout,
+    /// All layouts that were tried if the record layout was inferred from the hierarchy
+    layout_candidates: Option<Vec<LayoutCandidate>>,
+    options: AnalysisOptions,
 }
 
 impl PotreeV2FormatAnalyzer {
-    pub fn new<P: AsRef<Path>>(
-        root_dir: P,
-        count_nodes: bool,
-        histogram_config: Option<HistogramConfig>,
-    ) -> Result<Self> {
+    pub fn new<P: AsRef<Path>>(root_dir: P, options: AnalysisOptions) -> error::Result<Self> {
         if !root_dir.as_ref().exists() {
-            return Err(anyhow!(
-                "root directory {} does not exist!",
-                root_dir.as_ref().display()
-            ));
+            return Err(AnalyzerError::RootDirNotFound(root_dir.as_ref().to_owned()));
         }
 
         let hierarchy_file = root_dir.as_ref().to_owned().join("hierarchy.bin");
         if !hierarchy_file.exists() {
-            return Err(anyhow!("hierarchy.bin file does not exist!",));
+            return Err(AnalyzerError::FileNotFound(hierarchy_file));
         }
 
+        let metadata_file = root_dir.as_ref().join("metadata.json");
+        let (record_layout, layout_candidates) = match options.record_layout {
+            Some(record_layout) => (record_layout, None),
+            None if options.infer_record_size => {
+                let hierarchy_bytes = std::fs::read(&hierarchy_file)
+                    .with_context(|| format!("Could not read {}", hierarchy_file.display()))?;
+                let (record_layout, candidates) = potree_v2::infer_record_layout(&hierarchy_bytes)?;
+                (record_layout, Some(candidates))
+            }
+            None => (RecordLayout::from_metadata_file(&metadata_file)?, None),
+        };
+
         Ok(Self {
             hierarchy_file,
-            count_nodes,
-            histogram_config,
+            metadata_file,
+            record_layout,
+            layout_candidates,
+            options,
         })
     }
-}
-
-impl Analyzer for PotreeV2FormatAnalyzer {
-    fn run(&self) -> Result<Vec<AnalyzerResult>> {
-        eprintln!("Analyzing dataset in PotreeConverter v2 format");
 
-        let mut results = vec![];
+    /// Walks the hierarchy, keeping only the nodes up to the maximum depth and with the node prefix. When checking for
+    /// missing children, missing nodes are skipped, so that the rest of a truncated hierarchy can still be analyzed
+    fn walk_hierarchy(&self, hierarchy_bytes: &[u8]) -> Result<Vec<OctreeNode>> {
+        let octree_nodes = if self.options.check_children {
+            potree_v2::walk_hierarchy_lenient(
+                hierarchy_bytes,
+                self.record_layout,
+                self.options.max_depth,
+            )?
+            .nodes
+        } else {
+            potree_v2::walk_hierarchy(hierarchy_bytes, self.record_layout, self.options.max_depth)?
+        };
+        match self.options.node_prefix.as_ref() {
+            Some(node_prefix) => Ok(octree_nodes
+                .into_iter()
+                .filter(|node| node.name.starts_with(node_prefix.as_str()))
+                .collect()),
+            None => Ok(octree_nodes),
+        }
+    }
 
-        if !self.count_nodes && self.histogram_config.is_none() {
-            return Ok(results);
+    /// Finds all nodes that are referenced by the child mask of their parent or by a proxy node, but whose record is
+    /// missing from the hierarchy
+    fn check_children(&self, hierarchy_bytes: &[u8]) -> Result<AnalyzerResult> {
+        let mut missing = potree_v2::walk_hierarchy_lenient(
+            hierarchy_bytes,
+            self.record_layout,
+            self.options.max_depth,
+        )?
+        .missing;
+        if let Some(node_prefix) = self.options.node_prefix.as_ref() {
+            missing.retain(|name| name.starts_with(node_prefix.as_str()));
         }
+        Ok(AnalyzerResult::MissingChildren { missing })
+    }
+
+    /// Finds all nodes that contain more points than their parent node. Inner nodes are subsampled, so counts differ
+    /// between levels, but a child node with more points than its parent often indicates a decoding or structural
+    /// error
+    fn check_hierarchy(&self, hierarchy_bytes: &[u8]) -> Result<AnalyzerResult> {
+        let octree_nodes = self.walk_hierarchy(hierarchy_bytes)?;
+        let points_per_node = octree_nodes
+            .iter()
+            .map(|node| (node.name.as_str(), node.record.num_points))
+            .collect::<HashMap<_, _>>();
+
+        let anomalies = octree_nodes
+            .iter()
+            .filter(|node| node.level > 0)
+            .filter_map(|node| {
+                let parent_name = &node.name[..node.name.len() - 1];
+                // The parent is missing if it is outside of the node prefix
+                let parent_points = *points_per_node.get(parent_name)?;
+                if node.record.num_points > parent_points {
+                    Some((node.name.clone(), node.record.num_points, parent_points))
+                } else {
+                    None
+                }
+            })
+            .collect();
 
-        let mut reader = BufReader::new(File::open(&self.hierarchy_file)?);
-        let mut bytes = vec![];
-        std::io::Read::read_to_end(&mut reader, &mut bytes)?;
+        Ok(AnalyzerResult::HierarchyAnomalies { anomalies })
+    }
 
-        let size_of_node = 22;
-        if bytes.len() % size_of_node != 0 {
+    /// Finds all nodes whose byte size differs from their point count times the size of a point according to the
+    /// attribute schema, which indicates partially written nodes or nodes that were written with a different
+    /// schema. Compressed points have no fixed size, so the check is skipped for them
+    fn check_attributes(&self, hierarchy_bytes: &[u8]) -> Result<Option<AnalyzerResult>> {
+        let metadata = PotreeV2Metadata::from_file(&self.metadata_file)?;
+        if !metadata.is_uncompressed() {
+            info!(
+                self.options,
+                "Checking the attributes is not supported for points with {} encoding, skipping",
+                metadata.encoding
+            );
+            return Ok(None);
+        }
+        if metadata.attributes.is_empty() {
             return Err(anyhow!(
-                "File size of hierarchy.bin must be a multiple of {}!",
-                size_of_node
+                "metadata.json contains no point attributes, can't check the attributes!"
             ));
         }
 
-        let num_node_entries = bytes.len() / size_of_node;
-        let valid_node_indices = (0..num_node_entries)
-            .filter(|idx| bytes[idx * size_of_node] != 2 || bytes[idx * size_of_node + 1] == 0)
+        let bytes_per_point = metadata.bytes_per_point();
+        let mismatches = self
+            .walk_hierarchy(hierarchy_bytes)?
+            .into_iter()
+            .filter_map(|node| {
+                let expected_size = node.record.num_points * bytes_per_point;
+                if node.record.byte_size == expected_size {
+                    None
+                } else {
+                    Some((node.name, node.record.byte_size, expected_size))
+                }
+            })
+            .collect();
+
+        Ok(Some(AnalyzerResult::AttributeMismatches {
+            bytes_per_point,
+            mismatches,
+        }))
+    }
+
+    /// Counts the nodes of the whole hierarchy in two independent ways: By scanning all records for valid nodes and
+    /// by following the child masks from the root node. Both counts must agree, a mismatch indicates a bug in the
+    /// decoder or a corrupt hierarchy. The depth limit and node prefix are ignored, as they only apply to the walk
+    fn cross_check_node_count(&self, hierarchy_bytes: &[u8]) -> Result<AnalyzerResult> {
+        let flat = hierarchy_bytes
+            .chunks_exact(self.record_layout.size())
+            .map(|record_bytes| PotreeV2Node::from_bytes(record_bytes, self.record_layout))
+            .filter(PotreeV2Node::is_valid)
+            .count();
+        let walked = potree_v2::walk_hierarchy(hierarchy_bytes, self.record_layout, None)?.len();
+        Ok(AnalyzerResult::CountCrossCheck { flat, walked })
+    }
+
+    /// Estimates the point spacing of each level as the side length of its nodes divided by the square root of
+    /// the mean number of points per node, assuming that the points of a node are spread over a surface. The
+    /// deepest levels store all remaining points, so they can legitimately be denser than expected
+    fn check_gsd(&self, hierarchy_bytes: &[u8], tolerance: f64) -> Result<AnalyzerResult> {
+        let metadata = PotreeV2Metadata::from_file(&self.metadata_file)?;
+        let root_side_length = metadata.bounding_box.max[0] - metadata.bounding_box.min[0];
+
+        let octree_nodes = self.walk_hierarchy(hierarchy_bytes)?;
+        let max_level = octree_nodes
+            .iter()
+            .map(|node| node.level)
+            .max()
+            .unwrap_or_default();
+        let mut nodes_per_level = vec![0_u64; max_level as usize + 1];
+        let mut points_per_level = vec![0_u64; max_level as usize + 1];
+        for node in octree_nodes.iter() {
+            nodes_per_level[node.level as usize] += 1;
+            points_per_level[node.level as usize] += node.record.num_points;
+        }
+
+        let levels = (0..=max_level)
+            .filter(|&level| points_per_level[level as usize] > 0)
+            .map(|level| {
+                let scale = 2.0_f64.powi(level as i32);
+                let expected_spacing = metadata.spacing / scale;
+                let mean_points_per_node = points_per_level[level as usize] as f64
+                    / nodes_per_level[level as usize] as f64;
+                let estimated_spacing = (root_side_length / scale) / mean_points_per_node.sqrt();
+                GsdLevel {
+                    level,
+                    expected_spacing,
+                    estimated_spacing,
+                    deviates: (estimated_spacing / expected_spacing - 1.0).abs() > tolerance,
+                }
+            })
+            .collect();
+
+        Ok(AnalyzerResult::GsdConsistency {
+            spacing: metadata.spacing,
+            tolerance,
+            levels,
+        })
+    }
+
+    /// Calculates the number of nodes and points of each octree level, including levels without points
+    fn level_summary(&self, hierarchy_bytes: &[u8]) -> Result<AnalyzerResult> {
+        let octree_nodes = self.walk_hierarchy(hierarchy_bytes)?;
+        let max_level = match octree_nodes.iter().map(|node| node.level).max() {
+            Some(max_level) => max_level,
+            None => return Ok(AnalyzerResult::LevelSummary(vec![])),
+        };
+
+        let mut levels = (0..=max_level)
+            .map(|level| LevelRow {
+                level,
+                node_count: 0,
+                point_count: 0,
+                mean_points_per_node: 0.0,
+                point_share: 0.0,
+            })
             .collect::<Vec<_>>();
+        for node in octree_nodes.iter() {
+            let row = &mut levels[node.level as usize];
+            row.node_count += 1;
+            row.point_count += node.record.num_points;
+        }
+
+        let total_points: u64 = levels.iter().map(|row| row.point_count).sum();
+        for row in levels.iter_mut() {
+            if row.node_count > 0 {
+                row.mean_points_per_node = row.point_count as f64 / row.node_count as f64;
+            }
+            if total_points > 0 {
+                row.point_share = row.point_count as f64 / total_points as f64;
+            }
+        }
+
+        Ok(AnalyzerResult::LevelSummary(levels))
+    }
+
+    /// Calculates the cumulative number of points of all levels up to each octree level, including levels without
+    /// points
+    fn lod_curve(&self, hierarchy_bytes: &[u8]) -> Result<AnalyzerResult> {
+        let octree_nodes = self.walk_hierarchy(hierarchy_bytes)?;
+        let max_level = match octree_nodes.iter().map(|node| node.level).max() {
+            Some(max_level) => max_level,
+            None => return Ok(AnalyzerResult::LodCurve(vec![])),
+        };
+
+        let mut points_per_level = vec![0_u64; max_level as usize + 1];
+        for node in octree_nodes.iter() {
+            points_per_level[node.level as usize] += node.record.num_points;
+        }
+
+        let curve = (0..=max_level)
+            .zip(points_per_level.iter())
+            .scan(0_u64, |cumulative_points, (level, points)| {
+                *cumulative_points += points;
+                Some((level, *cumulative_points))
+            })
+            .collect();
+        Ok(AnalyzerResult::LodCurve(curve))
+    }
+}
+
+/// Options that only apply to datasets with one file per node, which PotreeConverter v2 datasets don't have
+const POTREE_V2_UNSUPPORTED_OPTIONS: &[UnsupportedOption] = &[
+    skipped(|options| options.check_integrity, "Checking the integrity"),
+    skipped(
+        |options| options.estimate_uncompressed,
+        "Estimating the uncompressed size",
+    ),
+    UnsupportedOption {
+        is_set: |options| options.modified_since.is_some() || options.update_manifest.is_some(),
+        description: "Incremental analysis",
+        fallback: "analyzing the whole dataset",
+    },
+    skipped(
+        |options| options.check_overlap,
+        "Checking for overlapping nodes",
+    ),
+    UnsupportedOption {
+        is_set: |options| options.max_file_size.is_some(),
+        description: "Skipping large files",
+        fallback: "analyzing all nodes",
+    },
+    skipped(
+        |options| options.dedup_extensions,
+        "Collapsing .las/.laz pairs",
+    ),
+    skipped(|options| options.color_sample.is_some(), "Sampling colors"),
+    skipped(|options| options.use_lax, "Reading .lax indexes"),
+    UnsupportedOption {
+        is_set: |options| options.threads.is_some(),
+        description: "Limiting the number of threads",
+        fallback: "using all cores",
+    },
+    skipped(
+        |options| options.mtime_stats.is_some(),
+        "Collecting modification time statistics",
+    ),
+    skipped(|options| options.check_copc, "Checking COPC hierarchies"),
+    skipped(
+        |options| options.find_duplicates,
+        "Searching for duplicate files",
+    ),
+    UnsupportedOption {
+        is_set: |options| options.batch_size.is_some(),
+        description: "Reading files in batches",
+        fallback: "ignoring the batch size",
+    },
+    skipped(
+        |options| options.geojson.is_some(),
+        "Writing node footprints as GeoJSON",
+    ),
+    UnsupportedOption {
+        is_set: |options| options.trust_data,
+        description: "Recovering point counts from the file sizes",
+        fallback: "using the counts of hierarchy.bin",
+    },
+    UnsupportedOption {
+        is_set: |options| !options.exclude.is_empty(),
+        description: "Excluding files",
+        fallback: "analyzing all nodes",
+    },
+    UnsupportedOption {
+        is_set: |options| options.no_recurse || options.walk_max_depth.is_some(),
+        description: "Limiting the directory depth",
+        fallback: "analyzing all nodes",
+    },
+    UnsupportedOption {
+        is_set: |options| options.evlr_count.is_some(),
+        description: "Reading point counts from EVLRs",
+        fallback: "using the hierarchy",
+    },
+    UnsupportedOption {
+        is_set: |options| options.count_from_name.is_some(),
+        description: "Reading point counts from file names",
+        fallback: "using the hierarchy",
+    },
+    skipped(|options| options.work_stats, "Collecting work statistics"),
+];
 
-        if self.count_nodes {
-            results.push(AnalyzerResult::NodeCount(valid_node_indices.len()));
+impl Analyzer for PotreeV2FormatAnalyzer {
+    fn provenance(&self) -> Provenance {
+        Provenance {
+            nodes: "nodes reachable from the root node of hierarchy.bin, following proxy nodes into all hierarchy chunks",
+            point_counts: "from the node records in hierarchy.bin",
+            node_sizes: "from the byte sizes of the node records in hierarchy.bin",
+            epsg: "parsed from the WKT projection in metadata.json",
         }
+    }
+
+    fn run_with(&self, emit: &mut dyn FnMut(AnalyzerResult)) -> error::Result<()> {
+        emit_in_order(emit, |emit| {
+            info!(
+                self.options,
+                "Analyzing dataset in PotreeConverter v2 format"
+            );
+
+            if let Some(candidates) = self.layout_candidates.as_ref() {
+                emit.push(AnalyzerResult::InferredRecordLayout {
+                    record_size: self.record_layout.size(),
+                    point_count_offset: self.record_layout.point_count_offset(),
+                    point_count_bytes: self.record_layout.point_count_bytes(),
+                    rejected: candidates
+                        .iter()
+                        .filter_map(|candidate| {
+                            Some((candidate.layout.size(), candidate.rejection.clone()?))
+                        })
+                        .collect(),
+                });
+            }
+
+            if self.options.requires_epsg() {
+                let metadata = PotreeV2Metadata::from_file(&self.metadata_file)?;
+                emit.push(epsg_result(
+                    crs::epsg_from_wkt(&metadata.projection),
+                    &self.options,
+                )?);
+            }
+
+            if self.options.disk_usage {
+                let root_dir = self
+                    .hierarchy_file
+                    .parent()
+                    .unwrap_or_else(|| Path::new("."));
+                emit.push(disk_usage_result(&[root_dir], |path| {
+                    path.file_name().map_or(false, |name| name == "octree.bin")
+                })?);
+            }
+
+            if !self.options.count_nodes
+                && !self.options.requires_point_counts()
+                && !self.options.leaf_point_ratio
+                && !self.options.dump_nodes
+                && self.options.gsd_check.is_none()
+                && !self.options.check_hierarchy
+                && !self.options.check_children
+                && !self.options.round_trip
+                && !self.options.check_attributes
+                && !self.options.mean_fanout
+                && !self.options.level_summary
+                && !self.options.lod_curve
+                && !self.options.level_count
+                && self.options.size_histogram_config.is_none()
+                && self.options.approx_quantiles.is_none()
+                && self.options.write_index.is_none()
+                && self.options.trace_nodes.is_none()
+            {
+                return Ok(());
+            }
 
-        if self.histogram_config.is_some() {
-            let mut points_per_node = vec![];
-            for node_idx in valid_node_indices.iter() {
-                let offset_to_size_in_bytes = (node_idx * size_of_node) + 2;
-                let point_count_of_node = u32::from_le_bytes([
-                    bytes[offset_to_size_in_bytes],
-                    bytes[offset_to_size_in_bytes + 1],
-                    bytes[offset_to_size_in_bytes + 2],
-                    bytes[offset_to_size_in_bytes + 3],
-                ]);
-                points_per_node.push(point_count_of_node as usize);
+            if self.options.stop_requested() {
+                return Ok(());
             }
-            points_per_node.sort();
+            let bytes = potree_v2::read_hierarchy_bytes(&self.hierarchy_file, self.record_layout)?;
 
-            let histogram = match self.histogram_config.as_ref().unwrap() {
-                HistogramConfig::Linear(buckets) => {
-                    lin_histogram(points_per_node.as_slice(), *buckets)
+            if let Some(num_traced_nodes) = self.options.trace_nodes {
+                for (idx, record_bytes) in bytes
+                    .chunks_exact(self.record_layout.size())
+                    .take(num_traced_nodes)
+                    .enumerate()
+                {
+                    let node = PotreeV2Node::from_bytes(record_bytes, self.record_layout);
+                    eprintln!(
+                    "node {} at offset {}: type {}, child mask {:#010b}, {} points, byte offset {}, byte size {}",
+                    idx,
+                    idx * self.record_layout.size(),
+                    node.node_type,
+                    node.child_mask,
+                    node.num_points,
+                    node.byte_offset,
+                    node.byte_size
+                );
                 }
-                HistogramConfig::Logarithmic(buckets) => {
-                    log_histogram(points_per_node.as_slice(), *buckets)
+            }
+
+            // Large hierarchies are split into several chunks, which are only found by following the proxy nodes
+            let valid_nodes = self
+                .walk_hierarchy(&bytes)?
+                .into_iter()
+                .map(|node| node.record)
+                .collect::<Vec<_>>();
+
+            if let Some(node_prefix) = self.options.node_prefix.as_ref() {
+                info!(
+                    self.options,
+                    "{} nodes match the node prefix {}",
+                    valid_nodes.len(),
+                    node_prefix
+                );
+            }
+
+            write_index(
+                valid_nodes.iter().map(|node| node.num_points),
+                &self.options,
+            )?;
+
+            if self.options.count_nodes {
+                emit.push(AnalyzerResult::NodeCount(valid_nodes.len()));
+            }
+            emit.release_up_to(1);
+
+            if self.options.requires_point_counts() {
+                let mut points_per_node = valid_nodes
+                    .iter()
+                    .map(|node| node.num_points as usize)
+                    .collect::<Vec<_>>();
+                sort_point_counts(&mut points_per_node, self.options.assume_sorted);
+                for result in point_count_results(points_per_node.as_slice(), &self.options) {
+                    emit.push(result);
                 }
-            };
-            results.push(AnalyzerResult::Histogram(histogram));
-        }
+            }
 
-        Ok(results)
+            if let Some(relative_accuracy) = self.options.approx_quantiles {
+                let mut quantile_sketch = QuantileSketch::new(relative_accuracy);
+                for node in valid_nodes.iter() {
+                    if in_count_range(node.num_points, &self.options) {
+                        quantile_sketch.insert(node.num_points);
+                    }
+                }
+                emit.push(approx_quantiles_result(&quantile_sketch));
+            }
+
+            if let Some(size_histogram_config) = self.options.size_histogram_config.as_ref() {
+                emit.push(size_histogram_result(
+                    valid_nodes.iter().map(|node| node.byte_size),
+                    size_histogram_config,
+                ));
+            }
+
+            print_unsupported_notes(
+                &self.options,
+                POTREE_V2_UNSUPPORTED_OPTIONS,
+                "for PotreeConverter v2 datasets",
+            );
+
+            if self.options.leaf_point_ratio {
+                let (leaf_nodes, inner_nodes): (Vec<_>, Vec<_>) =
+                    valid_nodes.iter().partition(|node| node.is_leaf());
+                let sum_points =
+                    |nodes: Vec<&PotreeV2Node>| nodes.iter().map(|node| node.num_points).sum();
+                emit.push(AnalyzerResult::LeafPointRatio {
+                    leaf_points: sum_points(leaf_nodes),
+                    inner_points: sum_points(inner_nodes),
+                });
+            }
+
+            // Each of the following checks walks the whole hierarchy again, so they are skipped once the analysis is
+            // cancelled
+            if let Some(tolerance) = self
+                .options
+                .gsd_check
+                .filter(|_| !self.options.stop_requested())
+            {
+                emit.push(self.check_gsd(&bytes, tolerance)?);
+            }
+
+            if self.options.check_hierarchy && !self.options.stop_requested() {
+                emit.push(self.check_hierarchy(&bytes)?);
+            }
+
+            if self.options.check_children && !self.options.stop_requested() {
+                emit.push(self.check_children(&bytes)?);
+            }
+
+            if self.options.round_trip && !self.options.stop_requested() {
+                emit.push(self.cross_check_node_count(&bytes)?);
+            }
+
+            if self.options.check_attributes && !self.options.stop_requested() {
+                if let Some(result) = self.check_attributes(&bytes)? {
+                    emit.push(result);
+                }
+            }
+
+            if self.options.mean_fanout {
+                let inner_nodes = valid_nodes.iter().filter(|node| !node.is_leaf());
+                let (num_inner_nodes, num_children) =
+                    inner_nodes.fold((0_usize, 0_usize), |(num_nodes, num_children), node| {
+                        (
+                            num_nodes + 1,
+                            num_children + node.child_mask.count_ones() as usize,
+                        )
+                    });
+                let mean_fanout = if num_inner_nodes == 0 {
+                    0.0
+                } else {
+                    num_children as f64 / num_inner_nodes as f64
+                };
+                emit.push(AnalyzerResult::MeanFanout(mean_fanout));
+            }
+
+            if self.options.level_count && !self.options.stop_requested() {
+                emit.push(level_count_result(
+                    self.walk_hierarchy(&bytes)?
+                        .iter()
+                        .map(|node| node.level as u32),
+                ));
+            }
+
+            if self.options.level_summary && !self.options.stop_requested() {
+                emit.push(self.level_summary(&bytes)?);
+            }
+
+            if self.options.lod_curve && !self.options.stop_requested() {
+                emit.push(self.lod_curve(&bytes)?);
+            }
+
+            if self.options.dump_nodes {
+                // Nodes in hierarchy.bin have no name, so they are identified by their position in the hierarchy
+                let nodes = valid_nodes
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, node)| NodeInfo {
+                        id: idx.to_string(),
+                        point_count: node.num_points,
+                        file_size: node.byte_size,
+                        bounds: None,
+                        point_record_length: None,
+                        point_format: None,
+                        header_size: None,
+                        point_data_offset: None,
+                    })
+                    .collect();
+                emit.push(AnalyzerResult::Nodes(dumped_nodes(nodes, &self.options)));
+            }
+
+            Ok(())
+        })
     }
 }
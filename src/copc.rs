@@ -0,0 +1,129 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashSet;
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Size of the LAS 1.4 header, after which a COPC file has its info VLR
+const LAS_14_HEADER_SIZE: usize = 375;
+/// Size of the header of a VLR: reserved (u16), user ID (16 bytes), record ID (u16), record length (u16) and
+/// description (32 bytes)
+const VLR_HEADER_SIZE: usize = 54;
+/// Size of the payload of the COPC info VLR
+const COPC_INFO_SIZE: usize = 160;
+/// Size of a single entry of a hierarchy page: the key as level, x, y and z (i32 each), the offset (u64), the byte
+/// size (i32) and the point count (i32)
+const HIERARCHY_ENTRY_SIZE: usize = 32;
+
+/// The point count that the LAS header of a COPC file declares together with the sum of the point counts of all
+/// nodes of its hierarchy. These disagree in malformed COPC files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopcPointCounts {
+    /// Number of point records of the LAS 1.4 header
+    pub header: u64,
+    /// Sum of the point counts of all entries of the hierarchy pages
+    pub hierarchy: u64,
+    /// Number of nodes with point data in the hierarchy
+    pub nodes: usize,
+}
+
+impl CopcPointCounts {
+    /// Do the header and the hierarchy declare the same number of points?
+    pub fn matches(&self) -> bool {
+        self.header == self.hierarchy
+    }
+}
+
+/// Reads the point count of the LAS header of the file at `path` and sums the point counts of its COPC hierarchy.
+/// Returns `None` if the file is not a COPC file, i.e. if its first VLR is not the COPC info VLR
+pub fn read_copc_point_counts<P: AsRef<Path>>(path: P) -> Result<Option<CopcPointCounts>> {
+    let path = path.as_ref();
+    let mut file =
+        File::open(path).with_context(|| format!("Could not open {}", path.display()))?;
+    read_point_counts(&mut file).with_context(|| format!("Could not read {}", path.display()))
+}
+
+/// Like `read_copc_point_counts`, but reads from any seekable source. All values are little-endian. The COPC
+/// specification requires a LAS 1.4 header followed by the COPC info VLR (user ID `copc`, record ID 1) at byte 375.
+/// Its payload contains the offset and size of the root hierarchy page at bytes 40 and 48. Each page is a list of
+/// entries, where a point count of -1 marks an entry that points to another page instead of to point data
+pub fn read_point_counts<R: Read + Seek>(reader: &mut R) -> Result<Option<CopcPointCounts>> {
+    let mut head = vec![0; LAS_14_HEADER_SIZE + VLR_HEADER_SIZE + COPC_INFO_SIZE];
+    reader.seek(SeekFrom::Start(0))?;
+    if read_up_to(reader, &mut head)? < head.len() || &head[0..4] != b"LASF" {
+        return Ok(None);
+    }
+    let version = (head[24], head[25]);
+    let vlr = &head[LAS_14_HEADER_SIZE..LAS_14_HEADER_SIZE + VLR_HEADER_SIZE];
+    let user_id = vlr[2..18]
+        .split(|byte| *byte == 0)
+        .next()
+        .unwrap_or_default();
+    let record_id = u16::from_le_bytes(vlr[18..20].try_into().unwrap());
+    if version != (1, 4) || user_id != b"copc" || record_id != 1 {
+        return Ok(None);
+    }
+
+    let header = u64::from_le_bytes(head[247..255].try_into().unwrap());
+    let info = &head[LAS_14_HEADER_SIZE + VLR_HEADER_SIZE..];
+    let root_offset = u64::from_le_bytes(info[40..48].try_into().unwrap());
+    let root_size = u64::from_le_bytes(info[48..56].try_into().unwrap());
+
+    let mut counts = CopcPointCounts {
+        header,
+        hierarchy: 0,
+        nodes: 0,
+    };
+    let mut pages = vec![(root_offset, root_size)];
+    // Guards against pages that point to each other in malformed files
+    let mut visited_pages = HashSet::new();
+    while let Some((offset, size)) = pages.pop() {
+        if !visited_pages.insert(offset) {
+            return Err(anyhow!(
+                "The hierarchy page at byte {} is referenced more than once",
+                offset
+            ));
+        }
+        if size % HIERARCHY_ENTRY_SIZE as u64 != 0 {
+            return Err(anyhow!(
+                "The size {} of the hierarchy page at byte {} is not a multiple of {}",
+                size,
+                offset,
+                HIERARCHY_ENTRY_SIZE
+            ));
+        }
+        let mut page = vec![0; size as usize];
+        reader.seek(SeekFrom::Start(offset))?;
+        reader
+            .read_exact(&mut page)
+            .with_context(|| format!("Could not read the hierarchy page at byte {}", offset))?;
+        for entry in page.chunks_exact(HIERARCHY_ENTRY_SIZE) {
+            let entry_offset = u64::from_le_bytes(entry[16..24].try_into().unwrap());
+            let byte_size = i32::from_le_bytes(entry[24..28].try_into().unwrap());
+            let point_count = i32::from_le_bytes(entry[28..32].try_into().unwrap());
+            match point_count {
+                -1 => pages.push((entry_offset, byte_size.max(0) as u64)),
+                point_count if point_count > 0 => {
+                    counts.hierarchy += point_count as u64;
+                    counts.nodes += 1;
+                }
+                // Empty nodes and invalid negative counts don't add points
+                _ => {}
+            }
+        }
+    }
+    Ok(Some(counts))
+}
+
+/// Fills `buffer` as far as the reader allows and returns the number of bytes read
+fn read_up_to<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..])? {
+            0 => break,
+            bytes_read => filled += bytes_read,
+        }
+    }
+    Ok(filled)
+}
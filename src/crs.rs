@@ -0,0 +1,96 @@
+use las::Vlr;
+
+/// User ID of the VLRs that store the coordinate reference system of a LAS file
+const PROJECTION_USER_ID: &str = "LASF_Projection";
+/// Record ID of the GeoKeyDirectoryTag VLR
+const GEO_KEY_DIRECTORY_RECORD_ID: u16 = 34735;
+/// Record ID of the OGC WKT VLR
+const WKT_RECORD_ID: u16 = 2112;
+
+/// GeoTIFF key of the EPSG code of a projected CRS
+const PROJECTED_CS_TYPE_GEO_KEY: u16 = 3072;
+/// GeoTIFF key of the EPSG code of a geographic CRS
+const GEOGRAPHIC_TYPE_GEO_KEY: u16 = 2048;
+/// GeoTIFF value for CRS that are not identified by an EPSG code
+const USER_DEFINED: u16 = 32767;
+
+/// Returns the EPSG code of the coordinate reference system that is stored in the given VLRs, either as a WKT string
+/// or as GeoTIFF keys. Returns `None` if there is no CRS or if it is not identified by an EPSG code
+pub fn epsg_from_vlrs<'a, I: IntoIterator<Item = &'a Vlr>>(vlrs: I) -> Option<u32> {
+    let projection_vlrs = vlrs
+        .into_iter()
+        .filter(|vlr| vlr.user_id == PROJECTION_USER_ID)
+        .collect::<Vec<_>>();
+
+    // WKT is the more expressive representation, so prefer it if a file contains both
+    let from_wkt = projection_vlrs
+        .iter()
+        .filter(|vlr| vlr.record_id == WKT_RECORD_ID)
+        .find_map(|vlr| epsg_from_wkt(&String::from_utf8_lossy(&vlr.data)));
+    from_wkt.or_else(|| {
+        projection_vlrs
+            .iter()
+            .filter(|vlr| vlr.record_id == GEO_KEY_DIRECTORY_RECORD_ID)
+            .find_map(|vlr| epsg_from_geo_keys(&vlr.data))
+    })
+}
+
+/// Parses the EPSG code from the GeoKeyDirectoryTag stored in `data`. The directory starts with a header of four
+/// `u16` values, the last of which is the number of keys, followed by one entry of four `u16` values per key
+pub fn epsg_from_geo_keys(data: &[u8]) -> Option<u32> {
+    let values = data
+        .chunks_exact(2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .collect::<Vec<_>>();
+    let num_keys = *values.get(3)? as usize;
+
+    let keys = values.get(4..)?.chunks_exact(4).take(num_keys);
+    let mut geographic_code = None;
+    for key in keys {
+        let (key_id, tag_location, value) = (key[0], key[1], key[3]);
+        // A tag location other than 0 means that the value is stored in another VLR, which EPSG codes never are
+        if tag_location != 0 || value == 0 || value == USER_DEFINED {
+            continue;
+        }
+        match key_id {
+            PROJECTED_CS_TYPE_GEO_KEY => return Some(value as u32),
+            GEOGRAPHIC_TYPE_GEO_KEY => geographic_code = Some(value as u32),
+            _ => (),
+        }
+    }
+    geographic_code
+}
+
+/// Parses the EPSG code of the outermost CRS from a WKT string. In both WKT1 (`AUTHORITY["EPSG","25832"]`) and WKT2
+/// (`ID["EPSG",25832]`), the identifier of the outermost CRS is the last one in the string
+pub fn epsg_from_wkt(wkt: &str) -> Option<u32> {
+    let identifier_start = ["AUTHORITY[", "ID["]
+        .iter()
+        .filter_map(|keyword| rfind_keyword(wkt, keyword).map(|position| position + keyword.len()))
+        .max()?;
+    let identifier = &wkt[identifier_start..];
+    let identifier = &identifier[..identifier.find(']')?];
+
+    let mut parts = identifier
+        .split(',')
+        .map(|part| part.trim().trim_matches('"'));
+    if !parts.next()?.eq_ignore_ascii_case("EPSG") {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
+
+/// Finds the last occurrence of `keyword` that starts a WKT token, so that e.g. `ID[` doesn't match the end of
+/// `ELLIPSOID[`
+fn rfind_keyword(wkt: &str, keyword: &str) -> Option<usize> {
+    wkt.rmatch_indices(keyword)
+        .map(|(position, _)| position)
+        .find(|&position| {
+            !wkt[..position]
+                .chars()
+                .next_back()
+                .map_or(false, |previous| {
+                    previous.is_ascii_alphanumeric() || previous == '_'
+                })
+        })
+}
@@ -0,0 +1,317 @@
+//! Detection of the tiling format of a dataset, and construction of the analyzer for the detected format
+
+use crate::analyzer::{
+    AnalysisOptions, Analyzer, EptHierarchyAnalyzer, MultiFileAnalyzer, PotreeV2FormatAnalyzer,
+    SchwarzwaldAnalyzer, VpcAnalyzer,
+};
+use crate::error::{self, AnalyzerError};
+use crate::{ept, potree_v2};
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use walkdir::WalkDir;
+
+fn is_entwine_dataset(root_dir: &Path) -> bool {
+    let ept_data_dir = root_dir.to_owned().join("ept-data");
+    ept_data_dir.exists()
+}
+
+/// Entwine datasets whose node files are binary or zstandard chunks instead of LAZ files, as declared by the
+/// 'dataType' in ept.json
+fn is_chunked_entwine_dataset(root_dir: &Path) -> Result<bool> {
+    if !is_entwine_dataset(root_dir) {
+        return Ok(false);
+    }
+    Ok(ept::read_data_type(root_dir)?.map_or(false, |data_type| data_type != "laszip"))
+}
+
+fn is_potree_legacy_dataset(root_dir: &Path) -> bool {
+    let cloud_js_path = root_dir.to_owned().join("cloud.js");
+    cloud_js_path.exists()
+}
+
+/// Returns the parts of a PotreeConverter v2 dataset that are missing in `root_dir`. A stray hierarchy.bin alone is
+/// not enough, the dataset also needs octree.bin and a metadata.json of version 2.x
+fn missing_potree_v2_parts(root_dir: &Path) -> Vec<&'static str> {
+    let mut missing_parts = ["hierarchy.bin", "octree.bin", "metadata.json"]
+        .iter()
+        .copied()
+        .filter(|file| !root_dir.join(file).exists())
+        .collect::<Vec<_>>();
+    let metadata_file = root_dir.join("metadata.json");
+    if metadata_file.exists() {
+        match potree_v2::read_version(&metadata_file) {
+            Ok(Some(version)) if version.starts_with("2.") => {}
+            _ => missing_parts.push("version 2.x in metadata.json"),
+        }
+    }
+    missing_parts
+}
+
+fn is_potree_v2_dataset(root_dir: &Path) -> bool {
+    missing_potree_v2_parts(root_dir).is_empty()
+}
+
+/// How deep below the root directory of a Schwarzwald dataset the file of the root node is searched
+const SCHWARZWALD_ROOT_NODE_MAX_DEPTH: usize = 3;
+
+/// Schwarzwald datasets have a metadata.json file like PotreeConverter v2 datasets, but no hierarchy.bin. Instead,
+/// each node is a LAS/LAZ file named after the node, so the file of the root node ('r.las' or 'r.laz') must exist in
+/// the root directory or at most two directory levels below it
+fn is_schwarzwald_dataset(root_dir: &Path) -> bool {
+    if !root_dir.join("metadata.json").is_file() || root_dir.join("hierarchy.bin").exists() {
+        return false;
+    }
+    WalkDir::new(root_dir)
+        .max_depth(SCHWARZWALD_ROOT_NODE_MAX_DEPTH)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .any(|entry| {
+            entry.file_type().is_file()
+                && (entry.file_name() == "r.las" || entry.file_name() == "r.laz")
+        })
+}
+
+/// The input is either a .vpc file itself or a directory that contains a .vpc file
+fn find_vpc_file(input: &Path) -> Option<PathBuf> {
+    let is_vpc_file = |path: &Path| {
+        path.is_file()
+            && path
+                .extension()
+                .map_or(false, |extension| extension == "vpc")
+    };
+    if is_vpc_file(input) {
+        return Some(input.to_owned());
+    }
+
+    let mut vpc_files = std::fs::read_dir(input)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_vpc_file(path))
+        .collect::<Vec<_>>();
+    vpc_files.sort();
+    vpc_files.into_iter().next()
+}
+
+fn is_vpc_dataset(input: &Path) -> bool {
+    find_vpc_file(input).is_some()
+}
+
+/// Tiling format of a dataset, as detected from its files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetFormat {
+    Vpc,
+    ChunkedEntwine,
+    Entwine,
+    PotreeLegacy,
+    PotreeV2,
+    Schwarzwald,
+}
+
+impl DatasetFormat {
+    /// All supported formats, in the order in which ties between equally confident format checks are broken
+    pub fn all() -> impl Iterator<Item = DatasetFormat> {
+        FORMATS.iter().map(|entry| entry.format)
+    }
+
+    /// Name of the format on the command line, e.g. 'potree-v2'
+    pub fn id(self) -> &'static str {
+        match self {
+            DatasetFormat::Vpc => "vpc",
+            DatasetFormat::ChunkedEntwine => "ept-chunked",
+            DatasetFormat::Entwine => "ept",
+            DatasetFormat::PotreeLegacy => "potree-v1",
+            DatasetFormat::PotreeV2 => "potree-v2",
+            DatasetFormat::Schwarzwald => "schwarzwald",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            DatasetFormat::Vpc => "Virtual point cloud",
+            DatasetFormat::ChunkedEntwine => "Entwine with binary or zstandard node files",
+            DatasetFormat::Entwine => "Entwine",
+            DatasetFormat::PotreeLegacy => "PotreeConverter v1.7",
+            DatasetFormat::PotreeV2 => "PotreeConverter v2",
+            DatasetFormat::Schwarzwald => "Schwarzwald",
+        }
+    }
+
+    /// The file or directory in `input_dir` that the check for this format is based on
+    pub fn key_path(self, input_dir: &Path) -> PathBuf {
+        match self {
+            DatasetFormat::Vpc => {
+                find_vpc_file(input_dir).unwrap_or_else(|| input_dir.join("*.vpc"))
+            }
+            DatasetFormat::ChunkedEntwine => input_dir.join("ept.json"),
+            DatasetFormat::Entwine => input_dir.join("ept-data"),
+            DatasetFormat::PotreeLegacy => input_dir.join("cloud.js"),
+            DatasetFormat::PotreeV2 => input_dir.join("hierarchy.bin"),
+            DatasetFormat::Schwarzwald => input_dir.join("metadata.json"),
+        }
+    }
+
+    /// Returns the confidence that `input_dir` contains a dataset of this format, or `None` if it doesn't
+    pub fn check(self, input_dir: &Path) -> Result<Option<u8>> {
+        (self.entry().detect)(input_dir)
+    }
+
+    fn entry(self) -> &'static FormatEntry {
+        FORMATS
+            .iter()
+            .find(|entry| entry.format == self)
+            .expect("every format has an entry in FORMATS")
+    }
+}
+
+impl FromStr for DatasetFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        DatasetFormat::all()
+            .find(|format| format.id() == s)
+            .ok_or_else(|| anyhow!("Unknown dataset format {}", s))
+    }
+}
+
+/// Confidence of a format check that is based on a file that names the dataset explicitly, e.g. a .vpc file
+const CONFIDENCE_EXPLICIT: u8 = 3;
+/// Confidence of a format check that refines another format, e.g. the data type in ept.json of an Entwine dataset
+const CONFIDENCE_REFINED: u8 = 2;
+/// Confidence of a format check that is only based on the directory layout
+const CONFIDENCE_LAYOUT: u8 = 1;
+
+/// A supported tiling format together with its format check and the constructor of its analyzer
+struct FormatEntry {
+    format: DatasetFormat,
+    /// Returns the confidence that `input_dir` contains a dataset of this format, or `None` if it doesn't
+    detect: fn(&Path) -> Result<Option<u8>>,
+    /// Creates the analyzer for the dataset in `input_dir`, after it was detected as this format
+    construct: fn(PathBuf, AnalysisOptions) -> Result<Box<dyn Analyzer>>,
+}
+
+/// All supported tiling formats. Supporting a new format means adding an entry here. If the checks of several
+/// formats match, the one with the highest confidence wins, and among equally confident formats the one that comes
+/// first in this list
+const FORMATS: [FormatEntry; 6] = [
+    FormatEntry {
+        format: DatasetFormat::Vpc,
+        detect: |input_dir| Ok(is_vpc_dataset(input_dir).then(|| CONFIDENCE_EXPLICIT)),
+        construct: |input_dir, options| {
+            let vpc_file = find_vpc_file(&input_dir)
+                .ok_or_else(|| anyhow!("No .vpc file in {}", input_dir.display()))?;
+            Ok(Box::new(VpcAnalyzer::new(vpc_file, options)?))
+        },
+    },
+    FormatEntry {
+        format: DatasetFormat::ChunkedEntwine,
+        detect: |input_dir| Ok(is_chunked_entwine_dataset(input_dir)?.then(|| CONFIDENCE_REFINED)),
+        construct: |input_dir, options| {
+            Ok(Box::new(EptHierarchyAnalyzer::new(input_dir, options)?))
+        },
+    },
+    FormatEntry {
+        format: DatasetFormat::Entwine,
+        detect: |input_dir| Ok(is_entwine_dataset(input_dir).then(|| CONFIDENCE_LAYOUT)),
+        construct: |input_dir, options| {
+            make_ept_data_analyzer(DatasetFormat::Entwine, input_dir, options)
+        },
+    },
+    FormatEntry {
+        format: DatasetFormat::PotreeLegacy,
+        detect: |input_dir| Ok(is_potree_legacy_dataset(input_dir).then(|| CONFIDENCE_LAYOUT)),
+        construct: |input_dir, options| {
+            make_ept_data_analyzer(DatasetFormat::PotreeLegacy, input_dir, options)
+        },
+    },
+    FormatEntry {
+        format: DatasetFormat::PotreeV2,
+        detect: |input_dir| Ok(is_potree_v2_dataset(input_dir).then(|| CONFIDENCE_LAYOUT)),
+        construct: |input_dir, options| {
+            Ok(Box::new(PotreeV2FormatAnalyzer::new(input_dir, options)?))
+        },
+    },
+    FormatEntry {
+        format: DatasetFormat::Schwarzwald,
+        detect: |input_dir| Ok(is_schwarzwald_dataset(input_dir).then(|| CONFIDENCE_LAYOUT)),
+        construct: |input_dir, options| Ok(Box::new(SchwarzwaldAnalyzer::new(input_dir, options)?)),
+    },
+];
+
+/// Entwine and PotreeConverter v1.7 datasets store one LAS/LAZ file per node in the ept-data directory. The
+/// `MultiFileAnalyzer` doesn't know the format of its files, so the banner with the format is printed here
+fn make_ept_data_analyzer(
+    format: DatasetFormat,
+    input_dir: PathBuf,
+    options: AnalysisOptions,
+) -> Result<Box<dyn Analyzer>> {
+    if !options.quiet {
+        eprintln!("Analyzing dataset in {} format", format.description());
+    }
+    Ok(Box::new(
+        MultiFileAnalyzer::builder()
+            .root_dir(input_dir.join("ept-data"))
+            .options(options)
+            .build()?,
+    ))
+}
+
+/// Runs the checks of all formats against `input_dir` and returns the confidence of each format, `None` if its check
+/// did not match
+fn run_format_checks(input_dir: &Path) -> Result<Vec<(DatasetFormat, Option<u8>)>> {
+    DatasetFormat::all()
+        .map(|format| Ok((format, format.check(input_dir)?)))
+        .collect()
+}
+
+/// Picks the matching format with the highest confidence from the results of the format checks, the first one in
+/// `DatasetFormat::all` on ties
+pub fn best_match(checks: &[(DatasetFormat, Option<u8>)]) -> Option<DatasetFormat> {
+    checks
+        .iter()
+        .filter_map(|(format, confidence)| confidence.map(|confidence| (*format, confidence)))
+        .fold(
+            None,
+            |best: Option<(DatasetFormat, u8)>, (format, confidence)| match best {
+                Some((_, best_confidence)) if best_confidence >= confidence => best,
+                _ => Some((format, confidence)),
+            },
+        )
+        .map(|(format, _)| format)
+}
+
+/// Detects the tiling format of the dataset in `input_dir`, or returns `None` if no format check matches
+pub fn detect_format(input_dir: &Path) -> error::Result<Option<DatasetFormat>> {
+    Ok(best_match(&run_format_checks(input_dir)?))
+}
+
+/// Creates the analyzer for the format of the dataset in `input_dir` and returns it together with the format. Fails
+/// with `AnalyzerError::FormatNotRecognized` if the dataset is in none of the supported formats
+pub fn make_analyzer(
+    input_dir: PathBuf,
+    options: AnalysisOptions,
+) -> error::Result<(Box<dyn Analyzer>, DatasetFormat)> {
+    let format = match detect_format(&input_dir)? {
+        Some(format) => format,
+        // The metadata is only needed for the record layout, which is inferred from the hierarchy instead
+        None if options.infer_record_size
+            && missing_potree_v2_parts(&input_dir) == ["metadata.json"] =>
+        {
+            return Ok((
+                Box::new(PotreeV2FormatAnalyzer::new(input_dir, options)?),
+                DatasetFormat::PotreeV2,
+            ))
+        }
+        None if input_dir.join("hierarchy.bin").exists() => {
+            return Err(AnalyzerError::FormatNotRecognized {
+                hint: Some(format!(
+                    "Found hierarchy.bin, but the dataset is missing {} to be a PotreeConverter v2 dataset",
+                    missing_potree_v2_parts(&input_dir).join(", ")
+                )),
+            })
+        }
+        None => return Err(AnalyzerError::FormatNotRecognized { hint: None }),
+    };
+    Ok(((format.entry().construct)(input_dir, options)?, format))
+}
@@ -0,0 +1,83 @@
+use crate::error::AnalyzerError;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// The parts of the ept.json file of an Entwine dataset that are relevant for analyzing the dataset
+#[derive(Debug, Deserialize)]
+struct EptMetadata {
+    /// Format of the node files in ept-data: 'laszip', 'binary' or 'zstandard'
+    #[serde(rename = "dataType")]
+    data_type: String,
+}
+
+/// Reads the data type of the node files from the ept.json file in `root_dir`. Returns `None` if there is no
+/// ept.json file
+pub fn read_data_type<P: AsRef<Path>>(root_dir: P) -> Result<Option<String>> {
+    let ept_file = root_dir.as_ref().join("ept.json");
+    if !ept_file.exists() {
+        return Ok(None);
+    }
+    let reader = BufReader::new(
+        File::open(&ept_file).with_context(|| format!("Could not open {}", ept_file.display()))?,
+    );
+    let metadata: EptMetadata = serde_json::from_reader(reader)
+        .with_context(|| format!("Could not parse {}", ept_file.display()))?;
+    Ok(Some(metadata.data_type))
+}
+
+/// Reads the point counts of all nodes from the hierarchy of the Entwine dataset in `root_dir`.
+///
+/// The hierarchy is stored in the ept-hierarchy directory as JSON files that map node keys ('D-X-Y-Z') to point
+/// counts, starting with ept-hierarchy/0-0-0-0.json. A point count of -1 means that the hierarchy below this node is
+/// stored in a separate file named after the node key, e.g. ept-hierarchy/3-2-1-0.json, which contains the point
+/// count of the node itself and of its descendants. Nodes with a point count of 0 have no node file and are skipped
+pub fn read_hierarchy_point_counts<P: AsRef<Path>>(root_dir: P) -> Result<Vec<(String, u64)>> {
+    let hierarchy_dir = root_dir.as_ref().join("ept-hierarchy");
+    let mut point_counts = vec![];
+    let mut visited_files = HashSet::new();
+    let mut pending_files = VecDeque::new();
+    pending_files.push_back(String::from("0-0-0-0"));
+
+    while let Some(file_key) = pending_files.pop_front() {
+        if !visited_files.insert(file_key.clone()) {
+            return Err(AnalyzerError::CorruptHierarchy(format!(
+                "Hierarchy file {}.json is referenced more than once!",
+                file_key
+            ))
+            .into());
+        }
+
+        let hierarchy_file = hierarchy_dir.join(format!("{}.json", file_key));
+        let reader = BufReader::new(
+            File::open(&hierarchy_file)
+                .with_context(|| format!("Could not open {}", hierarchy_file.display()))?,
+        );
+        let entries: HashMap<String, i64> = serde_json::from_reader(reader)
+            .with_context(|| format!("Could not parse {}", hierarchy_file.display()))?;
+
+        for (key, point_count) in entries.into_iter() {
+            match point_count {
+                -1 => pending_files.push_back(key),
+                0 => {}
+                point_count if point_count > 0 => point_counts.push((key, point_count as u64)),
+                _ => {
+                    return Err(AnalyzerError::CorruptHierarchy(format!(
+                        "Invalid point count {} for node {} in {}",
+                        point_count,
+                        key,
+                        hierarchy_file.display()
+                    ))
+                    .into())
+                }
+            }
+        }
+    }
+
+    // JSON objects have no defined order, sorting makes the result deterministic
+    point_counts.sort();
+    Ok(point_counts)
+}
@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Result type of the analyzers
+pub type Result<T> = std::result::Result<T, AnalyzerError>;
+
+/// The ways in which analyzing a dataset can fail. Library users can match on the variants to handle specific
+/// failures, e.g. to skip datasets in an unknown format. Everything that has no dedicated variant, e.g. an I/O error
+/// while reading a single node, is wrapped in `Other`. All variants convert into `anyhow::Error`, which keeps their
+/// kind available through `anyhow::Error::downcast_ref`
+#[derive(Debug, Error)]
+pub enum AnalyzerError {
+    /// The tiling format of the dataset is not recognized
+    #[error("Tiling format not recognized!{}", .hint.as_ref().map_or_else(String::new, |hint| format!(" {}", hint)))]
+    FormatNotRecognized {
+        /// Why the dataset is close to, but not quite in a known format
+        hint: Option<String>,
+    },
+    /// A root directory of the dataset does not exist
+    #[error("root directory {} does not exist!", .0.display())]
+    RootDirNotFound(PathBuf),
+    /// A file that the format of the dataset requires does not exist
+    #[error("{} does not exist!", .0.display())]
+    FileNotFound(PathBuf),
+    /// The dataset contains no nodes to analyze
+    #[error("{0}")]
+    EmptyDataset(String),
+    /// The hierarchy of the dataset is malformed, e.g. truncated or with chunks that reference each other
+    #[error("{0}")]
+    CorruptHierarchy(String),
+    /// The dataset needs a cargo feature that this binary was built without
+    #[error("Found {found}, but this binary was built without {feature} support! Rebuild it with the '{feature}' feature enabled (e.g. 'cargo build --features {feature}') to {purpose}")]
+    MissingFeature {
+        feature: &'static str,
+        /// The file or URL that needs the feature
+        found: String,
+        purpose: &'static str,
+    },
+    /// The dataset has no coordinate reference system with an EPSG code, but `AnalysisOptions` ask for it
+    #[error("Dataset has no coordinate reference system that is identified by an EPSG code!")]
+    MissingEpsg,
+    /// The dataset has a different EPSG code than `AnalysisOptions::require_epsg`
+    #[error("Dataset has EPSG code {found}, but EPSG code {required} is required!")]
+    EpsgMismatch { found: u32, required: u32 },
+    /// The options can't be applied to the dataset or contradict each other
+    #[error("{0}")]
+    InvalidOptions(String),
+    /// Any other failure
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl From<anyhow::Error> for AnalyzerError {
+    /// Helper functions report errors through `anyhow`. An `AnalyzerError` that was converted into an
+    /// `anyhow::Error` on the way keeps its kind
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<AnalyzerError>() {
+            Ok(error) => error,
+            Err(error) => AnalyzerError::Other(error),
+        }
+    }
+}
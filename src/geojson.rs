@@ -0,0 +1,63 @@
+use crate::analyzer::NodeInfo;
+use anyhow::{Context, Result};
+use serde_json::json;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Writes the XY footprint of the bounding box of each node as a polygon feature of a GeoJSON feature collection to
+/// `path`, with the properties `id` and `point_count`. Nodes without bounds are skipped. If `epsg` is given, it is
+/// written as a named CRS (`urn:ogc:def:crs:EPSG::CODE`), which GIS tools like QGIS pick up. Otherwise the
+/// coordinates are written as they are, which GeoJSON readers assume to be WGS 84. Returns the number of features
+pub fn write_coverage<P: AsRef<Path>>(
+    path: P,
+    nodes: &[NodeInfo],
+    epsg: Option<u32>,
+) -> Result<usize> {
+    let features = nodes
+        .iter()
+        .filter_map(|node| {
+            let bounds = node.bounds.as_ref()?;
+            let (min, max) = (bounds.min, bounds.max);
+            Some(json!({
+                "type": "Feature",
+                "properties": {
+                    "id": node.id,
+                    "point_count": node.point_count,
+                },
+                "geometry": {
+                    "type": "Polygon",
+                    // Counterclockwise as required for exterior rings, the first position is repeated at the end
+                    "coordinates": [[
+                        [min.x, min.y],
+                        [max.x, min.y],
+                        [max.x, max.y],
+                        [min.x, max.y],
+                        [min.x, min.y],
+                    ]],
+                },
+            }))
+        })
+        .collect::<Vec<_>>();
+    let feature_count = features.len();
+
+    let mut collection = json!({
+        "type": "FeatureCollection",
+        "features": features,
+    });
+    if let Some(epsg) = epsg {
+        collection["crs"] = json!({
+            "type": "name",
+            "properties": { "name": format!("urn:ogc:def:crs:EPSG::{}", epsg) },
+        });
+    }
+
+    let mut writer = BufWriter::new(
+        File::create(path.as_ref())
+            .with_context(|| format!("Could not create {}", path.as_ref().display()))?,
+    );
+    serde_json::to_writer(&mut writer, &collection)?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(feature_count)
+}
@@ -0,0 +1,118 @@
+use crate::analyzer::{format_number, AnalyzerResult, Histogram};
+use crate::output::RunLabel;
+use anyhow::Result;
+use std::io::Write;
+
+const CHART_WIDTH: f64 = 800.0;
+const CHART_HEIGHT: f64 = 300.0;
+/// Space below the bars of the chart for the bucket labels
+const CHART_LABEL_HEIGHT: f64 = 20.0;
+
+/// Writes a self-contained HTML report of the given results. Each result is a row in a table, histograms are
+/// additionally shown as an inline SVG bar chart
+pub fn write_report<W: Write>(
+    writer: &mut W,
+    results: &[AnalyzerResult],
+    raw_numbers: bool,
+    label: Option<&RunLabel>,
+) -> Result<()> {
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, "<html>")?;
+    writeln!(writer, "<head>")?;
+    writeln!(writer, "<meta charset=\"utf-8\">")?;
+    writeln!(writer, "<title>Point cloud tiles analysis</title>")?;
+    writeln!(
+        writer,
+        "<style>body {{ font-family: sans-serif; }} table {{ border-collapse: collapse; }} td, th {{ border: 1px solid #ccc; padding: 0.5em; text-align: left; vertical-align: top; }} rect {{ fill: #4a7ebb; }} text {{ font-size: 10px; }}</style>"
+    )?;
+    writeln!(writer, "</head>")?;
+    writeln!(writer, "<body>")?;
+    writeln!(writer, "<h1>Point cloud tiles analysis</h1>")?;
+    if let Some(label) = label {
+        writeln!(
+            writer,
+            "<p>{} ({})</p>",
+            escape(&label.label),
+            escape(&label.timestamp)
+        )?;
+    }
+    writeln!(writer, "<table>")?;
+    for result in results.iter() {
+        let text = if raw_numbers {
+            format!("{:#}", result)
+        } else {
+            format!("{}", result)
+        };
+        writeln!(writer, "<tr>")?;
+        writeln!(writer, "<th>{}</th>", escape(result.title()))?;
+        writeln!(writer, "<td>")?;
+        if let AnalyzerResult::Histogram(histogram) | AnalyzerResult::SizeHistogram(histogram) =
+            result.inner()
+        {
+            write_histogram_chart(writer, histogram, raw_numbers)?;
+        }
+        writeln!(writer, "<pre>{}</pre>", escape(&text))?;
+        writeln!(writer, "</td>")?;
+        writeln!(writer, "</tr>")?;
+    }
+    writeln!(writer, "</table>")?;
+    writeln!(writer, "</body>")?;
+    writeln!(writer, "</html>")?;
+    Ok(())
+}
+
+fn write_histogram_chart<W: Write>(
+    writer: &mut W,
+    histogram: &Histogram,
+    raw_numbers: bool,
+) -> Result<()> {
+    let buckets = histogram.buckets();
+    let max_count = buckets
+        .iter()
+        .map(|bucket| bucket.count())
+        .max()
+        .unwrap_or_default()
+        .max(1);
+    let bar_width = CHART_WIDTH / buckets.len().max(1) as f64;
+
+    writeln!(
+        writer,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+        CHART_WIDTH,
+        CHART_HEIGHT + CHART_LABEL_HEIGHT
+    )?;
+    for (idx, bucket) in buckets.iter().enumerate() {
+        let bar_height = CHART_HEIGHT * bucket.count() as f64 / max_count as f64;
+        let x = idx as f64 * bar_width;
+        let label = if raw_numbers {
+            format!("{:#}", bucket)
+        } else {
+            format!("{}", bucket)
+        };
+        writeln!(
+            writer,
+            "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\"><title>{}</title></rect>",
+            x + 1.0,
+            CHART_HEIGHT - bar_height,
+            (bar_width - 2.0).max(1.0),
+            bar_height,
+            escape(&label)
+        )?;
+        writeln!(
+            writer,
+            "<text x=\"{:.2}\" y=\"{:.2}\">{}</text>",
+            x + 1.0,
+            CHART_HEIGHT + CHART_LABEL_HEIGHT - 5.0,
+            escape(&format_number(bucket.range().start as f64, raw_numbers))
+        )?;
+    }
+    writeln!(writer, "</svg>")?;
+    Ok(())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,76 @@
+use anyhow::{anyhow, Context, Result};
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Magic bytes at the start of every index file
+const MAGIC: &[u8; 4] = b"PCTI";
+/// Version of the index format that this module reads and writes
+pub const VERSION: u8 = 1;
+/// Magic bytes, version and number of nodes
+const HEADER_SIZE: usize = 4 + 1 + 8;
+
+/// Writes the point counts of all nodes to an index file at `path`. The index is a portable binary file that can be
+/// read back with `read_point_counts` to recompute histograms and statistics without reading the dataset again.
+///
+/// Format (all integers little-endian):
+/// - 4 bytes magic `PCTI`
+/// - 1 byte version, currently 1
+/// - `u64` number of nodes
+/// - one `u64` point count per node
+pub fn write_point_counts<P: AsRef<Path>>(path: P, point_counts: &[u64]) -> Result<()> {
+    let mut writer = BufWriter::new(
+        File::create(path.as_ref())
+            .with_context(|| format!("Could not create index {}", path.as_ref().display()))?,
+    );
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[VERSION])?;
+    writer.write_all(&(point_counts.len() as u64).to_le_bytes())?;
+    for point_count in point_counts.iter() {
+        writer.write_all(&point_count.to_le_bytes())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads the point counts of all nodes from an index file that was written by `write_point_counts`
+pub fn read_point_counts<P: AsRef<Path>>(path: P) -> Result<Vec<usize>> {
+    let mut reader = BufReader::new(
+        File::open(path.as_ref())
+            .with_context(|| format!("Could not open index {}", path.as_ref().display()))?,
+    );
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+
+    if bytes.len() < HEADER_SIZE || &bytes[..4] != MAGIC {
+        return Err(anyhow!(
+            "{} is not a point count index!",
+            path.as_ref().display()
+        ));
+    }
+    if bytes[4] != VERSION {
+        return Err(anyhow!(
+            "Index {} has version {}, but only version {} is supported!",
+            path.as_ref().display(),
+            bytes[4],
+            VERSION
+        ));
+    }
+
+    let num_nodes = u64::from_le_bytes(bytes[5..HEADER_SIZE].try_into().unwrap()) as usize;
+    let counts_bytes = &bytes[HEADER_SIZE..];
+    // The number of nodes is read from the file, so a corrupt index must not overflow the expected size
+    if num_nodes.checked_mul(8) != Some(counts_bytes.len()) {
+        return Err(anyhow!(
+            "Index {} should contain {} point counts, but its size doesn't match!",
+            path.as_ref().display(),
+            num_nodes
+        ));
+    }
+
+    Ok(counts_bytes
+        .chunks_exact(8)
+        .map(|count_bytes| u64::from_le_bytes(count_bytes.try_into().unwrap()) as usize)
+        .collect())
+}
@@ -0,0 +1,135 @@
+use anyhow::{anyhow, Context, Result};
+use std::convert::TryInto;
+use std::path::{Path, PathBuf};
+
+/// A single cell of the quadtree of a `.lax` index together with the number of points in it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LaxCell {
+    /// Index of the cell within the quadtree
+    pub index: i32,
+    pub point_count: u32,
+}
+
+/// The parts of a `.lax` spatial index of LASzip/LAStools that are relevant for analyzing the dataset
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaxIndex {
+    /// Number of levels of the quadtree
+    pub levels: u32,
+    /// Bounds of the quadtree in the xy plane as `[min_x, min_y, max_x, max_y]`
+    pub bounds: [f32; 4],
+    /// All non-empty cells of the quadtree
+    pub cells: Vec<LaxCell>,
+}
+
+impl LaxIndex {
+    /// Total number of points of all cells
+    pub fn point_count(&self) -> u64 {
+        self.cells.iter().map(|cell| cell.point_count as u64).sum()
+    }
+}
+
+/// Returns the path of the `.lax` index that belongs to the LAS/LAZ file at `file`, i.e. the same path with the
+/// extension `.lax`
+pub fn lax_path(file: &Path) -> PathBuf {
+    file.with_extension("lax")
+}
+
+/// Reads the `.lax` index at `path`
+pub fn read_lax_file<P: AsRef<Path>>(path: P) -> Result<LaxIndex> {
+    let bytes = std::fs::read(path.as_ref())
+        .with_context(|| format!("Could not read {}", path.as_ref().display()))?;
+    read_lax(&bytes).with_context(|| format!("Could not parse {}", path.as_ref().display()))
+}
+
+/// Parses a `.lax` index. All values are little-endian, the file consists of three sections:
+///
+/// - Header: signature `LASX`, version (u32)
+/// - Quadtree: signature `LASS`, type (u32, 0 for a quadtree), signature `LASQ`, version (u32), levels (u32), level
+///   index (u32), implicit levels (u32), min x, max x, min y, max y (f32 each)
+/// - Intervals: signature `LASV`, version (u32), number of cells (u32), followed by each cell with its index (i32),
+///   number of intervals (u32), number of points (u32) and the intervals as start and end point index (u32 each)
+///
+/// Only the levels, the bounds and the index and number of points of each cell are used. The intervals point into
+/// the point data of the LAS/LAZ file and are skipped
+pub fn read_lax(bytes: &[u8]) -> Result<LaxIndex> {
+    let mut reader = LaxReader { bytes, offset: 0 };
+    reader.expect_signature(b"LASX")?;
+    let _version = reader.read_u32()?;
+
+    reader.expect_signature(b"LASS")?;
+    let spatial_type = reader.read_u32()?;
+    if spatial_type != 0 {
+        return Err(anyhow!(
+            "Unsupported spatial index type {}, only quadtrees are supported",
+            spatial_type
+        ));
+    }
+    reader.expect_signature(b"LASQ")?;
+    let _version = reader.read_u32()?;
+    let levels = reader.read_u32()?;
+    let _level_index = reader.read_u32()?;
+    let _implicit_levels = reader.read_u32()?;
+    let min_x = reader.read_f32()?;
+    let max_x = reader.read_f32()?;
+    let min_y = reader.read_f32()?;
+    let max_y = reader.read_f32()?;
+
+    reader.expect_signature(b"LASV")?;
+    let _version = reader.read_u32()?;
+    let number_of_cells = reader.read_u32()?;
+    let mut cells = vec![];
+    for _ in 0..number_of_cells {
+        let index = reader.read_u32()? as i32;
+        let number_of_intervals = reader.read_u32()?;
+        let point_count = reader.read_u32()?;
+        reader.skip(number_of_intervals as usize * 8)?;
+        cells.push(LaxCell { index, point_count });
+    }
+
+    Ok(LaxIndex {
+        levels,
+        bounds: [min_x, min_y, max_x, max_y],
+        cells,
+    })
+}
+
+struct LaxReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> LaxReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .offset
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(self.offset..end))
+            .ok_or_else(|| anyhow!("Unexpected end of the index at byte {}", self.offset))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<()> {
+        self.take(len).map(|_| ())
+    }
+
+    fn expect_signature(&mut self, signature: &[u8; 4]) -> Result<()> {
+        let offset = self.offset;
+        if self.take(4)? != signature {
+            return Err(anyhow!(
+                "Expected signature {} at byte {}",
+                String::from_utf8_lossy(signature),
+                offset
+            ));
+        }
+        Ok(())
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn read_f32(&mut self) -> Result<f32> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
@@ -0,0 +1,47 @@
+#![feature(partition_point)]
+
+//! Analyzes the structure of tiled point clouds, e.g. the number of nodes and the distribution of points over all
+//! nodes. The analyzers can be used as a library as well:
+//!
+//! ```no_run
+//! use point_cloud_tiles_analyzer::analyzer::{Analyzer, HistogramConfig, MultiFileAnalyzer};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let analyzer = MultiFileAnalyzer::builder()
+//!     .root_dir("path/to/ept-data")
+//!     .count_nodes(true)
+//!     .histogram(HistogramConfig::Linear(10))
+//!     .build()?;
+//! for result in analyzer.run()? {
+//!     println!("{}", result);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod analyzer;
+pub mod copc;
+pub mod crs;
+pub mod detect;
+pub mod ept;
+pub mod error;
+pub mod geojson;
+mod html;
+pub mod index;
+pub mod lax;
+pub mod manifest;
+pub mod math;
+pub mod node_key;
+pub mod output;
+#[cfg(feature = "parquet")]
+mod parquet_writer;
+pub mod potree_v2;
+pub mod progress;
+pub mod quantiles;
+mod remote;
+pub mod status;
+#[cfg(feature = "tui")]
+mod tui;
+pub mod vpc;
+#[cfg(feature = "xml")]
+mod xml_writer;
@@ -1,37 +1,164 @@
-#![feature(partition_point)]
-
-use crate::analyzer::Analyzer;
-use crate::analyzer::HistogramConfig;
-use crate::analyzer::MultiFileAnalyzer;
-use analyzer::PotreeV2FormatAnalyzer;
-use anyhow::{anyhow, Result};
-use clap::{value_t, App, Arg};
-use std::str::FromStr;
+use anyhow::{anyhow, Context, Result};
+use clap::{value_t, values_t, App, AppSettings, Arg, SubCommand};
+use glob::Pattern;
+use point_cloud_tiles_analyzer::analyzer::explain_result;
+use point_cloud_tiles_analyzer::analyzer::AnalysisOptions;
+use point_cloud_tiles_analyzer::analyzer::Analyzer;
+use point_cloud_tiles_analyzer::analyzer::DumpSort;
+use point_cloud_tiles_analyzer::analyzer::HistogramConfig;
+use point_cloud_tiles_analyzer::analyzer::ManifestAnalyzer;
+use point_cloud_tiles_analyzer::analyzer::MultiFileAnalyzer;
+use point_cloud_tiles_analyzer::detect::{self, DatasetFormat};
+use point_cloud_tiles_analyzer::output;
+use point_cloud_tiles_analyzer::output::{OutputFormat, RunLabel};
+use point_cloud_tiles_analyzer::potree_v2::RecordLayout;
+use point_cloud_tiles_analyzer::quantiles::QuantileSketch;
+use point_cloud_tiles_analyzer::status::StatusFile;
+use regex::Regex;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use std::path::{Path, PathBuf};
 
-mod analyzer;
-mod math;
-mod progress;
+/// Number of buckets of the node size histogram if neither the size histogram nor the point histogram specify one
+const DEFAULT_SIZE_HISTOGRAM_BUCKETS: usize = 10;
+/// Number of node records that --trace-nodes prints if no number is given
+const DEFAULT_TRACED_NODES: usize = 20;
+/// Upper limit for the number of node records that --trace-nodes prints, to avoid flooding the output
+const MAX_TRACED_NODES: usize = 10000;
+
+/// Exit code if the analysis was stopped by --timeout, the same as the one of the timeout command
+const TIMEOUT_EXIT_CODE: i32 = 124;
+/// Exit code if the dataset is not detected as the format given by --assert-format
+const FORMAT_MISMATCH_EXIT_CODE: i32 = 3;
+
+/// Configuration of the `bench` subcommand
+struct BenchConfig {
+    input_dir: PathBuf,
+    verify_points: bool,
+}
 
 struct Config {
     input_dir: PathBuf,
-    count_nodes: bool,
-    histogram_config: Option<HistogramConfig>,
+    manifest_file: Option<PathBuf>,
+    index_file: Option<PathBuf>,
+    data_roots: Vec<PathBuf>,
+    files: Option<Vec<PathBuf>>,
+    options: AnalysisOptions,
+    output_format: OutputFormat,
+    output_file: Option<PathBuf>,
+    raw_numbers: bool,
+    tui: bool,
+    explain: bool,
+    label: Option<String>,
+    timeout: Option<Duration>,
+    detect_only: bool,
+    assert_format: Option<DatasetFormat>,
+    bench: Option<BenchConfig>,
 }
 
 fn get_config() -> Result<Config> {
     let matches = App::new("Point cloud tiles analyzer")
         .version("1.0")
         .author("Pascal Bormann")
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .subcommand(
+            SubCommand::with_name("bench")
+            .about("Measures how fast the headers of all LAS/LAZ files of a dataset can be read, e.g. to compare network and local storage. Prints files/s and bytes/s instead of analyzing the dataset")
+            .arg(
+                Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .value_name("DIR")
+                .help("The path to the directory of the tiled point cloud")
+                .takes_value(true)
+                .required(true)
+            )
+            .arg(
+                Arg::with_name("verify_points")
+                .long("verify-points")
+                .help("Read and decode all points instead of only the headers")
+            )
+        )
         .arg(
             Arg::with_name("input")
                 .short("i")
                 .long("input")
                 .value_name("DIR")
-                .help("The path to the directory of the tiled point cloud. Supported formats are PotreeConverter v1.7, PotreeConverter v2, Entwine and Schwarzwald")
+                .help("The path to the directory of the tiled point cloud. Supported formats are PotreeConverter v1.7, PotreeConverter v2, Entwine, Schwarzwald and virtual point clouds (.vpc) of PDAL. For virtual point clouds, this can also be the path to the .vpc file")
+                .takes_value(true)
+                .required_unless_one(&["manifest", "read_index", "data_roots", "files_from"]),
+        )
+        .arg(
+            Arg::with_name("data_roots")
+                .long("data-roots")
+                .value_name("DIRS")
+                .help("Comma-separated list of directories that together contain the node files of a single dataset, e.g. if the data is sharded across several mount points. All files are analyzed as one dataset")
+                .takes_value(true)
+                .use_delimiter(true)
+                .conflicts_with_all(&["input", "manifest"]),
+        )
+        .arg(
+            Arg::with_name("files_from")
+                .long("files-from")
+                .value_name("FILE")
+                .help("Analyze exactly the LAS/LAZ files listed in FILE (one path per line) as a single dataset, or read the list from stdin if FILE is '-'. Files that don't exist are reported and skipped")
                 .takes_value(true)
-                .required(true),
+                .conflicts_with_all(&["input", "manifest", "data_roots"]),
+        )
+        .arg(
+            Arg::with_name("manifest")
+                .long("manifest")
+                .value_name("FILE")
+                .help("Read the point counts of all nodes from FILE instead of analyzing a dataset. FILE contains one node per line, either as a plain point count or as comma-separated values with the point count as the last value")
+                .takes_value(true)
+                .conflicts_with("input"),
+        )
+        .arg(
+            Arg::with_name("detect_only")
+                .long("detect-only")
+                .help("Only detect the tiling format of the dataset in --input and print which format checks matched and which files they are based on, without reading any point data")
+                .requires("input"),
+        )
+        .arg(
+            Arg::with_name("assert_format")
+                .long("assert-format")
+                .value_name("FORMAT")
+                .help("Detect the tiling format of the dataset in --input before any analysis and exit with code 3 if it is not FORMAT")
+                .possible_values(&["vpc", "ept-chunked", "ept", "potree-v1", "potree-v2", "schwarzwald"])
+                .takes_value(true)
+                .requires("input"),
+        )
+        .arg(
+            Arg::with_name("read_index")
+                .long("read-index")
+                .value_name("FILE")
+                .help("Read the point counts of all nodes from the index FILE that was written by --write-index instead of analyzing a dataset")
+                .takes_value(true)
+                .conflicts_with_all(&["input", "manifest", "data_roots", "files_from"]),
+        )
+        .arg(
+            Arg::with_name("geojson")
+                .long("geojson")
+                .value_name("FILE")
+                .help("Write the XY footprint of the bounding box of each node as a polygon with its point count to the GeoJSON FILE, e.g. to view the tile layout in QGIS. Uses the EPSG code of the dataset as the CRS if it has one, otherwise the native coordinates. Only supported for formats where one node equals one file")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("write_index")
+                .long("write-index")
+                .value_name("FILE")
+                .help("Write the point counts of all nodes to the index FILE, a portable binary file that --read-index analyzes without reading the dataset again")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("assume_sorted")
+                .long("assume-sorted")
+                .help("The point counts in the manifest are sorted in ascending order already, so sorting them is skipped")
+                .requires("manifest"),
         )
         .arg(
             Arg::with_name("count_nodes")
@@ -49,80 +176,1243 @@ fn get_config() -> Result<Config> {
         .long("histogram-log")
         .help("Calculate a histogram of the number of points in each node with the specified number of buckets. Bucket size will be logarithmic between 1 and the maximum number points in a node")
         .takes_value(true))
+        .arg(
+            Arg::with_name("sparkline")
+            .long("sparkline")
+            .help("Additionally print the histogram of the number of points in each node as a single line of block characters, one per bucket. Requires --histogram-lin, --histogram-log, --histogram-step, --histogram-bins or --histogram-quantile")
+        )
+        .arg(
+            Arg::with_name("histogram_size")
+            .long("histogram-size")
+            .value_name("NUM_BUCKETS")
+            .help("Calculate a histogram of the sizes of the nodes in bytes. NUM_BUCKETS overrides the number of buckets of --histogram-lin/--histogram-log/--histogram-quantile for this histogram, the buckets are linear, logarithmic or equal-frequency like the point histogram. Without a point histogram, the buckets are logarithmic and NUM_BUCKETS defaults to 10. Not supported for manifests and virtual point clouds")
+            .takes_value(true)
+            .min_values(0)
+            .max_values(1)
+            .require_equals(true)
+        )
+        .arg(
+            Arg::with_name("histogram_step")
+            .long("histogram-step")
+            .value_name("START:STOP:STEP")
+            .help("Calculate a histogram of the number of points in each node with evenly spaced buckets of width STEP between START and STOP, e.g. '0:100000:10000'. Point counts below START and at or above STOP go into an additional underflow and open-ended overflow bucket")
+            .takes_value(true)
+            .conflicts_with_all(&["histogram_lin", "histogram_log"])
+        )
+        .arg(
+            Arg::with_name("histogram_bins")
+            .long("histogram-bins")
+            .value_name("BOUNDARIES")
+            .help("Calculate a histogram of the number of points in each node with buckets between the given comma-separated boundaries, e.g. '0,1000,10000,100000'. The boundaries must be strictly increasing. Point counts below the first boundary go into an additional underflow bucket, point counts at or above the last boundary into an open-ended overflow bucket. With the same boundaries, the histograms of different datasets have the same buckets")
+            .takes_value(true)
+            .use_delimiter(true)
+            .conflicts_with_all(&["histogram_lin", "histogram_log", "histogram_step"])
+        )
+        .arg(
+            Arg::with_name("histogram_quantile")
+            .long("histogram-quantile")
+            .value_name("NUM_BUCKETS")
+            .help("Calculate a histogram of the number of points in each node with the specified number of buckets, which contain approximately the same number of nodes each. The ranges of the buckets vary instead of their counts, which shows skewed distributions better than linear or logarithmic buckets. If many nodes have the same number of points, buckets with the same boundaries are merged, so there can be fewer buckets")
+            .takes_value(true)
+            .conflicts_with_all(&["histogram_lin", "histogram_log", "histogram_step", "histogram_bins"])
+        )
+        .arg(
+            Arg::with_name("gini")
+            .long("gini")
+            .help("Calculate the Gini coefficient of the number of points in each node. 0 means that all nodes contain the same number of points, values close to 1 mean that almost all points are in a single node")
+        )
+        .arg(
+            Arg::with_name("moments")
+            .long("moments")
+            .help("Calculate the skewness and excess kurtosis of the number of points in each node. A positive skewness indicates a heavy tail of large nodes")
+        )
+        .arg(
+            Arg::with_name("stats")
+            .long("stats")
+            .help("Calculate the mean and standard deviation of the number of points in each node")
+        )
+        .arg(
+            Arg::with_name("total_points")
+            .long("total-points")
+            .help("Report the total number of points of all nodes")
+        )
+        .arg(
+            Arg::with_name("percentiles")
+            .long("percentiles")
+            .value_name("PERCENTILES")
+            .help("Comma-separated list of percentiles in [0;100], e.g. '50,90,99'. Reports the number of points per node at each percentile, interpolated linearly between the two closest nodes")
+            .takes_value(true)
+            .use_delimiter(true)
+        )
+        .arg(
+            Arg::with_name("approx_quantiles")
+            .long("approx-quantiles")
+            .value_name("ACCURACY")
+            .help("Estimate the 50th, 75th, 90th, 95th and 99th percentile of the number of points in each node while reading the nodes, without storing and sorting all point counts. Each estimate is within the relative ACCURACY of the exact value, ACCURACY defaults to 0.01 (i.e. 1%). Not supported for manifests and virtual point clouds")
+            .takes_value(true)
+            .min_values(0)
+            .max_values(1)
+            .require_equals(true)
+        )
+        .arg(
+            Arg::with_name("count_range")
+            .long("count-range")
+            .value_name("MIN:MAX")
+            .help("Only include the nodes with at least MIN and at most MAX points in the histograms, statistics and all other analyses of the point counts, e.g. '10000:100000'. The number of nodes within the range is printed")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("warn_above")
+            .long("warn-above")
+            .value_name("N")
+            .help("Report how many nodes contain more than N points, which is more than renderers handle well. For formats where one node equals one file, the files are listed as well")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("downsample_target")
+            .long("downsample-target")
+            .value_name("N")
+            .help("Report how many nodes contain more than N points and by which factor they would have to be downsampled on average and at most to contain at most N points, e.g. to plan a re-tiling")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("leaf_point_ratio")
+            .long("leaf-point-ratio")
+            .help("Calculate how many points are stored in leaf nodes compared to inner nodes. Only supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("gsd_check")
+            .long("gsd-check")
+            .help("Compare the point spacing of each octree level with the spacing that is expected from the root spacing in metadata.json and flag levels that deviate too much. Only supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("gsd_tolerance")
+            .long("gsd-tolerance")
+            .value_name("TOLERANCE")
+            .help("Relative deviation from the expected point spacing above which --gsd-check flags a level. Defaults to 0.5")
+            .takes_value(true)
+            .requires("gsd_check")
+        )
+        .arg(
+            Arg::with_name("max_depth")
+            .long("max-depth")
+            .value_name("LEVEL")
+            .help("Only analyze nodes up to the given octree level (the root node is level 0). Deeper nodes are excluded from all results. Only supported for PotreeConverter v2")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("node_record_size")
+            .long("node-record-size")
+            .value_name("BYTES")
+            .help("Size of a node record in hierarchy.bin in bytes, overriding the size determined from the metadata. The byte offset and byte size of each node are read from the last 16 bytes of the record. Defaults to the point count offset plus the point count width plus 16. Only supported for PotreeConverter v2")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("infer_record_size")
+            .long("infer-record-size")
+            .help("Infer the size of the node records in hierarchy.bin by trying the known record layouts and picking the first one under which the file size is a multiple of the record size and all decoded node types, point counts and proxy nodes look sane, and report it. For hierarchies of unknown converter versions without a metadata.json, which is then not required to detect the dataset. Only supported for PotreeConverter v2")
+            .conflicts_with_all(&["node_record_size", "point_count_offset", "point_count_width"])
+        )
+        .arg(
+            Arg::with_name("point_count_offset")
+            .long("point-count-offset")
+            .value_name("BYTES")
+            .help("Offset of the point count within a node record in hierarchy.bin in bytes. Defaults to 2. Only supported for PotreeConverter v2")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("point_count_width")
+            .long("point-count-width")
+            .value_name("BYTES")
+            .help("Width of the point count within a node record in hierarchy.bin in bytes. Defaults to 4. Only supported for PotreeConverter v2")
+            .possible_values(&["4", "8"])
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("node_prefix")
+            .long("node-prefix")
+            .value_name("PREFIX")
+            .help("Only analyze the nodes whose name starts with PREFIX, i.e. a single subtree of the octree (e.g. 'r04' for PotreeConverter). For PotreeConverter v2, node names are resolved by walking the hierarchy, for all other formats the file names are matched")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("exclude")
+            .long("exclude")
+            .value_name("PATTERN")
+            .help("Skip all files whose path relative to the input directory matches the glob PATTERN, e.g. 'backup/**'. Can be given multiple times. Only supported for formats where one node equals one file")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+        )
+        .arg(
+            Arg::with_name("no_recurse")
+            .long("no-recurse")
+            .help("Only search the input directory itself for files, without descending into its subdirectories. Only supported for formats where one node equals one file")
+        )
+        .arg(
+            Arg::with_name("walk_max_depth")
+            .long("walk-max-depth")
+            .value_name("N")
+            .help("Do not descend deeper than N directory levels below the input directory while searching for files. Guards against runaway recursion on cyclic mounts. Defaults to 64 (earlier versions had no limit), a warning is printed if the limit is reached. Only supported for formats where one node equals one file")
+            .takes_value(true)
+            .conflicts_with("no_recurse")
+        )
+        .arg(
+            Arg::with_name("timeout")
+            .long("timeout")
+            .value_name("SECONDS")
+            .help("Stop searching for and reading node files after SECONDS and print the partial results of the files that were read so far. For PotreeConverter v2 datasets, the remaining hierarchy checks are skipped. Exits with code 124 if the analysis timed out, i.e. if any work was skipped")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("quiet")
+            .short("q")
+            .long("quiet")
+            .help("Don't print any progress information, e.g. the number of files discovered so far and the percentage of files read, or status messages such as skipped analyses. Warnings and hints are still printed")
+        )
+        .arg(
+            Arg::with_name("no_hints")
+            .long("no-hints")
+            .help("Don't print hints on how to improve the analysis, e.g. suggesting logarithmic buckets if almost all nodes end up in a single bucket of a linear histogram")
+        )
+        .arg(
+            Arg::with_name("progress_json")
+            .long("progress-json")
+            .help("Print progress updates to stderr as JSON lines with the fields 'event', 'percentage', 'throughput' and 'eta_seconds' instead of human-readable text, e.g. for driving the progress bar of a GUI")
+            .conflicts_with("quiet")
+        )
+        .arg(
+            Arg::with_name("threads")
+            .long("threads")
+            .value_name("N")
+            .help("Read the files with N threads instead of one thread per CPU core, e.g. to leave cores for other work or to reduce the load on network drives. Not supported for PotreeConverter v2")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("batch_size")
+            .long("batch-size")
+            .value_name("N")
+            .help("Read the files in batches of N files and only keep the point count and file size of each node, which bounds the memory usage for datasets with millions of files. Can't be combined with --dump-nodes, --check-integrity, --check-overlap, --estimate-uncompressed, --geojson, --tui and the parquet output format, which need all node information. With --warn-above, only the number of oversized nodes is reported, not their files. Not supported for PotreeConverter v2")
+            .takes_value(true)
+            .conflicts_with_all(&["dump_nodes", "check_integrity", "check_overlap", "estimate_uncompressed", "geojson", "tui"])
+        )
+        .arg(
+            Arg::with_name("work_stats")
+            .long("work-stats")
+            .help("Print how many files each worker thread read and how long it took, to find an imbalanced distribution of work. Not supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("epsg")
+            .long("epsg")
+            .help("Report the EPSG code of the coordinate reference system of the dataset. Fails if the dataset has no CRS with an EPSG code")
+        )
+        .arg(
+            Arg::with_name("require_epsg")
+            .long("require-epsg")
+            .value_name("CODE")
+            .help("Fail if the coordinate reference system of the dataset does not have the EPSG code CODE, e.g. 25832")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("evlr_count")
+            .long("evlr-count")
+            .value_name("USER_ID")
+            .help("Read the point count of each file from the extended VLR with the user ID USER_ID, which stores it as a 64-bit little-endian integer, instead of the header. Files without this EVLR use the point count of the header. Not supported for PotreeConverter v2")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("count_from_name")
+            .long("count-from-name")
+            .value_name("REGEX")
+            .help("Take the point count of each file from its file name instead of opening the file. REGEX is matched against the file name and must have a capture group named 'count' that supplies the point count, e.g. 'count(?P<count>[0-9]+)'. Files whose names don't match use the point count of the header. Not supported for PotreeConverter v2")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("max_file_size")
+            .long("max-file-size")
+            .value_name("BYTES")
+            .help("Skip all files that are larger than BYTES without opening them and list them in the results. Guards against corrupt files with huge sizes. Not supported for PotreeConverter v2")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("dedup_extensions")
+            .long("dedup-extensions")
+            .help("Count a node that has both a .las and a .laz file with the same name only once, using the .laz file, and report how many such pairs were collapsed. Fixes the doubled node count of datasets in the middle of a conversion. Not supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("mtime_stats")
+            .long("mtime-stats")
+            .help("Report the oldest and newest modification time of all node files and the span between them, and warn if the span exceeds --mtime-spread, which suggests that only some files were regenerated. Not supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("check_copc")
+            .long("check-copc")
+            .help("For COPC files, compare the number of points of the LAS header with the sum of the point counts of the COPC hierarchy and report the files where they disagree, which indicates a broken COPC export. Files that are not COPC files are ignored. Not supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("find_duplicates")
+            .long("find-duplicates")
+            .help("Report groups of node files with byte-identical content, e.g. from merging datasets, and the disk space they waste. Files of the same size are compared by hashes of their content. Not supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("mtime_spread")
+            .long("mtime-spread")
+            .value_name("SECONDS")
+            .help("Span of the modification times above which --mtime-stats warns about a partially rewritten dataset")
+            .default_value("3600")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("color_sample")
+            .long("color-sample")
+            .value_name("R")
+            .help("Read every Rth point of each file, starting with the first one, and report how many of them are not black and the range of their colors. A fast estimate of whether the dataset actually has colors. Not supported for PotreeConverter v2")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("use_lax")
+            .long("use-lax")
+            .help("Calculate a histogram of the points per spatial cell from the .lax indexes of LASzip/LAStools next to the LAS/LAZ files, without reading the point data. Uses the buckets of --histogram-lin, --histogram-log, --histogram-step, --histogram-bins or --histogram-quantile, or 10 logarithmic buckets. Files without a .lax index are counted, but not included. Not supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("trust_data")
+            .long("trust-data")
+            .help("For .las files whose header declares zero points, compute the point count from the file size and the point record length instead, and list the affected files in the results. Not supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("trace_nodes")
+            .long("trace-nodes")
+            .value_name("N")
+            .help("Print the first N raw node records of hierarchy.bin (type, child mask, point count, byte offset and byte size) to stderr. N defaults to 20 and is capped at 10000. Only supported for PotreeConverter v2")
+            .takes_value(true)
+            .min_values(0)
+            .max_values(1)
+            .require_equals(true)
+        )
+        .arg(
+            Arg::with_name("mean_fanout")
+            .long("mean-fanout")
+            .help("Calculate the mean number of children of all inner nodes. Values close to 8 indicate a dense subdivision, values well below 8 a sparse octree. Only supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("level_summary")
+            .long("level-summary")
+            .help("Print a table with the number of nodes, the number of points, the mean points per node and the share of all points for each octree level. Only supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("lod_curve")
+            .long("lod-curve")
+            .help("Print the cumulative number of points when loading the octree down to each level, i.e. the memory and bandwidth cost of each level of detail. Only supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("level_count")
+            .long("level-count")
+            .help("Count the number of distinct octree levels that contain nodes. Supported for PotreeConverter v2 and for datasets whose node files are named after their octree position, like Entwine ('D-X-Y-Z') or PotreeConverter v1.7 ('r0426')")
+        )
+        .arg(
+            Arg::with_name("check_hierarchy")
+            .long("check-hierarchy")
+            .help("Flag nodes that contain more points than their parent node, which often indicates a decoding or structural error. Only supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("check_children")
+            .long("check-children")
+            .help("Flag nodes that are referenced by the child mask of their parent or by a proxy node, but are missing from the hierarchy, which indicates a truncated or corrupt hierarchy. The remaining nodes are analyzed as usual. Only supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("check_attributes")
+            .long("check-attributes")
+            .help("Flag nodes whose byte size differs from their point count times the point size from the attribute schema in metadata.json, which indicates partially written nodes or a schema mismatch. Only supported for PotreeConverter v2 datasets with uncompressed points")
+        )
+        .arg(
+            Arg::with_name("round_trip")
+            .long("round-trip")
+            .help("Count the nodes both by scanning all records of hierarchy.bin and by walking the hierarchy from the root node, and report whether the counts agree. A mismatch indicates a decoding or structural error. Only supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("check_overlap")
+            .long("check-overlap")
+            .help("Report pairs of nodes whose bounding boxes overlap significantly although neither node is an ancestor of the other, which indicates a tiling bug. Not supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("dump_nodes")
+            .long("dump-nodes")
+            .help("Output the point count, size and bounds of every node")
+        )
+        .arg(
+            Arg::with_name("head")
+            .long("head")
+            .value_name("N")
+            .help("Only output the first N nodes of --dump-nodes in the order of --dump-sort. Can be combined with --tail")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("tail")
+            .long("tail")
+            .value_name("N")
+            .help("Only output the last N nodes of --dump-nodes in the order of --dump-sort. Can be combined with --head")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("dump_sort")
+            .long("dump-sort")
+            .value_name("ORDER")
+            .help("Order of the nodes of --dump-nodes: 'discovery' keeps the order in which the nodes were found, 'point-count' sorts them by ascending point count, so that --head shows the smallest and --tail the largest nodes")
+            .possible_values(&["discovery", "point-count"])
+            .default_value("discovery")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("estimate_uncompressed")
+            .long("estimate-uncompressed")
+            .help("Estimate the size of the point data in uncompressed form from the point record length of each node. Not supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("disk_usage")
+            .long("disk-usage")
+            .help("Sum the sizes of all files of the dataset on disk, split into the point data and the hierarchy and metadata files. Not supported for manifests, virtual point clouds and Entwine datasets with binary or zstandard node files")
+        )
+        .arg(
+            Arg::with_name("check_integrity")
+            .long("check-integrity")
+            .help("Check the dataset for inconsistencies, such as nodes whose point format differs from the other nodes. Not supported for PotreeConverter v2")
+        )
+        .arg(
+            Arg::with_name("since")
+            .long("since")
+            .value_name("TIMESTAMP")
+            .help("Only analyze files that were modified after TIMESTAMP, given in seconds since the Unix epoch. Not supported for PotreeConverter v2")
+            .takes_value(true)
+            .conflicts_with("since_file")
+        )
+        .arg(
+            Arg::with_name("since_file")
+            .long("since-file")
+            .value_name("FILE")
+            .help("Only analyze files that were modified after FILE. If FILE does not exist, all files are analyzed. Not supported for PotreeConverter v2")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("update_manifest")
+            .long("update-manifest")
+            .value_name("FILE")
+            .help("Merge the point counts of the analyzed files into the manifest FILE and count nodes and calculate histograms from the merged point counts. Not supported for PotreeConverter v2")
+            .takes_value(true)
+            .conflicts_with("manifest")
+        )
+        .arg(
+            Arg::with_name("status_file")
+            .long("status-file")
+            .value_name("FILE")
+            .help("Periodically overwrite FILE with a JSON document of the state of the run, the progress and all results that are available so far, for monitoring long runs from outside. The file is replaced atomically through a temporary file next to it. The progress is only reported for formats where one node equals one file")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("status_interval")
+            .long("status-interval")
+            .value_name("SECONDS")
+            .help("Minimum time between two progress updates of --status-file. New results are always written immediately")
+            .default_value("10")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("eta_window")
+            .long("eta-window")
+            .value_name("N")
+            .help("Number of recent progress updates that the ETA is calculated from. Defaults to 32")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("format")
+            .long("format")
+            .alias("output-format")
+            .help("Output format of the results. 'html' writes a self-contained HTML report to --output or stdout. 'parquet' writes the per-node information as a Parquet table to the file given by --output and requires the 'parquet' feature. 'ron' writes all results in Rusty Object Notation to --output or stdout. 'json' writes all results as JSON on a single line to --output or stdout, 'json-pretty' writes them as indented JSON. 'flat' writes one key=value line per value (e.g. node_count=12345) to --output or stdout. 'xml' writes all results as an XML element tree to --output or stdout and requires the 'xml' feature. 'influx' writes the main metrics as a single line of InfluxDB line protocol to --output or stdout. 'csv-wide' writes all scalar values as a single CSV row below a header row to --output or stdout. If --output already exists with the same header, only the row is appended, so one file collects the rows of many datasets. 'csv-levels' writes the level summary of --level-summary as CSV with one row per octree level to --output or stdout. 'csv-histogram-matrix' writes the point histogram as a CSV matrix with one row per bucket to --output or stdout. If --output already exists, the histogram is added as a new column named after --label, so one file collects the histograms of many datasets. This requires buckets with the same boundaries for all datasets, e.g. through --histogram-step or --histogram-bins")
+            .possible_values(&["text", "html", "parquet", "ron", "json", "json-pretty", "flat", "xml", "influx", "csv-wide", "csv-levels", "csv-histogram-matrix"])
+            .default_value("text")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("raw_numbers")
+            .long("raw-numbers")
+            .help("Print all numbers as plain numbers instead of using metric prefixes (e.g. 12345678 instead of 12.35 M)")
+        )
+        .arg(
+            Arg::with_name("tui")
+            .long("tui")
+            .help("Explore the results in an interactive terminal view instead of printing them: the summary, the histogram as a bar chart and the largest nodes. Implies --dump-nodes. Requires a binary built with '--features tui'")
+            .conflicts_with_all(&["format", "output"])
+        )
+        .arg(
+            Arg::with_name("explain")
+            .long("explain")
+            .help("Annotate each result with a short note on how it was computed, e.g. where the point counts were read from")
+        )
+        .arg(
+            Arg::with_name("label")
+            .long("label")
+            .value_name("LABEL")
+            .help("Label the results with LABEL and the start time of the run (RFC 3339), e.g. to collate the results of many runs. The label is printed as a header in text output and included in all other formats")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("output")
+            .short("o")
+            .long("output")
+            .value_name("FILE")
+            .help("The file to write the results to, if the output format requires one")
+            .takes_value(true)
+        )
         .get_matches();
 
-    let file = matches
-        .value_of("input")
-        .expect("Argument --input was missing!");
-    let path = PathBuf::from_str(file)?;
+    let bench = matches
+        .subcommand_matches("bench")
+        .map(|bench| BenchConfig {
+            input_dir: PathBuf::from(bench.value_of("input").unwrap_or_default()),
+            verify_points: bench.is_present("verify_points"),
+        });
+    let path = PathBuf::from(matches.value_of("input").unwrap_or_default());
+    let manifest_file = matches.value_of("manifest").map(PathBuf::from);
+    let index_file = matches.value_of("read_index").map(PathBuf::from);
+    let data_roots = matches
+        .values_of("data_roots")
+        .map(|roots| roots.map(PathBuf::from).collect())
+        .unwrap_or_default();
+    let files = match matches.value_of("files_from") {
+        Some(source) => Some(read_file_list(source)?),
+        None => None,
+    };
+    let assume_sorted = matches.is_present("assume_sorted");
 
     let count_nodes = matches.is_present("count_nodes");
+    let gini = matches.is_present("gini");
+    let leaf_point_ratio = matches.is_present("leaf_point_ratio");
+    let estimate_uncompressed = matches.is_present("estimate_uncompressed");
+    let trace_nodes = if matches.is_present("trace_nodes") {
+        let num_traced_nodes = match matches.value_of("trace_nodes") {
+            Some(_) => value_t!(matches, "trace_nodes", usize)?,
+            None => DEFAULT_TRACED_NODES,
+        };
+        Some(num_traced_nodes.min(MAX_TRACED_NODES))
+    } else {
+        None
+    };
+    let record_layout = if matches.is_present("node_record_size")
+        || matches.is_present("point_count_offset")
+        || matches.is_present("point_count_width")
+    {
+        let point_count_offset = if matches.is_present("point_count_offset") {
+            value_t!(matches, "point_count_offset", usize)?
+        } else {
+            2
+        };
+        let point_count_width = if matches.is_present("point_count_width") {
+            value_t!(matches, "point_count_width", usize)?
+        } else {
+            4
+        };
+        let record_size = if matches.is_present("node_record_size") {
+            value_t!(matches, "node_record_size", usize)?
+        } else {
+            point_count_offset + point_count_width + 16
+        };
+        Some(RecordLayout::custom(
+            record_size,
+            point_count_offset,
+            point_count_width,
+        )?)
+    } else {
+        None
+    };
+    let walk_max_depth = if matches.is_present("walk_max_depth") {
+        let walk_max_depth = value_t!(matches, "walk_max_depth", usize)?;
+        if walk_max_depth == 0 {
+            return Err(anyhow!("Argument walk-max-depth must be greater than 0!"));
+        }
+        Some(walk_max_depth)
+    } else {
+        None
+    };
+    let max_depth = if matches.is_present("max_depth") {
+        Some(value_t!(matches, "max_depth", u8)?)
+    } else {
+        None
+    };
+    let timeout = if matches.is_present("timeout") {
+        Some(Duration::from_secs(value_t!(matches, "timeout", u64)?))
+    } else {
+        None
+    };
+    let require_epsg = if matches.is_present("require_epsg") {
+        Some(value_t!(matches, "require_epsg", u32)?)
+    } else {
+        None
+    };
+    let warn_above = if matches.is_present("warn_above") {
+        Some(value_t!(matches, "warn_above", u64)?)
+    } else {
+        None
+    };
+    let downsample_target = if matches.is_present("downsample_target") {
+        let downsample_target = value_t!(matches, "downsample_target", u64)?;
+        if downsample_target == 0 {
+            return Err(anyhow!(
+                "Argument downsample-target must be greater than 0!"
+            ));
+        }
+        Some(downsample_target)
+    } else {
+        None
+    };
+    let color_sample = if matches.is_present("color_sample") {
+        let color_sample = value_t!(matches, "color_sample", u64)?;
+        if color_sample == 0 {
+            return Err(anyhow!("Argument color-sample must be greater than 0!"));
+        }
+        Some(color_sample)
+    } else {
+        None
+    };
+    let max_file_size = if matches.is_present("max_file_size") {
+        Some(value_t!(matches, "max_file_size", u64)?)
+    } else {
+        None
+    };
+    let gsd_check = if matches.is_present("gsd_check") {
+        let tolerance = if matches.is_present("gsd_tolerance") {
+            value_t!(matches, "gsd_tolerance", f64)?
+        } else {
+            0.5
+        };
+        if tolerance < 0.0 {
+            return Err(anyhow!("Argument gsd-tolerance must not be negative!"));
+        }
+        Some(tolerance)
+    } else {
+        None
+    };
+    let check_integrity = matches.is_present("check_integrity");
     let calculate_linear_histogram = matches.is_present("histogram_lin");
     let calculate_logarithmic_histogram = matches.is_present("histogram_log");
     if calculate_linear_histogram && calculate_logarithmic_histogram {
         panic!("Arguments histogram-lin and histogram-log are mutually exclusive!");
     }
     let histogram_config = if calculate_linear_histogram {
-        Some(HistogramConfig::Linear(value_t!(
-            matches,
-            "histogram_lin",
-            usize
-        )?))
+        let num_buckets = value_t!(matches, "histogram_lin", usize)?;
+        if num_buckets == 0 {
+            return Err(anyhow!("Argument histogram-lin must be greater than 0!"));
+        }
+        Some(HistogramConfig::Linear(num_buckets))
     } else if calculate_logarithmic_histogram {
-        Some(HistogramConfig::Logarithmic(value_t!(
-            matches,
-            "histogram_log",
-            usize
+        let num_buckets = value_t!(matches, "histogram_log", usize)?;
+        if num_buckets == 0 {
+            return Err(anyhow!("Argument histogram-log must be greater than 0!"));
+        }
+        Some(HistogramConfig::Logarithmic(num_buckets))
+    } else if let Some(histogram_step) = matches.value_of("histogram_step") {
+        Some(HistogramConfig::Custom(parse_histogram_step(
+            histogram_step,
         )?))
+    } else if matches.is_present("histogram_bins") {
+        let boundaries = values_t!(matches, "histogram_bins", usize)?;
+        if let Some(pair) = boundaries.windows(2).find(|pair| pair[0] >= pair[1]) {
+            return Err(anyhow!(
+                "Argument histogram-bins must be strictly increasing, but {} is followed by {}!",
+                pair[0],
+                pair[1]
+            ));
+        }
+        Some(HistogramConfig::Custom(boundaries))
+    } else if matches.is_present("histogram_quantile") {
+        let num_buckets = value_t!(matches, "histogram_quantile", usize)?;
+        if num_buckets == 0 {
+            return Err(anyhow!(
+                "Argument histogram-quantile must be greater than 0!"
+            ));
+        }
+        Some(HistogramConfig::Quantile(num_buckets))
+    } else {
+        None
+    };
+
+    let count_range = matches
+        .value_of("count_range")
+        .map(parse_count_range)
+        .transpose()?;
+
+    let sparkline = matches.is_present("sparkline");
+    if sparkline && histogram_config.is_none() {
+        return Err(anyhow!(
+            "Argument sparkline requires one of histogram-lin, histogram-log, histogram-step, histogram-bins or histogram-quantile!"
+        ));
+    }
+
+    let percentiles = if matches.is_present("percentiles") {
+        values_t!(matches, "percentiles", f64)?
+    } else {
+        vec![]
+    };
+    if let Some(p) = percentiles.iter().find(|p| !(0.0..=100.0).contains(*p)) {
+        return Err(anyhow!(
+            "Argument percentiles must only contain values between 0 and 100, got {}!",
+            p
+        ));
+    }
+
+    let approx_quantiles = if matches.is_present("approx_quantiles") {
+        let relative_accuracy = match matches.value_of("approx_quantiles") {
+            Some(_) => value_t!(matches, "approx_quantiles", f64)?,
+            None => QuantileSketch::DEFAULT_RELATIVE_ACCURACY,
+        };
+        if relative_accuracy <= 0.0 || relative_accuracy >= 1.0 {
+            return Err(anyhow!(
+                "Argument approx-quantiles must be greater than 0 and less than 1!"
+            ));
+        }
+        Some(relative_accuracy)
+    } else {
+        None
+    };
+
+    let size_histogram_config = if matches.is_present("histogram_size") {
+        let num_buckets = match matches.value_of("histogram_size") {
+            Some(_) => Some(value_t!(matches, "histogram_size", usize)?),
+            None => None,
+        };
+        if num_buckets == Some(0) {
+            return Err(anyhow!("Argument histogram-size must be greater than 0!"));
+        }
+        Some(size_histogram_config(
+            histogram_config.as_ref(),
+            num_buckets,
+        ))
     } else {
         None
     };
 
+    let mtime_stats = if matches.is_present("mtime_stats") {
+        Some(value_t!(matches, "mtime_spread", u64)?)
+    } else {
+        None
+    };
+
+    let modified_since = if matches.is_present("since") {
+        let seconds = value_t!(matches, "since", u64)?;
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+    } else if let Some(since_file) = matches.value_of("since_file") {
+        match std::fs::metadata(since_file) {
+            Ok(metadata) => Some(metadata.modified()?),
+            Err(_) => {
+                eprintln!("{} does not exist, analyzing all files", since_file);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let update_manifest = matches.value_of("update_manifest").map(PathBuf::from);
+
+    let exclude = matches
+        .values_of("exclude")
+        .map(|patterns| {
+            patterns
+                .map(|pattern| {
+                    Pattern::new(pattern)
+                        .with_context(|| format!("Invalid exclude pattern {}", pattern))
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    let count_from_name = matches
+        .value_of("count_from_name")
+        .map(|pattern| -> Result<Regex> {
+            let regex = Regex::new(pattern)
+                .with_context(|| format!("Invalid count-from-name regex {}", pattern))?;
+            if !regex.capture_names().any(|name| name == Some("count")) {
+                return Err(anyhow!(
+                    "The count-from-name regex {} has no capture group named 'count'!",
+                    pattern
+                ));
+            }
+            Ok(regex)
+        })
+        .transpose()?;
+
+    let threads = if matches.is_present("threads") {
+        let threads = value_t!(matches, "threads", usize)?;
+        if threads == 0 {
+            return Err(anyhow!("Argument threads must be greater than 0!"));
+        }
+        Some(threads)
+    } else {
+        None
+    };
+
+    let batch_size = if matches.is_present("batch_size") {
+        let batch_size = value_t!(matches, "batch_size", usize)?;
+        if batch_size == 0 {
+            return Err(anyhow!("Argument batch-size must be greater than 0!"));
+        }
+        Some(batch_size)
+    } else {
+        None
+    };
+
+    let status_file = match matches.value_of("status_file") {
+        Some(status_file) => {
+            let interval = value_t!(matches, "status_interval", u64)?;
+            if interval == 0 {
+                return Err(anyhow!("Argument status-interval must be greater than 0!"));
+            }
+            Some(Arc::new(StatusFile::new(
+                status_file,
+                Duration::from_secs(interval),
+            )))
+        }
+        None => None,
+    };
+
+    let dump_head = if matches.is_present("head") {
+        let dump_head = value_t!(matches, "head", usize)?;
+        if dump_head == 0 {
+            return Err(anyhow!("Argument head must be greater than 0!"));
+        }
+        Some(dump_head)
+    } else {
+        None
+    };
+    let dump_tail = if matches.is_present("tail") {
+        let dump_tail = value_t!(matches, "tail", usize)?;
+        if dump_tail == 0 {
+            return Err(anyhow!("Argument tail must be greater than 0!"));
+        }
+        Some(dump_tail)
+    } else {
+        None
+    };
+    let dump_sort = match matches.value_of("dump_sort") {
+        Some("point-count") => DumpSort::PointCount,
+        _ => DumpSort::Discovery,
+    };
+
+    let eta_window = if matches.is_present("eta_window") {
+        let eta_window = value_t!(matches, "eta_window", usize)?;
+        if eta_window < 2 {
+            return Err(anyhow!("Argument eta-window must be at least 2!"));
+        }
+        Some(eta_window)
+    } else {
+        None
+    };
+
+    let output_format = value_t!(matches, "format", OutputFormat)?;
+    let output_file = matches.value_of("output").map(PathBuf::from);
+    let raw_numbers = matches.is_present("raw_numbers");
+    let tui = matches.is_present("tui");
+    if tui && !cfg!(feature = "tui") {
+        // Fail before the analysis instead of after it
+        return Err(anyhow!(
+            "--tui is not supported by this binary. Rebuild with '--features tui' to enable it!"
+        ));
+    }
+    let dump_nodes = match output_format {
+        OutputFormat::Text
+        | OutputFormat::Html
+        | OutputFormat::Ron
+        | OutputFormat::Json
+        | OutputFormat::JsonPretty
+        | OutputFormat::Flat
+        | OutputFormat::Xml
+        | OutputFormat::Influx
+        | OutputFormat::CsvWide
+        | OutputFormat::CsvLevels
+        | OutputFormat::CsvHistogramMatrix => tui || matches.is_present("dump_nodes"),
+        OutputFormat::Parquet => {
+            if output_file.is_none() {
+                return Err(anyhow!("Output format parquet requires --output!"));
+            }
+            if batch_size.is_some() {
+                return Err(anyhow!("Output format parquet writes all node information, so it can't be combined with --batch-size!"));
+            }
+            true
+        }
+    };
+
     Ok(Config {
         input_dir: path,
-        count_nodes,
-        histogram_config,
+        manifest_file,
+        index_file,
+        data_roots,
+        files,
+        options: AnalysisOptions {
+            count_nodes,
+            histogram_config,
+            sparkline,
+            size_histogram_config,
+            gini,
+            moments: matches.is_present("moments"),
+            stats: matches.is_present("stats"),
+            percentiles,
+            total_points: matches.is_present("total_points"),
+            count_range,
+            approx_quantiles,
+            warn_above,
+            downsample_target,
+            leaf_point_ratio,
+            dump_nodes,
+            dump_head,
+            dump_tail,
+            dump_sort,
+            estimate_uncompressed,
+            disk_usage: matches.is_present("disk_usage"),
+            check_integrity,
+            mean_fanout: matches.is_present("mean_fanout"),
+            level_summary: matches.is_present("level_summary"),
+            lod_curve: matches.is_present("lod_curve"),
+            level_count: matches.is_present("level_count"),
+            check_hierarchy: matches.is_present("check_hierarchy"),
+            check_children: matches.is_present("check_children"),
+            round_trip: matches.is_present("round_trip"),
+            check_attributes: matches.is_present("check_attributes"),
+            check_overlap: matches.is_present("check_overlap"),
+            gsd_check,
+            trace_nodes,
+            quiet: matches.is_present("quiet"),
+            no_hints: matches.is_present("no_hints"),
+            cancelled: Default::default(),
+            incomplete: Default::default(),
+            progress_json: matches.is_present("progress_json"),
+            status_file,
+            work_stats: matches.is_present("work_stats"),
+            report_epsg: matches.is_present("epsg"),
+            require_epsg,
+            evlr_count: matches.value_of("evlr_count").map(String::from),
+            count_from_name,
+            max_file_size,
+            dedup_extensions: matches.is_present("dedup_extensions"),
+            trust_data: matches.is_present("trust_data"),
+            color_sample,
+            use_lax: matches.is_present("use_lax"),
+            mtime_stats,
+            find_duplicates: matches.is_present("find_duplicates"),
+            check_copc: matches.is_present("check_copc"),
+            node_prefix: matches.value_of("node_prefix").map(String::from),
+            exclude,
+            no_recurse: matches.is_present("no_recurse"),
+            walk_max_depth,
+            max_depth,
+            record_layout,
+            infer_record_size: matches.is_present("infer_record_size"),
+            modified_since,
+            write_index: matches.value_of("write_index").map(PathBuf::from),
+            geojson: matches.value_of("geojson").map(PathBuf::from),
+            update_manifest,
+            assume_sorted,
+            eta_window,
+            threads,
+            batch_size,
+        },
+        output_format,
+        output_file,
+        raw_numbers,
+        tui,
+        explain: matches.is_present("explain"),
+        label: matches.value_of("label").map(String::from),
+        timeout,
+        detect_only: matches.is_present("detect_only"),
+        assert_format: if matches.is_present("assert_format") {
+            Some(value_t!(matches, "assert_format", DatasetFormat)?)
+        } else {
+            None
+        },
+        bench,
     })
 }
 
-fn is_entwine_dataset(root_dir: &Path) -> bool {
-    let ept_data_dir = root_dir.to_owned().join("ept-data");
-    ept_data_dir.exists()
+/// Parses the 'min:max' argument of --count-range into an inclusive range of point counts
+fn parse_count_range(count_range: &str) -> Result<(u64, u64)> {
+    let (min, max) = match count_range.split_once(':') {
+        Some((min, max)) => (min.trim().parse::<u64>(), max.trim().parse::<u64>()),
+        None => {
+            return Err(anyhow!(
+                "Argument count-range must have the form min:max, got {}!",
+                count_range
+            ))
+        }
+    };
+    match (min, max) {
+        (Ok(min), Ok(max)) if min <= max => Ok((min, max)),
+        (Ok(_), Ok(_)) => Err(anyhow!(
+            "Minimum of argument count-range must not be greater than its maximum!"
+        )),
+        _ => Err(anyhow!(
+            "Argument count-range must have the form min:max with non-negative integers, got {}!",
+            count_range
+        )),
+    }
 }
 
-fn is_potree_legacy_dataset(root_dir: &Path) -> bool {
-    let cloud_js_path = root_dir.to_owned().join("cloud.js");
-    cloud_js_path.exists()
+/// Derives the configuration of the node size histogram from the point histogram, overriding its number of buckets
+/// with `num_buckets`. Custom buckets of the point histogram are point counts, so they don't apply to node sizes
+fn size_histogram_config(
+    point_histogram_config: Option<&HistogramConfig>,
+    num_buckets: Option<usize>,
+) -> HistogramConfig {
+    match point_histogram_config {
+        Some(HistogramConfig::Linear(point_buckets)) => {
+            HistogramConfig::Linear(num_buckets.unwrap_or(*point_buckets))
+        }
+        Some(HistogramConfig::Logarithmic(point_buckets)) => {
+            HistogramConfig::Logarithmic(num_buckets.unwrap_or(*point_buckets))
+        }
+        Some(HistogramConfig::Quantile(point_buckets)) => {
+            HistogramConfig::Quantile(num_buckets.unwrap_or(*point_buckets))
+        }
+        Some(HistogramConfig::Custom(_)) | None => {
+            HistogramConfig::Logarithmic(num_buckets.unwrap_or(DEFAULT_SIZE_HISTOGRAM_BUCKETS))
+        }
+    }
 }
 
-fn is_potree_v2_dataset(root_dir: &Path) -> bool {
-    let hierarchy_bin_path = root_dir.to_owned().join("hierarchy.bin");
-    hierarchy_bin_path.exists()
+/// Parses the 'start:stop:step' argument of --histogram-step into the bucket boundaries start, start + step, ...,
+/// stop. If the range is not a multiple of step, the last bucket ends at stop
+fn parse_histogram_step(histogram_step: &str) -> Result<Vec<usize>> {
+    let values = histogram_step
+        .split(':')
+        .map(|value| value.trim().parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| {
+            anyhow!(
+                "Argument histogram-step must have the form start:stop:step with non-negative integers, got {}!",
+                histogram_step
+            )
+        })?;
+    let (start, stop, step) = match values.as_slice() {
+        [start, stop, step] => (*start, *stop, *step),
+        _ => {
+            return Err(anyhow!(
+                "Argument histogram-step must have the form start:stop:step, got {}!",
+                histogram_step
+            ))
+        }
+    };
+    if step == 0 {
+        return Err(anyhow!(
+            "Step of argument histogram-step must be greater than 0!"
+        ));
+    }
+    if stop <= start {
+        return Err(anyhow!(
+            "Stop of argument histogram-step must be greater than start!"
+        ));
+    }
+
+    let mut boundaries = (start..stop).step_by(step).collect::<Vec<_>>();
+    boundaries.push(stop);
+    Ok(boundaries)
 }
 
-fn make_analyzer(config: Config) -> Result<Box<dyn Analyzer>> {
-    if is_entwine_dataset(&config.input_dir) || is_potree_legacy_dataset(&config.input_dir) {
-        let ept_data_dir = config.input_dir.to_owned().join("ept-data");
-        let analyzer =
-            MultiFileAnalyzer::new(ept_data_dir, config.count_nodes, config.histogram_config)?;
-        Ok(Box::new(analyzer))
-    } else if is_potree_v2_dataset(&config.input_dir) {
-        let analyzer = PotreeV2FormatAnalyzer::new(
-            config.input_dir,
-            config.count_nodes,
-            config.histogram_config,
-        )?;
-        Ok(Box::new(analyzer))
+/// Reads a newline-separated list of file paths from the file `source`, or from stdin if `source` is '-'
+fn read_file_list(source: &str) -> Result<Vec<PathBuf>> {
+    let reader: Box<dyn BufRead> = if source == "-" {
+        Box::new(BufReader::new(std::io::stdin()))
     } else {
-        Err(anyhow!("Tiling format not recognized!"))
+        Box::new(BufReader::new(File::open(source).with_context(|| {
+            format!("Could not open file list {}", source)
+        })?))
+    };
+
+    let mut files = vec![];
+    for line in reader.lines() {
+        let line = line.context("Could not read file list")?;
+        let path = line.trim();
+        if !path.is_empty() {
+            files.push(PathBuf::from(path));
+        }
+    }
+    Ok(files)
+}
+
+/// Runs all format checks against `input_dir` and prints which of them matched with which confidence, together with
+/// the file or directory that each check is based on, and which format wins
+fn print_detected_format(input_dir: &Path) {
+    let checks = DatasetFormat::all()
+        .map(|format| (format, format.check(input_dir).unwrap_or(None)))
+        .collect::<Vec<_>>();
+
+    for (format, confidence) in checks.iter() {
+        let result = match confidence {
+            Some(confidence) => format!("matched with confidence {}", confidence),
+            None => "no match".to_owned(),
+        };
+        println!(
+            "{}: {} ({})",
+            format.description(),
+            result,
+            format.key_path(input_dir).display()
+        );
+    }
+    match detect::best_match(&checks) {
+        Some(format) => println!("Detected format: {}", format.description()),
+        None => println!("Tiling format not recognized!"),
+    }
+}
+
+/// Exits with `FORMAT_MISMATCH_EXIT_CODE` if the dataset in `input_dir` is not detected as `expected_format`
+fn assert_format(input_dir: &Path, expected_format: DatasetFormat) {
+    let detected_format = match detect::detect_format(input_dir) {
+        Ok(format) => format,
+        Err(error) => {
+            eprintln!("Could not detect the format of the dataset: {:#}", error);
+            None
+        }
+    };
+    if detected_format != Some(expected_format) {
+        eprintln!(
+            "Expected a {} dataset, but detected {}",
+            expected_format.description(),
+            detected_format.map_or("no known format", DatasetFormat::description)
+        );
+        std::process::exit(FORMAT_MISMATCH_EXIT_CODE);
     }
 }
 
 fn main() -> Result<()> {
     let config = get_config()?;
-    let analyzer = make_analyzer(config)?;
-    let results = analyzer.run()?;
-    results.iter().for_each(|result| print!("{}", result));
+    if let Some(bench) = config.bench {
+        let analyzer = MultiFileAnalyzer::builder()
+            .root_dir(bench.input_dir)
+            .options(config.options)
+            .build()?;
+        let summary = analyzer.benchmark(bench.verify_points)?;
+        if config.raw_numbers {
+            print!("{:#}", summary);
+        } else {
+            print!("{}", summary);
+        }
+        return Ok(());
+    }
+    if config.detect_only {
+        print_detected_format(&config.input_dir);
+    }
+    if let Some(expected_format) = config.assert_format {
+        assert_format(&config.input_dir, expected_format);
+    }
+    if config.detect_only {
+        return Ok(());
+    }
 
+    let cancelled = config.options.cancelled.clone();
+    let incomplete = config.options.incomplete.clone();
+    let status_file = config.options.status_file.clone();
+    if let Some(timeout) = config.timeout {
+        let cancelled = cancelled.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(timeout);
+            cancelled.store(true, Ordering::Relaxed);
+        });
+    }
+
+    // Name of the input format, e.g. for tagging the results in the Influx output
+    let (analyzer, input_format): (Box<dyn Analyzer>, &str) =
+        if let Some(manifest_file) = config.manifest_file {
+            (
+                Box::new(ManifestAnalyzer::new(manifest_file, config.options)?),
+                "manifest",
+            )
+        } else if let Some(index_file) = config.index_file {
+            (
+                Box::new(ManifestAnalyzer::from_index(index_file, config.options)?),
+                "index",
+            )
+        } else if let Some(files) = config.files {
+            (
+                Box::new(
+                    MultiFileAnalyzer::builder()
+                        .files(files)
+                        .options(config.options)
+                        .build()?,
+                ),
+                "files",
+            )
+        } else if !config.data_roots.is_empty() {
+            (
+                Box::new(
+                    config
+                        .data_roots
+                        .iter()
+                        .fold(MultiFileAnalyzer::builder(), |builder, root_dir| {
+                            builder.root_dir(root_dir)
+                        })
+                        .options(config.options)
+                        .build()?,
+                ),
+                "files",
+            )
+        } else {
+            let (analyzer, format) = detect::make_analyzer(config.input_dir, config.options)?;
+            (analyzer, format.id())
+        };
+    let provenance = analyzer.provenance();
+    let (explain_results, raw_numbers) = (config.explain, config.raw_numbers);
+    let explain = |result| {
+        if explain_results {
+            explain_result(result, &provenance)
+        } else {
+            result
+        }
+    };
+    // Text output is printed as soon as each result is available, so that e.g. the node count shows up while a
+    // histogram is still being calculated. All other formats need all results at once
+    let stream_results = matches!(config.output_format, OutputFormat::Text) && !config.tui;
+    let label = config.label.map(RunLabel::now);
+    if stream_results {
+        if let Some(label) = label.as_ref() {
+            output::print_label(label);
+        }
+    }
+    let mut results = vec![];
+    let outcome = analyzer
+        .run_with(&mut |result| {
+            if let Some(status_file) = status_file.as_ref() {
+                status_file.add_result(&result);
+            }
+            if stream_results {
+                output::print_text(&explain(result), raw_numbers);
+            } else {
+                results.push(explain(result));
+            }
+        })
+        .map_err(anyhow::Error::from);
+    // The timeout only counts if it made the analyzer skip work, not if it expired after all files were read
+    let timed_out = incomplete.load(Ordering::Relaxed);
+    if let Some(status_file) = status_file.as_ref() {
+        status_file.finish(outcome.as_ref().err(), timed_out)?;
+    }
+    outcome?;
+    if timed_out {
+        eprintln!(
+            "Analysis timed out after {} seconds, the results are partial and only contain the work that was done so far",
+            config.timeout.unwrap_or_default().as_secs()
+        );
+    }
+
+    if config.tui {
+        output::show_tui(&results, config.raw_numbers)?;
+    } else if !stream_results {
+        output::write_results(
+            &results,
+            &config.output_format,
+            config.output_file.as_deref(),
+            config.raw_numbers,
+            label.as_ref(),
+            input_format,
+        )?;
+    }
+
+    if timed_out {
+        std::process::exit(TIMEOUT_EXIT_CODE);
+    }
     Ok(())
 }
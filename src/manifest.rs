@@ -0,0 +1,107 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// A single node within a manifest
+pub struct ManifestEntry {
+    /// Identifier of the node, if the manifest contains one
+    pub id: Option<String>,
+    /// The number of points in the node
+    pub point_count: usize,
+}
+
+/// Reads the point counts of all nodes from a manifest file. See `read_entries` for the format of the manifest
+pub fn read_point_counts<P: AsRef<Path>>(path: P) -> Result<Vec<usize>> {
+    Ok(read_entries(path)?
+        .into_iter()
+        .map(|entry| entry.point_count)
+        .collect())
+}
+
+/// Reads all nodes from a manifest file. The manifest contains one node per line, either as a plain integer or
+/// as comma-separated values where the last value is the point count and everything before it is the identifier
+/// of the node (e.g. `r0426.laz,12345`). Empty lines are skipped, as is a header line at the start of the file
+pub fn read_entries<P: AsRef<Path>>(path: P) -> Result<Vec<ManifestEntry>> {
+    let reader = BufReader::new(
+        File::open(path.as_ref())
+            .with_context(|| format!("Could not open manifest {}", path.as_ref().display()))?,
+    );
+
+    let mut entries = vec![];
+    for (line_idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (id, count_str) = match line.rfind(',') {
+            Some(separator_idx) => (
+                Some(line[..separator_idx].to_owned()),
+                line[separator_idx + 1..].trim(),
+            ),
+            None => (None, line),
+        };
+        match count_str.parse::<usize>() {
+            Ok(point_count) => entries.push(ManifestEntry { id, point_count }),
+            Err(_) if line_idx == 0 => continue,
+            Err(_) => {
+                return Err(anyhow!(
+                    "Invalid point count '{}' in line {} of manifest {}",
+                    count_str,
+                    line_idx + 1,
+                    path.as_ref().display()
+                ))
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Writes the given entries to a manifest file at `path`, in the format that `read_entries` expects
+pub fn write_entries<P: AsRef<Path>>(path: P, entries: &[ManifestEntry]) -> Result<()> {
+    let mut writer = BufWriter::new(
+        File::create(path.as_ref())
+            .with_context(|| format!("Could not create manifest {}", path.as_ref().display()))?,
+    );
+    for entry in entries.iter() {
+        match &entry.id {
+            Some(id) => writeln!(writer, "{},{}", id, entry.point_count)?,
+            None => writeln!(writer, "{}", entry.point_count)?,
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Merges `new_entries` into `entries`. Entries with the same identifier are replaced, all other new entries are
+/// appended
+pub fn merge_entries<I: IntoIterator<Item = ManifestEntry>>(
+    entries: &mut Vec<ManifestEntry>,
+    new_entries: I,
+) {
+    let mut indices_by_id = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, entry)| entry.id.clone().map(|id| (id, idx)))
+        .collect::<HashMap<_, _>>();
+
+    for new_entry in new_entries {
+        let existing_idx = new_entry
+            .id
+            .as_ref()
+            .and_then(|id| indices_by_id.get(id).copied());
+        match existing_idx {
+            Some(idx) => entries[idx] = new_entry,
+            None => {
+                if let Some(id) = &new_entry.id {
+                    indices_by_id.insert(id.clone(), entries.len());
+                }
+                entries.push(new_entry);
+            }
+        }
+    }
+}
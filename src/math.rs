@@ -29,3 +29,77 @@ pub fn mean_and_std_deviation(data: &[usize]) -> Option<(f64, f64)> {
         _ => None,
     }
 }
+
+/// Computes the Gini coefficient of the given values, which must be sorted in ascending order. Returns 0 if all
+/// values are zero
+pub fn gini_coefficient(sorted_data: &[usize]) -> Option<f64> {
+    let count = sorted_data.len();
+    if count == 0 {
+        return None;
+    }
+
+    let sum = sorted_data.iter().map(|&value| value as f64).sum::<f64>();
+    if sum == 0.0 {
+        return Some(0.0);
+    }
+
+    let weighted_sum = sorted_data
+        .iter()
+        .enumerate()
+        .map(|(idx, &value)| (idx + 1) as f64 * value as f64)
+        .sum::<f64>();
+
+    Some((2.0 * weighted_sum) / (count as f64 * sum) - (count as f64 + 1.0) / count as f64)
+}
+
+/// Computes the skewness and the excess kurtosis of the given values. The skewness is positive if the distribution
+/// has a long tail of large values, the excess kurtosis is 0 for a normal distribution and positive if the
+/// distribution has heavier tails. Returns 0 for both if all values are equal
+pub fn skewness_and_kurtosis(data: &[usize]) -> Option<(f64, f64)> {
+    let (data_mean, std_deviation) = mean_and_std_deviation(data)?;
+    if std_deviation == 0.0 {
+        return Some((0.0, 0.0));
+    }
+
+    let count = data.len() as f64;
+    let (third_moment, fourth_moment) =
+        data.iter()
+            .fold((0.0, 0.0), |(third_moment, fourth_moment), &value| {
+                let diff = value as f64 - data_mean;
+                (third_moment + diff.powi(3), fourth_moment + diff.powi(4))
+            });
+    let skewness = (third_moment / count) / std_deviation.powi(3);
+    let kurtosis = (fourth_moment / count) / std_deviation.powi(4) - 3.0;
+    Some((skewness, kurtosis))
+}
+
+/// Computes the percentage of the total of each of the given values, rounded to `decimals` decimal places such that
+/// the rounded percentages sum to exactly 100. Rounding each percentage on its own can make them sum to e.g. 99.9 or
+/// 100.1, so the values are rounded down and the remaining units are given to the values with the largest remainders
+/// (largest remainder method). Returns all zeros if the total is 0
+pub fn rounded_percentages(values: &[usize], decimals: u32) -> Vec<f64> {
+    let total = values.iter().sum::<usize>() as u128;
+    if total == 0 {
+        return vec![0.0; values.len()];
+    }
+
+    let units = 100 * 10_u128.pow(decimals);
+    let mut shares = values
+        .iter()
+        .map(|&value| value as u128 * units)
+        .map(|scaled| (scaled / total, scaled % total))
+        .collect::<Vec<_>>();
+    let assigned = shares.iter().map(|(share, _)| share).sum::<u128>();
+    let mut by_remainder = (0..shares.len()).collect::<Vec<_>>();
+    // Stable sort, so ties go to the values that come first
+    by_remainder.sort_by(|&a, &b| shares[b].1.cmp(&shares[a].1));
+    for &index in by_remainder.iter().take((units - assigned) as usize) {
+        shares[index].0 += 1;
+    }
+
+    let scale = 10_f64.powi(decimals as i32);
+    shares
+        .iter()
+        .map(|(share, _)| *share as f64 / scale)
+        .collect()
+}
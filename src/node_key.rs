@@ -0,0 +1,79 @@
+use std::path::Path;
+
+/// Position of a node within the octree, as encoded in the file names of the node files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeKey {
+    /// Entwine names its node files 'D-X-Y-Z', where D is the depth of the node and X, Y and Z are the indices of the
+    /// node within the grid of its depth
+    Entwine { depth: u32, x: u64, y: u64, z: u64 },
+    /// PotreeConverter names its nodes 'r' for the root node followed by the index (0-7) of the child node for each
+    /// level, e.g. 'r0426'
+    Potree(String),
+}
+
+impl NodeKey {
+    /// Parses the key from the file name of a node file, ignoring the extension. Returns `None` if the file name
+    /// follows neither naming convention
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let name = path.as_ref().file_stem()?.to_str()?;
+        Self::from_entwine_name(name).or_else(|| Self::from_potree_name(name))
+    }
+
+    fn from_entwine_name(name: &str) -> Option<Self> {
+        let mut parts = name.split('-');
+        let depth = parts.next()?.parse().ok()?;
+        let x = parts.next()?.parse().ok()?;
+        let y = parts.next()?.parse().ok()?;
+        let z = parts.next()?.parse().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(NodeKey::Entwine { depth, x, y, z })
+    }
+
+    fn from_potree_name(name: &str) -> Option<Self> {
+        let child_indices = name.strip_prefix('r')?;
+        if child_indices.chars().all(|c| ('0'..='7').contains(&c)) {
+            Some(NodeKey::Potree(name.to_owned()))
+        } else {
+            None
+        }
+    }
+
+    /// The octree level of the node, where the root node is level 0
+    pub fn depth(&self) -> u32 {
+        match self {
+            NodeKey::Entwine { depth, .. } => *depth,
+            NodeKey::Potree(name) => (name.len() - 1) as u32,
+        }
+    }
+
+    /// Is this node an ancestor of `other`, i.e. is `other` contained in the subtree of this node? A node is not its
+    /// own ancestor
+    pub fn is_ancestor_of(&self, other: &NodeKey) -> bool {
+        match (self, other) {
+            (
+                NodeKey::Entwine { depth, x, y, z },
+                NodeKey::Entwine {
+                    depth: other_depth,
+                    x: other_x,
+                    y: other_y,
+                    z: other_z,
+                },
+            ) => {
+                if other_depth <= depth {
+                    return false;
+                }
+                let shift = other_depth - depth;
+                let parent_index = |index: u64| index.checked_shr(shift).unwrap_or_default();
+                parent_index(*other_x) == *x
+                    && parent_index(*other_y) == *y
+                    && parent_index(*other_z) == *z
+            }
+            (NodeKey::Potree(name), NodeKey::Potree(other_name)) => {
+                other_name.len() > name.len() && other_name.starts_with(name.as_str())
+            }
+            _ => false,
+        }
+    }
+}
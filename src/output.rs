@@ -0,0 +1,748 @@
+use crate::analyzer::AnalyzerResult;
+use crate::html;
+#[cfg(feature = "parquet")]
+use crate::parquet_writer;
+#[cfg(feature = "tui")]
+use crate::tui;
+#[cfg(feature = "xml")]
+use crate::xml_writer;
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Format in which the results are written
+pub enum OutputFormat {
+    /// Human-readable text on stdout
+    Text,
+    /// Self-contained HTML report with a table of all results and a bar chart for each histogram
+    Html,
+    /// Table of per-node information in a Parquet file. All other results are written as text
+    Parquet,
+    /// All results in Rusty Object Notation, for reading them back into Rust tools
+    Ron,
+    /// All results as JSON on a single line, for piping them into other tools
+    Json,
+    /// All results as indented JSON, for reading them
+    JsonPretty,
+    /// One `key=value` line per value, e.g. `node_count=12345` or `histogram.buckets.0.count=42`, for parsing the
+    /// results in shell scripts
+    Flat,
+    /// All results as an XML element tree, e.g. `<report><nodeCount>12345</nodeCount></report>`, for XML-based GIS
+    /// pipelines
+    Xml,
+    /// The main metrics as a single line of InfluxDB line protocol, for tracking them in a time-series database
+    Influx,
+    /// All scalar values as a single CSV row below a header row, for tracking many datasets side by side in a
+    /// spreadsheet
+    CsvWide,
+    /// The level summary as CSV with one row per octree level, for comparing tiling configurations level by level
+    CsvLevels,
+    /// The point histograms of several datasets as a CSV matrix with one row per bucket and one column per dataset,
+    /// for charting the distributions of several datasets together
+    CsvHistogramMatrix,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "html" => Ok(OutputFormat::Html),
+            "parquet" => Ok(OutputFormat::Parquet),
+            "ron" => Ok(OutputFormat::Ron),
+            "json" => Ok(OutputFormat::Json),
+            "json-pretty" => Ok(OutputFormat::JsonPretty),
+            "flat" => Ok(OutputFormat::Flat),
+            "xml" => Ok(OutputFormat::Xml),
+            "influx" => Ok(OutputFormat::Influx),
+            "csv-wide" => Ok(OutputFormat::CsvWide),
+            "csv-levels" => Ok(OutputFormat::CsvLevels),
+            "csv-histogram-matrix" => Ok(OutputFormat::CsvHistogramMatrix),
+            _ => Err(anyhow!("Unknown output format {}", s)),
+        }
+    }
+}
+
+/// A user-supplied label of an analysis run together with the time at which the run started, for collating the
+/// results of many runs
+#[derive(Debug, Clone, Serialize)]
+pub struct RunLabel {
+    pub label: String,
+    /// Start of the run in RFC 3339 format, e.g. '2024-03-01T12:00:00Z'
+    pub timestamp: String,
+}
+
+impl RunLabel {
+    /// Creates a label for a run that starts now
+    pub fn now(label: String) -> Self {
+        Self {
+            label,
+            timestamp: humantime::format_rfc3339_seconds(std::time::SystemTime::now()).to_string(),
+        }
+    }
+}
+
+/// The results together with the label of the run, for the formats that write all results at once
+#[derive(Serialize)]
+struct LabeledResults<'a> {
+    label: &'a str,
+    timestamp: &'a str,
+    results: &'a [AnalyzerResult],
+}
+
+/// Name of the measurement in the Influx output
+const INFLUX_MEASUREMENT: &str = "pointcloud";
+
+/// Writes the results in the given format, either to `output_file` or to stdout if there is no output file. If the
+/// run has a label, it is written before the results. `input_format` names the format of the analyzed input, e.g.
+/// 'potree-v2' or 'manifest', and is used as a tag in the Influx output
+pub fn write_results(
+    results: &[AnalyzerResult],
+    format: &OutputFormat,
+    output_file: Option<&Path>,
+    raw_numbers: bool,
+    label: Option<&RunLabel>,
+    input_format: &str,
+) -> Result<()> {
+    match format {
+        OutputFormat::Text => {
+            if let Some(label) = label {
+                print_label(label);
+            }
+            results
+                .iter()
+                .for_each(|result| print_text(result, raw_numbers));
+            Ok(())
+        }
+        OutputFormat::Html => {
+            let mut writer = open_output(output_file)?;
+            html::write_report(&mut writer, results, raw_numbers, label)?;
+            writer.flush()?;
+            Ok(())
+        }
+        OutputFormat::Ron => {
+            let mut writer = open_output(output_file)?;
+            match label {
+                Some(label) => ron::ser::to_writer_pretty(
+                    &mut writer,
+                    &LabeledResults {
+                        label: &label.label,
+                        timestamp: &label.timestamp,
+                        results,
+                    },
+                    ron::ser::PrettyConfig::default(),
+                )?,
+                None => ron::ser::to_writer_pretty(
+                    &mut writer,
+                    results,
+                    ron::ser::PrettyConfig::default(),
+                )?,
+            }
+            writeln!(writer)?;
+            writer.flush()?;
+            Ok(())
+        }
+        OutputFormat::Json | OutputFormat::JsonPretty => {
+            let mut writer = open_output(output_file)?;
+            write_json(
+                &mut writer,
+                results,
+                label,
+                matches!(format, OutputFormat::JsonPretty),
+            )?;
+            writer.flush()?;
+            Ok(())
+        }
+        OutputFormat::Flat => {
+            let mut writer = open_output(output_file)?;
+            if let Some(label) = label {
+                writeln!(writer, "label={}", label.label)?;
+                writeln!(writer, "timestamp={}", label.timestamp)?;
+            }
+            for result in results.iter() {
+                write_flat(&mut writer, result)?;
+            }
+            writer.flush()?;
+            Ok(())
+        }
+        OutputFormat::Xml => {
+            let mut writer = open_output(output_file)?;
+            write_xml(&mut writer, results, label)?;
+            writer.flush()?;
+            Ok(())
+        }
+        OutputFormat::Influx => {
+            let mut writer = open_output(output_file)?;
+            write_influx(&mut writer, results, label, input_format)?;
+            writer.flush()?;
+            Ok(())
+        }
+        OutputFormat::CsvWide => write_csv_wide(results, output_file, label, input_format),
+        OutputFormat::CsvHistogramMatrix => {
+            write_csv_histogram_matrix(results, output_file, label, input_format)
+        }
+        OutputFormat::CsvLevels => {
+            let mut writer = open_output(output_file)?;
+            write_csv_levels(&mut writer, results)?;
+            writer.flush()?;
+            Ok(())
+        }
+        OutputFormat::Parquet => {
+            if let Some(label) = label {
+                print_label(label);
+            }
+            write_parquet(
+                results,
+                output_file.expect("Parquet output requires an output file"),
+                raw_numbers,
+            )
+        }
+    }
+}
+
+/// Writes all results as JSON, either on a single line or indented if `pretty` is set. With a label, the results are
+/// wrapped in an object with the label and the timestamp, like in the RON output
+fn write_json(
+    writer: &mut dyn Write,
+    results: &[AnalyzerResult],
+    label: Option<&RunLabel>,
+    pretty: bool,
+) -> Result<()> {
+    let value = match label {
+        Some(label) => serde_json::to_value(LabeledResults {
+            label: &label.label,
+            timestamp: &label.timestamp,
+            results,
+        })?,
+        None => serde_json::to_value(results)?,
+    };
+    if pretty {
+        serde_json::to_writer_pretty(&mut *writer, &value)?;
+    } else {
+        serde_json::to_writer(&mut *writer, &value)?;
+    }
+    writeln!(writer)?;
+    Ok(())
+}
+
+fn open_output(output_file: Option<&Path>) -> Result<Box<dyn Write>> {
+    match output_file {
+        Some(path) => Ok(Box::new(BufWriter::new(File::create(path)?))),
+        None => Ok(Box::new(std::io::stdout())),
+    }
+}
+
+/// Writes a single result as `key=value` lines. The keys are the names of the result kinds in snake case followed by
+/// the names of the fields and the indices of list entries, separated by dots. Notes of `--explain` are written with
+/// the key of the explained result followed by `.explanation`
+fn write_flat(writer: &mut dyn Write, result: &AnalyzerResult) -> Result<()> {
+    let (result, explanation) = match result {
+        AnalyzerResult::Explained {
+            result,
+            explanation,
+        } => (result.as_ref(), Some(explanation)),
+        _ => (result, None),
+    };
+
+    // Results are serialized as a map with the name of the result kind as the only key, e.g. {"NodeCount": 12}
+    if let serde_json::Value::Object(map) = serde_json::to_value(result)? {
+        for (kind, value) in map.iter() {
+            let key = snake_case(kind);
+            write_flat_value(writer, &key, value)?;
+            if let Some(explanation) = explanation {
+                writeln!(writer, "{}.explanation={}", key, explanation)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_flat_value(writer: &mut dyn Write, key: &str, value: &serde_json::Value) -> Result<()> {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (field, value) in map.iter() {
+                write_flat_value(writer, &format!("{}.{}", key, field), value)?;
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                write_flat_value(writer, &format!("{}.{}", key, index), value)?;
+            }
+        }
+        serde_json::Value::String(string) => {
+            writeln!(writer, "{}={}", key, string.replace('\n', "\\n"))?
+        }
+        serde_json::Value::Null => writeln!(writer, "{}=", key)?,
+        _ => writeln!(writer, "{}={}", key, value)?,
+    }
+    Ok(())
+}
+
+/// Writes the main metrics of the results as a single line of InfluxDB line protocol, e.g.
+/// `pointcloud,label=nightly,format=potree-v2 node_count=12345i,mean_points=4321.5 1709294400000000000`. The names
+/// of the tags and fields are stable:
+///
+/// - Tag `label`: the label of the run, only if the run has a label
+/// - Tag `format`: the format of the analyzed input, e.g. `potree-v2`, `ept`, `files` or `manifest`
+/// - Field `node_count` (integer): the node count
+/// - Field `total_points` (integer): the number of points of all nodes, from the level summary, the LOD curve or the
+///   leaf point ratio
+/// - Field `mean_points` (float): the mean number of points per node, from the point histogram
+/// - Field `p99` (float): the estimated 99th percentile of the number of points per node, from the approximate
+///   quantiles
+///
+/// Each field is only written if one of the results it is taken from is present. The timestamp is the start of the
+/// run in nanoseconds if the run has a label, otherwise it is omitted and the database uses the time of ingestion
+fn write_influx(
+    writer: &mut dyn Write,
+    results: &[AnalyzerResult],
+    label: Option<&RunLabel>,
+    input_format: &str,
+) -> Result<()> {
+    let mut fields = vec![];
+    let mut add_field = |name: &str, value: Option<String>| {
+        if let Some(value) = value {
+            fields.push(format!("{}={}", name, value));
+        }
+    };
+    add_field(
+        "node_count",
+        results.iter().find_map(|result| match result.inner() {
+            AnalyzerResult::NodeCount(count) => Some(format!("{}i", count)),
+            _ => None,
+        }),
+    );
+    add_field(
+        "total_points",
+        total_points(results).map(|points| format!("{}i", points)),
+    );
+    add_field(
+        "mean_points",
+        results.iter().find_map(|result| match result.inner() {
+            AnalyzerResult::Histogram(histogram) => Some(histogram.mean().to_string()),
+            _ => None,
+        }),
+    );
+    add_field(
+        "p99",
+        results.iter().find_map(|result| match result.inner() {
+            AnalyzerResult::ApproxQuantiles { quantiles, .. } => quantiles
+                .iter()
+                .find(|(quantile, _)| *quantile == 0.99)
+                .map(|(_, value)| value.to_string()),
+            _ => None,
+        }),
+    );
+    if fields.is_empty() {
+        return Err(anyhow!(
+            "None of the results can be written in the Influx format, enable e.g. --count-nodes or a histogram!"
+        ));
+    }
+
+    write!(writer, "{}", INFLUX_MEASUREMENT)?;
+    if let Some(label) = label {
+        write!(writer, ",label={}", escape_influx_tag(&label.label))?;
+    }
+    write!(
+        writer,
+        ",format={} {}",
+        escape_influx_tag(input_format),
+        fields.join(",")
+    )?;
+    if let Some(label) = label {
+        let timestamp = humantime::parse_rfc3339(&label.timestamp)?
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_nanos();
+        write!(writer, " {}", timestamp)?;
+    }
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Returns the number of points of all nodes, from `--total-points`, the level summary, the LOD curve or the leaf point
+/// ratio, or `None` if none of these results is present
+fn total_points(results: &[AnalyzerResult]) -> Option<u64> {
+    results.iter().find_map(|result| match result.inner() {
+        AnalyzerResult::TotalPoints(total_points) => Some(*total_points),
+        AnalyzerResult::LevelSummary(levels) => {
+            Some(levels.iter().map(|row| row.point_count).sum::<u64>())
+        }
+        AnalyzerResult::LodCurve(levels) => levels.last().map(|(_, points)| *points),
+        AnalyzerResult::LeafPointRatio {
+            leaf_points,
+            inner_points,
+        } => Some(leaf_points + inner_points),
+        _ => None,
+    })
+}
+
+/// Writes all scalar values of the results as a single CSV row below a header row. The columns are:
+///
+/// - `label` and `timestamp`, only if the run has a label
+/// - `format`: the format of the analyzed input, e.g. `potree-v2`, `ept`, `files` or `manifest`
+/// - `total_points`: the number of points of all nodes, only if `--total-points`, the level summary, the LOD curve or the leaf point
+///   ratio is present
+/// - One column for each plain value of the results, named like the keys of the flat format, e.g. `node_count` or
+///   `histogram.mean`. Lists, such as the buckets of a histogram, don't fit into a single row and are omitted
+/// - One column for each approximate quantile, e.g. `approx_quantiles.p50` for the median
+///
+/// If `output_file` already exists and starts with the same header row, only the data row is appended to it, so that
+/// a single file collects the rows of many datasets. An existing file with a different header is an error, as the
+/// rows would not line up
+fn write_csv_wide(
+    results: &[AnalyzerResult],
+    output_file: Option<&Path>,
+    label: Option<&RunLabel>,
+    input_format: &str,
+) -> Result<()> {
+    let mut columns = vec![];
+    if let Some(label) = label {
+        columns.push(("label".to_owned(), label.label.clone()));
+        columns.push(("timestamp".to_owned(), label.timestamp.clone()));
+    }
+    columns.push(("format".to_owned(), input_format.to_owned()));
+    if let Some(points) = total_points(results) {
+        columns.push(("total_points".to_owned(), points.to_string()));
+    }
+    for result in results.iter() {
+        let result = result.inner();
+        if let AnalyzerResult::ApproxQuantiles { quantiles, .. } = result {
+            for (quantile, value) in quantiles.iter() {
+                columns.push((
+                    // Rounded, so that e.g. 0.29 is named p29 instead of p28.999999999999996
+                    format!(
+                        "approx_quantiles.p{}",
+                        (quantile * 100_000.0).round() / 1000.0
+                    ),
+                    value.to_string(),
+                ));
+            }
+            continue;
+        }
+        // Already written as the total_points column
+        if let AnalyzerResult::TotalPoints(_) = result {
+            continue;
+        }
+        // Results are serialized as a map with the name of the result kind as the only key, e.g. {"NodeCount": 12}
+        if let serde_json::Value::Object(map) = serde_json::to_value(result)? {
+            for (kind, value) in map.iter() {
+                collect_csv_columns(&mut columns, &snake_case(kind), value);
+            }
+        }
+    }
+
+    let header = columns
+        .iter()
+        .map(|(name, _)| escape_csv(name))
+        .collect::<Vec<_>>()
+        .join(",");
+    let row = columns
+        .iter()
+        .map(|(_, value)| escape_csv(value))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let existing_header = match output_file {
+        Some(path) if path.exists() => std::fs::read_to_string(path)?
+            .lines()
+            .next()
+            .map(|line| line.to_owned()),
+        _ => None,
+    };
+    let mut writer: Box<dyn Write> = match (output_file, existing_header) {
+        (Some(path), Some(existing_header)) => {
+            if existing_header != header {
+                return Err(anyhow!(
+                    "Can't append to {}, its columns differ from the columns of this run!",
+                    path.display()
+                ));
+            }
+            Box::new(BufWriter::new(
+                std::fs::OpenOptions::new().append(true).open(path)?,
+            ))
+        }
+        _ => {
+            let mut writer = open_output(output_file)?;
+            writeln!(writer, "{}", header)?;
+            writer
+        }
+    };
+    writeln!(writer, "{}", row)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the level summary as CSV with the columns `level`, `node_count`, `total_points`, `mean_points` and
+/// `point_share` (in [0;1]), one row per octree level. All other results are not written
+fn write_csv_levels(writer: &mut dyn Write, results: &[AnalyzerResult]) -> Result<()> {
+    let levels = results
+        .iter()
+        .find_map(|result| match result.inner() {
+            AnalyzerResult::LevelSummary(levels) => Some(levels),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            anyhow!("Output format csv-levels requires a level summary, enable --level-summary!")
+        })?;
+
+    writeln!(
+        writer,
+        "level,node_count,total_points,mean_points,point_share"
+    )?;
+    for row in levels.iter() {
+        writeln!(
+            writer,
+            "{},{},{},{},{}",
+            row.level, row.node_count, row.point_count, row.mean_points_per_node, row.point_share
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the point histogram as a CSV matrix with the columns `bucket_start` and `bucket_end` followed by one column
+/// of bucket counts per dataset. The column of this run is named after its label, or after the input format if the
+/// run has no label.
+///
+/// If `output_file` already exists, the histogram is added to it as a new column, so that a single file collects the
+/// histograms of many datasets. The rows are the union of the buckets of all datasets, with a count of 0 for datasets
+/// that don't have a bucket. Buckets with the same start are merged and end at the largest end of all datasets. The
+/// open-ended overflow buckets of `--histogram-step` and `--histogram-bins` end at `usize::MAX`, so they line up as
+/// well. Apart from that, the buckets of all datasets must share their boundaries, e.g. through `--histogram-step` with a common
+/// start and step or through `--histogram-bins`, otherwise the buckets would overlap and this is an error
+fn write_csv_histogram_matrix(
+    results: &[AnalyzerResult],
+    output_file: Option<&Path>,
+    label: Option<&RunLabel>,
+    input_format: &str,
+) -> Result<()> {
+    let histogram = results
+        .iter()
+        .find_map(|result| match result.inner() {
+            AnalyzerResult::Histogram(histogram) => Some(histogram),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            anyhow!("Output format csv-histogram-matrix requires a histogram of the points per node, enable e.g. --histogram-step or --histogram-bins!")
+        })?;
+
+    // End of each bucket and its counts, one entry per dataset, by the start of the bucket
+    let mut columns = vec![];
+    let mut rows: BTreeMap<usize, (usize, Vec<usize>)> = BTreeMap::new();
+    if let Some(path) = output_file.filter(|path| path.exists()) {
+        let existing = std::fs::read_to_string(path)?;
+        let mut lines = existing.lines();
+        if let Some(header) = lines.next() {
+            columns = split_csv_line(header).into_iter().skip(2).collect();
+        }
+        for line in lines {
+            let fields = split_csv_line(line);
+            let parse = |field: &String| -> Result<usize> {
+                field
+                    .parse()
+                    .with_context(|| format!("Invalid value {} in {}", field, path.display()))
+            };
+            if fields.len() != columns.len() + 2 {
+                return Err(anyhow!(
+                    "Invalid row {} in {}, expected {} columns",
+                    line,
+                    path.display(),
+                    columns.len() + 2
+                ));
+            }
+            let counts = fields[2..].iter().map(parse).collect::<Result<Vec<_>>>()?;
+            rows.insert(parse(&fields[0])?, (parse(&fields[1])?, counts));
+        }
+    }
+
+    let previous_columns = columns.len();
+    columns.push(label.map_or_else(|| input_format.to_owned(), |label| label.label.clone()));
+    // Many buckets over few distinct counts can be empty ranges such as [0;0) that share their start with the next
+    // bucket. They can't contain any nodes, and a second count in the same row would shift the columns
+    for bucket in histogram
+        .buckets()
+        .iter()
+        .filter(|bucket| !bucket.range().is_empty())
+    {
+        let (end, counts) = rows
+            .entry(bucket.range().start)
+            .or_insert_with(|| (bucket.range().end, vec![0; previous_columns]));
+        *end = (*end).max(bucket.range().end);
+        counts.push(bucket.count());
+    }
+    for (_, counts) in rows.values_mut() {
+        counts.resize(columns.len(), 0);
+    }
+    let ranges = rows
+        .iter()
+        .map(|(start, (end, _))| (*start, *end))
+        .collect::<Vec<_>>();
+    if let Some(pair) = ranges.windows(2).find(|pair| pair[0].1 > pair[1].0) {
+        return Err(anyhow!(
+            "The buckets [{};{}) and [{};{}) of different datasets overlap, use buckets with the same boundaries for all datasets, e.g. through --histogram-step with a common start and step!",
+            pair[0].0,
+            pair[0].1,
+            pair[1].0,
+            pair[1].1
+        ));
+    }
+
+    let mut writer = open_output(output_file)?;
+    write!(writer, "bucket_start,bucket_end")?;
+    for column in columns.iter() {
+        write!(writer, ",{}", escape_csv(column))?;
+    }
+    writeln!(writer)?;
+    for (start, (end, counts)) in rows.iter() {
+        write!(writer, "{},{}", start, end)?;
+        for count in counts.iter() {
+            write!(writer, ",{}", count)?;
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Splits a line of CSV into its fields, removing the quotes that `escape_csv` adds
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Collects the plain values below `value` as columns, named like the keys of the flat format. Lists are omitted
+fn collect_csv_columns(columns: &mut Vec<(String, String)>, key: &str, value: &serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (field, value) in map.iter() {
+                collect_csv_columns(columns, &format!("{}.{}", key, field), value);
+            }
+        }
+        serde_json::Value::Array(_) => {}
+        serde_json::Value::String(string) => columns.push((key.to_owned(), string.clone())),
+        serde_json::Value::Null => columns.push((key.to_owned(), String::new())),
+        _ => columns.push((key.to_owned(), value.to_string())),
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break
+fn escape_csv(field: &str) -> String {
+    if field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Escapes the characters that have a special meaning in the tags of the InfluxDB line protocol
+fn escape_influx_tag(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c == ',' || c == '=' || c == ' ' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Converts a name in camel case, like the name of a result kind, to snake case, e.g. `NodeCount` to `node_count`
+fn snake_case(name: &str) -> String {
+    let mut snake_case = String::with_capacity(name.len() + 4);
+    for (index, c) in name.chars().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            snake_case.push('_');
+        }
+        snake_case.extend(c.to_lowercase());
+    }
+    snake_case
+}
+
+/// Prints the label of the run as a text header to stdout
+pub fn print_label(label: &RunLabel) {
+    println!("Label: {}", label.label);
+    println!("Timestamp: {}", label.timestamp);
+}
+
+/// Prints a single result as text to stdout
+pub fn print_text(result: &AnalyzerResult, raw_numbers: bool) {
+    if raw_numbers {
+        print!("{:#}", result);
+    } else {
+        print!("{}", result);
+    }
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet(results: &[AnalyzerResult], output_file: &Path, raw_numbers: bool) -> Result<()> {
+    for result in results.iter() {
+        match result {
+            AnalyzerResult::Nodes(nodes) => parquet_writer::write_nodes(output_file, nodes)?,
+            _ => print_text(result, raw_numbers),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn write_parquet(
+    _results: &[AnalyzerResult],
+    _output_file: &Path,
+    _raw_numbers: bool,
+) -> Result<()> {
+    Err(anyhow!(
+        "Parquet output is not supported by this binary. Rebuild with '--features parquet' to enable it!"
+    ))
+}
+
+/// Shows the results in an interactive terminal view instead of writing them, see `--tui`
+#[cfg(feature = "tui")]
+pub fn show_tui(results: &[AnalyzerResult], raw_numbers: bool) -> Result<()> {
+    tui::show(results, raw_numbers)
+}
+
+#[cfg(not(feature = "tui"))]
+pub fn show_tui(_results: &[AnalyzerResult], _raw_numbers: bool) -> Result<()> {
+    Err(anyhow!(
+        "The interactive view is not supported by this binary. Rebuild with '--features tui' to enable it!"
+    ))
+}
+
+#[cfg(feature = "xml")]
+fn write_xml(
+    writer: &mut dyn Write,
+    results: &[AnalyzerResult],
+    label: Option<&RunLabel>,
+) -> Result<()> {
+    xml_writer::write_report(writer, results, label)
+}
+
+#[cfg(not(feature = "xml"))]
+fn write_xml(
+    _writer: &mut dyn Write,
+    _results: &[AnalyzerResult],
+    _label: Option<&RunLabel>,
+) -> Result<()> {
+    Err(anyhow!(
+        "XML output is not supported by this binary. Rebuild with '--features xml' to enable it!"
+    ))
+}
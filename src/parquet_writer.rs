@@ -0,0 +1,56 @@
+use crate::analyzer::NodeInfo;
+use anyhow::Result;
+use arrow::array::{ArrayRef, Float64Array, StringArray, UInt64Array};
+use arrow::record_batch::RecordBatch;
+use las::Bounds;
+use parquet::arrow::ArrowWriter;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Writes the given nodes as a table to a Parquet file at `path`. The table has one row per node with the
+/// columns `id`, `point_count`, `file_size` and `min_x` through `max_z`. The bounds columns are null for
+/// nodes with unknown bounds
+pub fn write_nodes<P: AsRef<Path>>(path: P, nodes: &[NodeInfo]) -> Result<()> {
+    let bounds_column = |get_value: fn(&Bounds) -> f64| -> ArrayRef {
+        Arc::new(
+            nodes
+                .iter()
+                .map(|node| node.bounds.as_ref().map(get_value))
+                .collect::<Float64Array>(),
+        )
+    };
+
+    let batch = RecordBatch::try_from_iter(vec![
+        (
+            "id",
+            Arc::new(StringArray::from_iter_values(
+                nodes.iter().map(|node| node.id.as_str()),
+            )) as ArrayRef,
+        ),
+        (
+            "point_count",
+            Arc::new(UInt64Array::from_iter_values(
+                nodes.iter().map(|node| node.point_count),
+            )),
+        ),
+        (
+            "file_size",
+            Arc::new(UInt64Array::from_iter_values(
+                nodes.iter().map(|node| node.file_size),
+            )),
+        ),
+        ("min_x", bounds_column(|bounds| bounds.min.x)),
+        ("min_y", bounds_column(|bounds| bounds.min.y)),
+        ("min_z", bounds_column(|bounds| bounds.min.z)),
+        ("max_x", bounds_column(|bounds| bounds.max.x)),
+        ("max_y", bounds_column(|bounds| bounds.max.y)),
+        ("max_z", bounds_column(|bounds| bounds.max.z)),
+    ])?;
+
+    let file = File::create(path)?;
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
@@ -0,0 +1,512 @@
+use crate::error::AnalyzerError;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::collections::{HashSet, VecDeque};
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Axis-aligned bounding box as stored in the metadata.json file of PotreeConverter v2
+#[derive(Debug, Deserialize)]
+pub struct PotreeV2BoundingBox {
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+}
+
+/// The parts of the metadata.json file of PotreeConverter v2 that are relevant for analyzing the dataset
+#[derive(Debug, Deserialize)]
+pub struct PotreeV2Metadata {
+    /// Minimum distance between points in the root node
+    pub spacing: f64,
+    /// Bounding box of the octree. This is a cube, each level halves its side length
+    #[serde(rename = "boundingBox")]
+    pub bounding_box: PotreeV2BoundingBox,
+    /// Coordinate reference system of the dataset as WKT. Empty if the input data had no CRS
+    #[serde(default)]
+    pub projection: String,
+    /// Encoding of the points in octree.bin, either 'DEFAULT' for uncompressed points or 'BROTLI'
+    #[serde(default)]
+    pub encoding: String,
+    /// The attributes that are stored for each point, in the order in which they are stored
+    #[serde(default)]
+    pub attributes: Vec<PotreeV2Attribute>,
+}
+
+/// A single point attribute as listed in the metadata.json file of PotreeConverter v2
+#[derive(Debug, Deserialize)]
+pub struct PotreeV2Attribute {
+    pub name: String,
+    /// Size of the attribute in bytes per point
+    pub size: u64,
+}
+
+impl PotreeV2Metadata {
+    /// Reads the metadata from the metadata.json file at `path`
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let reader = BufReader::new(File::open(path.as_ref()).with_context(|| {
+            format!("Could not open metadata file {}", path.as_ref().display())
+        })?);
+        let metadata = serde_json::from_reader(reader).with_context(|| {
+            format!("Could not parse metadata file {}", path.as_ref().display())
+        })?;
+        Ok(metadata)
+    }
+
+    /// Are the points in octree.bin stored uncompressed, so that each node takes exactly
+    /// `bytes_per_point() * num_points` bytes?
+    pub fn is_uncompressed(&self) -> bool {
+        self.encoding.is_empty() || self.encoding == "DEFAULT"
+    }
+
+    /// Size of a single point in bytes, according to the attribute schema
+    pub fn bytes_per_point(&self) -> u64 {
+        self.attributes.iter().map(|attribute| attribute.size).sum()
+    }
+}
+
+/// The hierarchy section of the metadata.json file of PotreeConverter v2
+#[derive(Debug, Deserialize)]
+struct PotreeV2HierarchyMetadata {
+    /// Size of the first hierarchy chunk in bytes, which is a multiple of the node record size
+    #[serde(rename = "firstChunkSize")]
+    first_chunk_size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PotreeV2HierarchyOnlyMetadata {
+    #[serde(default)]
+    hierarchy: Option<PotreeV2HierarchyMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PotreeV2VersionOnlyMetadata {
+    #[serde(default)]
+    version: Option<String>,
+}
+
+/// Reads the version of PotreeConverter that wrote the metadata.json file at `path`, e.g. '2.0'. Returns `None` if
+/// the file has no version
+pub fn read_version<P: AsRef<Path>>(path: P) -> Result<Option<String>> {
+    let reader =
+        BufReader::new(File::open(path.as_ref()).with_context(|| {
+            format!("Could not open metadata file {}", path.as_ref().display())
+        })?);
+    let metadata: PotreeV2VersionOnlyMetadata = serde_json::from_reader(reader)
+        .with_context(|| format!("Could not parse metadata file {}", path.as_ref().display()))?;
+    Ok(metadata.version)
+}
+
+/// Layout of the node records within hierarchy.bin. PotreeConverter v2 stores the point count of each node as a
+/// `u32`, hierarchies whose nodes can exceed this range store it as a `u64` instead. The byte offset and byte size of
+/// a node are always the last 16 bytes of its record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordLayout {
+    size: usize,
+    point_count_offset: usize,
+    point_count_bytes: usize,
+}
+
+impl RecordLayout {
+    // Type and child mask, the point count, byte offset and byte size
+    pub const U32_POINT_COUNTS: Self = Self {
+        size: 2 + 4 + 16,
+        point_count_offset: 2,
+        point_count_bytes: 4,
+    };
+    pub const U64_POINT_COUNTS: Self = Self {
+        size: 2 + 8 + 16,
+        point_count_offset: 2,
+        point_count_bytes: 8,
+    };
+
+    /// Creates a layout for records of `size` bytes whose point count is stored as a `point_count_bytes` wide
+    /// integer at `point_count_offset`, for hierarchies of converters that don't follow PotreeConverter v2. The
+    /// point count must be located between the type and child mask at the start of the record and the byte offset
+    /// and byte size at the end of the record
+    pub fn custom(
+        size: usize,
+        point_count_offset: usize,
+        point_count_bytes: usize,
+    ) -> Result<Self> {
+        if point_count_bytes != 4 && point_count_bytes != 8 {
+            return Err(anyhow!(
+                "Point counts must be 4 or 8 bytes wide, not {} bytes!",
+                point_count_bytes
+            ));
+        }
+        if point_count_offset < 2 || point_count_offset + point_count_bytes + 16 > size {
+            return Err(anyhow!(
+                "A {} byte point count at offset {} does not fit into a node record of {} bytes between the type and child mask (bytes 0-1) and the byte offset and byte size (the last 16 bytes)!",
+                point_count_bytes,
+                point_count_offset,
+                size
+            ));
+        }
+        Ok(Self {
+            size,
+            point_count_offset,
+            point_count_bytes,
+        })
+    }
+
+    /// Size of a single node record in bytes
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Offset of the point count within a node record in bytes
+    pub fn point_count_offset(&self) -> usize {
+        self.point_count_offset
+    }
+
+    /// Width of the point count in bytes, either 4 or 8
+    pub fn point_count_bytes(&self) -> usize {
+        self.point_count_bytes
+    }
+
+    /// Determines the layout from the size of the first hierarchy chunk, which is a multiple of the record size.
+    /// If it is a multiple of both record sizes, the layout of PotreeConverter v2 is assumed
+    pub fn from_first_chunk_size(first_chunk_size: u64) -> Result<Self> {
+        [Self::U32_POINT_COUNTS, Self::U64_POINT_COUNTS]
+            .iter()
+            .copied()
+            .find(|layout| first_chunk_size % layout.size() as u64 == 0)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Size of the first hierarchy chunk ({} bytes) is not a multiple of any known node record size!",
+                    first_chunk_size
+                )
+            })
+    }
+
+    /// Determines the layout from the metadata.json file at `path`. Without a metadata file or hierarchy
+    /// information in it, the layout of PotreeConverter v2 is assumed
+    pub fn from_metadata_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        if !path.as_ref().exists() {
+            return Ok(Self::default());
+        }
+
+        let reader = BufReader::new(File::open(path.as_ref()).with_context(|| {
+            format!("Could not open metadata file {}", path.as_ref().display())
+        })?);
+        let metadata: PotreeV2HierarchyOnlyMetadata = serde_json::from_reader(reader)
+            .with_context(|| {
+                format!("Could not parse metadata file {}", path.as_ref().display())
+            })?;
+        match metadata.hierarchy {
+            Some(hierarchy) => Self::from_first_chunk_size(hierarchy.first_chunk_size),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+impl Default for RecordLayout {
+    fn default() -> Self {
+        Self::U32_POINT_COUNTS
+    }
+}
+
+/// Node record layouts that `infer_record_layout` tries, in order of preference: the layouts of PotreeConverter v2
+/// with 32-bit and 64-bit point counts, followed by both with the point count aligned to its width
+pub const CANDIDATE_LAYOUTS: [RecordLayout; 4] = [
+    RecordLayout::U32_POINT_COUNTS,
+    RecordLayout::U64_POINT_COUNTS,
+    RecordLayout {
+        size: 4 + 4 + 16,
+        point_count_offset: 4,
+        point_count_bytes: 4,
+    },
+    RecordLayout {
+        size: 8 + 8 + 16,
+        point_count_offset: 8,
+        point_count_bytes: 8,
+    },
+];
+
+/// Point counts above this value are considered implausible when inferring the record layout. Negative 32-bit
+/// point counts and bytes that are decoded from the wrong offset usually end up above it
+const MAX_PLAUSIBLE_POINT_COUNT: u64 = i32::MAX as u64;
+
+/// A layout that `infer_record_layout` tried, together with the reason why it was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutCandidate {
+    pub layout: RecordLayout,
+    /// `None` if the hierarchy looks sane under this layout
+    pub rejection: Option<String>,
+}
+
+/// Infers the layout of the node records of the hierarchy in `bytes`, for hierarchies without a metadata.json. Tries
+/// all `CANDIDATE_LAYOUTS` and rejects a layout if the size of the hierarchy is not a multiple of its record size, if
+/// a record has an unknown node type (0 to 2 are known), an implausible point count or a proxy node that references
+/// bytes outside of the hierarchy, or if walking the hierarchy from the root node fails. Returns the first layout
+/// that is not rejected together with the checks of all candidates, or an error if all layouts are rejected
+pub fn infer_record_layout(bytes: &[u8]) -> Result<(RecordLayout, Vec<LayoutCandidate>)> {
+    let candidates = CANDIDATE_LAYOUTS
+        .iter()
+        .map(|&layout| LayoutCandidate {
+            layout,
+            rejection: check_layout(bytes, layout),
+        })
+        .collect::<Vec<_>>();
+    match candidates
+        .iter()
+        .find(|candidate| candidate.rejection.is_none())
+    {
+        Some(candidate) => Ok((candidate.layout, candidates)),
+        None => Err(anyhow!(
+            "Could not infer the node record size of hierarchy.bin, no candidate fits: {}",
+            candidates
+                .iter()
+                .map(|candidate| format!(
+                    "{} bytes: {}",
+                    candidate.layout.size(),
+                    candidate.rejection.as_deref().unwrap_or_default()
+                ))
+                .collect::<Vec<_>>()
+                .join("; ")
+        )),
+    }
+}
+
+/// Returns why the hierarchy in `bytes` does not look sane under `layout`, or `None` if it does
+fn check_layout(bytes: &[u8], layout: RecordLayout) -> Option<String> {
+    if bytes.is_empty() {
+        return Some("hierarchy.bin is empty".to_owned());
+    }
+    if bytes.len() % layout.size() != 0 {
+        return Some(format!(
+            "the size of hierarchy.bin ({} bytes) is not a multiple of the record size",
+            bytes.len()
+        ));
+    }
+    for (index, record_bytes) in bytes.chunks_exact(layout.size()).enumerate() {
+        let record = PotreeV2Node::from_bytes(record_bytes, layout);
+        if record.node_type > PotreeV2Node::TYPE_PROXY {
+            return Some(format!(
+                "record {} has the unknown node type {}",
+                index, record.node_type
+            ));
+        }
+        if record.num_points > MAX_PLAUSIBLE_POINT_COUNT {
+            return Some(format!(
+                "record {} has an implausible point count of {}",
+                index, record.num_points
+            ));
+        }
+        let outside = record.byte_offset.saturating_add(record.byte_size) > bytes.len() as u64;
+        if record.is_proxy() && (outside || record.byte_size % layout.size() as u64 != 0) {
+            return Some(format!(
+                "proxy record {} references {} bytes at offset {}, which are not a whole number of records within hierarchy.bin",
+                index, record.byte_size, record.byte_offset
+            ));
+        }
+    }
+    walk_hierarchy(bytes, layout, None)
+        .err()
+        .map(|why| format!("{:#}", why))
+}
+
+/// A single node record within the hierarchy.bin file of PotreeConverter v2
+#[derive(Debug, Clone)]
+pub struct PotreeV2Node {
+    pub node_type: u8,
+    pub child_mask: u8,
+    /// Number of points in the node. Always widened to `u64`, independent of the `RecordLayout`
+    pub num_points: u64,
+    /// For proxy nodes, this is the offset of the referenced hierarchy chunk within hierarchy.bin. Otherwise it is
+    /// the offset of the node's points within octree.bin
+    pub byte_offset: u64,
+    pub byte_size: u64,
+}
+
+impl PotreeV2Node {
+    const TYPE_PROXY: u8 = 2;
+
+    /// Decodes the node record with the given layout starting at the beginning of `bytes`
+    pub fn from_bytes(bytes: &[u8], layout: RecordLayout) -> Self {
+        let offsets_start = layout.size - 16;
+        let point_count_bytes =
+            &bytes[layout.point_count_offset..layout.point_count_offset + layout.point_count_bytes];
+        let num_points = match layout.point_count_bytes {
+            4 => u32::from_le_bytes(point_count_bytes.try_into().unwrap()) as u64,
+            _ => u64::from_le_bytes(point_count_bytes.try_into().unwrap()),
+        };
+        Self {
+            node_type: bytes[0],
+            child_mask: bytes[1],
+            num_points,
+            byte_offset: u64::from_le_bytes(
+                bytes[offsets_start..offsets_start + 8].try_into().unwrap(),
+            ),
+            byte_size: u64::from_le_bytes(
+                bytes[offsets_start + 8..offsets_start + 16]
+                    .try_into()
+                    .unwrap(),
+            ),
+        }
+    }
+
+    /// Proxy nodes reference a part of the hierarchy that is stored elsewhere in the file
+    pub fn is_valid(&self) -> bool {
+        !self.is_proxy() || self.child_mask == 0
+    }
+
+    pub fn is_proxy(&self) -> bool {
+        self.node_type == Self::TYPE_PROXY
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.child_mask == 0
+    }
+}
+
+/// A node of the octree, as found by walking the hierarchy from the root node
+#[derive(Debug, Clone)]
+pub struct OctreeNode {
+    /// Name of the node, e.g. 'r0426'
+    pub name: String,
+    /// Level of the node within the octree, starting at 0 for the root node
+    pub level: u8,
+    pub record: PotreeV2Node,
+}
+
+/// Reads the raw contents of the hierarchy.bin file at `path`, whose node records have the given layout
+pub fn read_hierarchy_bytes<P: AsRef<Path>>(path: P, layout: RecordLayout) -> Result<Vec<u8>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+
+    if bytes.len() % layout.size() != 0 {
+        return Err(AnalyzerError::CorruptHierarchy(format!(
+            "File size of hierarchy.bin must be a multiple of {}!",
+            layout.size()
+        ))
+        .into());
+    }
+
+    Ok(bytes)
+}
+
+/// Walks the hierarchy stored in `bytes` from the root node. The hierarchy is split into chunks (pages), each chunk
+/// stores its nodes in breadth-first order starting with the root node of the chunk. Nodes are named like in Potree,
+/// i.e. 'r' for the root node followed by the index of the child node for each level (e.g. 'r0426'). Proxy nodes are
+/// followed into the chunk that they reference, so the returned nodes contain the whole octree across all chunks but
+/// no proxy nodes. Each chunk is bounded by the byte size of its proxy node, and a chunk whose nodes run past this
+/// size is reported as corrupt. If `max_depth` is set, the walk does not descend past this level and all deeper
+/// nodes are excluded
+pub fn walk_hierarchy(
+    bytes: &[u8],
+    layout: RecordLayout,
+    max_depth: Option<u8>,
+) -> Result<Vec<OctreeNode>> {
+    Ok(walk(bytes, layout, max_depth, false)?.nodes)
+}
+
+/// The nodes found by `walk_hierarchy_lenient`, together with the nodes that are referenced but missing
+#[derive(Debug, Default)]
+pub struct HierarchyWalk {
+    pub nodes: Vec<OctreeNode>,
+    /// Names of the nodes that are referenced by the child mask of their parent or by a proxy node, but whose record
+    /// is not stored in hierarchy.bin. Their descendants are unknown, so they are not included
+    pub missing: Vec<String>,
+}
+
+/// Walks the hierarchy like `walk_hierarchy`, but does not fail on nodes that are referenced but missing from
+/// hierarchy.bin. Instead, the walk skips them and returns their names, so that the rest of a truncated hierarchy can
+/// still be analyzed
+pub fn walk_hierarchy_lenient(
+    bytes: &[u8],
+    layout: RecordLayout,
+    max_depth: Option<u8>,
+) -> Result<HierarchyWalk> {
+    walk(bytes, layout, max_depth, true)
+}
+
+fn walk(
+    bytes: &[u8],
+    layout: RecordLayout,
+    max_depth: Option<u8>,
+    lenient: bool,
+) -> Result<HierarchyWalk> {
+    let mut walk = HierarchyWalk::default();
+    let mut visited_chunks = HashSet::new();
+    // Each chunk is identified by the name of its root node, its offset within hierarchy.bin and its size in bytes.
+    // The size of the first chunk is only known from the metadata, so it may extend to the end of the file
+    let mut chunks = VecDeque::new();
+    chunks.push_back((String::from("r"), 0_u64, bytes.len() as u64));
+
+    while let Some((chunk_root_name, chunk_offset, chunk_size)) = chunks.pop_front() {
+        if !visited_chunks.insert(chunk_offset) {
+            return Err(AnalyzerError::CorruptHierarchy(format!(
+                "Hierarchy chunk at offset {} is referenced more than once!",
+                chunk_offset
+            ))
+            .into());
+        }
+
+        let chunk_end = chunk_offset.saturating_add(chunk_size);
+        if chunk_end > bytes.len() as u64 {
+            if lenient {
+                walk.missing.push(chunk_root_name);
+                continue;
+            }
+            return Err(AnalyzerError::CorruptHierarchy(format!(
+                "Hierarchy chunk of node {} at offset {} with {} bytes is outside of hierarchy.bin, the hierarchy is truncated!",
+                chunk_root_name,
+                chunk_offset,
+                chunk_size
+            )).into());
+        }
+
+        let mut pending_names = VecDeque::new();
+        pending_names.push_back(chunk_root_name);
+        let mut record_offset = chunk_offset as usize;
+
+        while let Some(name) = pending_names.pop_front() {
+            let level = (name.len() - 1) as u8;
+            // Nodes are stored in breadth-first order, so all remaining nodes of this chunk are too deep as well
+            if max_depth.map_or(false, |max_depth| level > max_depth) {
+                break;
+            }
+
+            if (record_offset + layout.size()) as u64 > chunk_end {
+                if lenient {
+                    // All remaining nodes of this chunk are missing as well, except for those that are too deep
+                    walk.missing.push(name);
+                    walk.missing.extend(pending_names.drain(..).filter(|name| {
+                        max_depth.map_or(true, |max_depth| (name.len() - 1) as u8 <= max_depth)
+                    }));
+                    break;
+                }
+                return Err(AnalyzerError::CorruptHierarchy(format!(
+                    "Node {} at offset {} is outside of its hierarchy chunk, which ends at offset {}. The hierarchy is truncated or a proxy node is corrupt!",
+                    name,
+                    record_offset,
+                    chunk_end
+                )).into());
+            }
+            let record = PotreeV2Node::from_bytes(&bytes[record_offset..], layout);
+            record_offset += layout.size();
+
+            if record.is_proxy() {
+                chunks.push_back((name, record.byte_offset, record.byte_size));
+                continue;
+            }
+
+            for child_index in 0..8 {
+                if record.child_mask & (1 << child_index) != 0 {
+                    pending_names.push_back(format!("{}{}", name, child_index));
+                }
+            }
+
+            walk.nodes.push(OctreeNode {
+                name,
+                level,
+                record,
+            });
+        }
+    }
+
+    Ok(walk)
+}
@@ -1,4 +1,11 @@
-use std::{collections::VecDeque, time::Instant};
+use crate::status::StatusFile;
+use serde::Serialize;
+use std::io::Write;
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 /// Different conditions for printing an update of the current progress
 #[derive(Debug)]
@@ -9,6 +16,35 @@ pub enum ProgressUpdateCondition {
     OnProgressChanged(f64),
 }
 
+/// Format in which progress updates are printed to stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressFormat {
+    /// Human-readable text, e.g. `12.50% [ETA: 10s]`
+    Text,
+    /// One JSON object per line, for parsing the progress in other tools
+    Json,
+}
+
+/// A single progress update in the JSON progress format
+#[derive(Debug, Serialize)]
+struct ProgressEvent {
+    event: &'static str,
+    percentage: f64,
+    /// Progress per second, averaged over the throughput window
+    throughput: Option<f64>,
+    eta_seconds: Option<f64>,
+}
+
+/// Progress per second for the given progress made in `delta_time`, or `None` if no time has passed
+pub fn throughput(delta_progress: f64, delta_time: Duration) -> Option<f64> {
+    let seconds = delta_time.as_secs_f64();
+    if seconds == 0.0 {
+        None
+    } else {
+        Some(delta_progress / seconds)
+    }
+}
+
 /// Helper structure for tracking progress. Progress can be any number, integer or real
 #[derive(Debug)]
 pub struct ProgressTracker {
@@ -16,10 +52,16 @@ pub struct ProgressTracker {
     target_progress: f64,
     update_condition: ProgressUpdateCondition,
     last_n_progresses: VecDeque<(f64, Instant)>,
+    throughput_window: usize,
+    format: ProgressFormat,
+    /// Don't print progress updates, only report them to the status file
+    silent: bool,
+    status_file: Option<Arc<StatusFile>>,
 }
 
 impl ProgressTracker {
-    const MAX_THROUGHPUTS_ENTRIES: usize = 32;
+    /// Default number of progress entries that the throughput and ETA are calculated from
+    pub const DEFAULT_THROUGHPUT_WINDOW: usize = 32;
 
     pub fn new(target_progress: f64, update_condition: ProgressUpdateCondition) -> Self {
         if target_progress < 0.0 {
@@ -30,9 +72,50 @@ impl ProgressTracker {
             target_progress,
             update_condition,
             last_n_progresses: VecDeque::new(),
+            throughput_window: Self::DEFAULT_THROUGHPUT_WINDOW,
+            format: ProgressFormat::Text,
+            silent: false,
+            status_file: None,
         }
     }
 
+    /// Stops printing progress updates. They are still reported to the status file, if there is one
+    pub fn silent(mut self) -> Self {
+        self.silent = true;
+        self
+    }
+
+    /// Reports the progress and ETA to `status_file` after every increment. The status file decides itself how
+    /// often it is written
+    pub fn with_status_file(mut self, status_file: Arc<StatusFile>) -> Self {
+        self.status_file = Some(status_file);
+        self
+    }
+
+    /// Sets the format in which progress updates are printed
+    pub fn with_format(mut self, format: ProgressFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the number of progress entries that the throughput and ETA are calculated from. Larger values give
+    /// a steadier ETA, smaller values adapt faster to changes in throughput
+    pub fn with_throughput_window(mut self, throughput_window: usize) -> Self {
+        if throughput_window < 2 {
+            panic!(
+                "ProgressTracker::with_throughput_window: throughput_window must be at least 2!"
+            );
+        }
+        self.throughput_window = throughput_window;
+        self
+    }
+
+    /// Number of progress entries that the throughput and ETA are currently calculated from. This never exceeds the
+    /// throughput window
+    pub fn throughput_samples(&self) -> usize {
+        self.last_n_progresses.len()
+    }
+
     pub fn inc_progress(&mut self, increment: f64) {
         if increment < 0.0 {
             panic!("ProgressTracker::inc_progress: increment must be a positive number!");
@@ -45,17 +128,20 @@ impl ProgressTracker {
         if self.current_progress + increment >= self.target_progress {
             self.current_progress = self.target_progress;
             let mean_throughput = self.calculate_throughput(old_progress, self.current_progress);
+            self.report_status(mean_throughput);
             self.print_progress(mean_throughput);
             return;
         }
 
         self.current_progress += increment;
         let mean_throughput = self.calculate_throughput(old_progress, self.current_progress);
+        self.report_status(mean_throughput);
 
         match self.update_condition {
             ProgressUpdateCondition::OnPercentageChanged(percentage_step) => {
-                let old_percentage = old_progress / self.target_progress;
-                let new_percentage = self.current_progress / self.target_progress;
+                // The target is positive here, since the progress is still below it
+                let old_percentage = 100.0 * old_progress / self.target_progress;
+                let new_percentage = self.percentage();
                 let old_percentage_steps = (old_percentage / percentage_step) as usize;
                 let new_percentage_steps = (new_percentage / percentage_step) as usize;
                 if new_percentage_steps > old_percentage_steps {
@@ -74,10 +160,11 @@ impl ProgressTracker {
 
     fn calculate_throughput(&mut self, old_progress: f64, new_progress: f64) -> Option<f64> {
         let now = Instant::now();
-        if self.last_n_progresses.len() == Self::MAX_THROUGHPUTS_ENTRIES {
+        while self.last_n_progresses.len() >= self.throughput_window {
             self.last_n_progresses.pop_front();
         }
         self.last_n_progresses.push_back((new_progress, now));
+        debug_assert!(self.last_n_progresses.len() <= self.throughput_window);
 
         if self.last_n_progresses.len() < 2 {
             return None;
@@ -88,20 +175,104 @@ impl ProgressTracker {
         let newest_progress = self.last_n_progresses.back().unwrap();
         let delta_time = (newest_progress.1).duration_since(oldest_progress.1);
         let delta_progress = newest_progress.0 - oldest_progress.0;
-        Some(delta_progress / delta_time.as_secs_f64())
+        throughput(delta_progress, delta_time)
+    }
+
+    fn etr_seconds(&self, mean_throughput: Option<f64>) -> Option<f64> {
+        mean_throughput
+            .map(|throughput| (self.target_progress - self.current_progress) / throughput)
+    }
+
+    /// Current progress in percent. A target of 0 means that there is nothing to do, which is reported as complete
+    /// instead of dividing by zero
+    fn percentage(&self) -> f64 {
+        if self.target_progress == 0.0 {
+            100.0
+        } else {
+            100.0 * self.current_progress / self.target_progress
+        }
+    }
+
+    fn report_status(&self, mean_throughput: Option<f64>) {
+        if let Some(status_file) = self.status_file.as_ref() {
+            status_file.set_progress(self.percentage(), self.etr_seconds(mean_throughput));
+        }
     }
 
     fn print_progress(&mut self, mean_throughput: Option<f64>) {
-        let progress_percentage =
-            100.0 * self.current_progress as f64 / self.target_progress as f64;
-
-        match mean_throughput {
-            Some(throughput) => {
-                let remaining_progress = self.target_progress - self.current_progress;
-                let etr_seconds = remaining_progress / throughput;
-                eprintln!("{:.2}% [ETA: {:.0}s]", progress_percentage, etr_seconds);
+        if self.silent {
+            return;
+        }
+        let progress_percentage = self.percentage();
+
+        let etr_seconds = self.etr_seconds(mean_throughput);
+
+        match (self.format, etr_seconds) {
+            (ProgressFormat::Json, _) => {
+                let event = ProgressEvent {
+                    event: "progress",
+                    percentage: progress_percentage,
+                    throughput: mean_throughput,
+                    eta_seconds: etr_seconds,
+                };
+                // Serializing this struct can't fail, and progress output must never abort the analysis
+                if let Ok(line) = serde_json::to_string(&event) {
+                    eprintln!("{}", line);
+                }
+            }
+            (ProgressFormat::Text, Some(etr_seconds)) => {
+                eprintln!("{:.2}% [ETA: {:.0}s]", progress_percentage, etr_seconds)
+            }
+            (ProgressFormat::Text, None) => eprintln!("{:.2}%", progress_percentage),
+        }
+    }
+}
+
+/// Spinner for tasks without a known target, showing a live count of the items that were processed so far
+#[derive(Debug)]
+pub struct Spinner {
+    message: &'static str,
+    count: usize,
+    frame: usize,
+    last_update: Option<Instant>,
+}
+
+impl Spinner {
+    const FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+    /// Minimum time between two updates of the spinner, so that fast tasks don't spend their time printing
+    const UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+
+    pub fn new(message: &'static str) -> Self {
+        Self {
+            message,
+            count: 0,
+            frame: 0,
+            last_update: None,
+        }
+    }
+
+    pub fn inc(&mut self) {
+        self.count += 1;
+
+        let now = Instant::now();
+        if let Some(last_update) = self.last_update {
+            if now.duration_since(last_update) < Self::UPDATE_INTERVAL {
+                return;
             }
-            None => eprintln!("{:.2}%", progress_percentage),
         }
+        self.last_update = Some(now);
+        self.frame = (self.frame + 1) % Self::FRAMES.len();
+        eprint!(
+            "\r{} {}: {}",
+            Self::FRAMES[self.frame],
+            self.message,
+            self.count
+        );
+        let _ = std::io::stderr().flush();
+    }
+
+    /// Prints the final count, replacing the spinner. The trailing spaces overwrite the rest of the spinner line
+    pub fn finish(self) {
+        eprintln!("\r{}: {}  ", self.message, self.count);
     }
 }
@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+
+/// Streaming estimator for quantiles of non-negative integers with a guaranteed relative accuracy. Values are
+/// counted in logarithmically sized bins, so memory grows with the logarithm of the value range instead of the
+/// number of values, and the values don't have to be stored or sorted. Each estimated quantile is within
+/// `relative_accuracy * value` of the value at the exact rank
+#[derive(Debug, Clone)]
+pub struct QuantileSketch {
+    relative_accuracy: f64,
+    /// Natural logarithm of the growth factor between the bounds of two consecutive bins
+    ln_gamma: f64,
+    /// Zero can't be put into a logarithmic bin, so zeros are counted separately
+    zero_count: u64,
+    /// Number of values in each bin. Bin `i` contains the values in (gamma^(i-1); gamma^i]
+    bins: BTreeMap<i32, u64>,
+    count: u64,
+}
+
+impl QuantileSketch {
+    /// Relative accuracy that is used if no other accuracy is given, i.e. estimates are within 1% of the exact value
+    pub const DEFAULT_RELATIVE_ACCURACY: f64 = 0.01;
+
+    /// Creates a new, empty sketch. `relative_accuracy` must be in (0;1)
+    pub fn new(relative_accuracy: f64) -> Self {
+        if relative_accuracy <= 0.0 || relative_accuracy >= 1.0 {
+            panic!("QuantileSketch::new: relative_accuracy must be in (0;1)!");
+        }
+        let gamma = (1.0 + relative_accuracy) / (1.0 - relative_accuracy);
+        Self {
+            relative_accuracy,
+            ln_gamma: gamma.ln(),
+            zero_count: 0,
+            bins: BTreeMap::new(),
+            count: 0,
+        }
+    }
+
+    pub fn relative_accuracy(&self) -> f64 {
+        self.relative_accuracy
+    }
+
+    /// Number of values that were inserted into the sketch
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn insert(&mut self, value: u64) {
+        self.count += 1;
+        if value == 0 {
+            self.zero_count += 1;
+            return;
+        }
+        let bin_index = ((value as f64).ln() / self.ln_gamma).ceil() as i32;
+        *self.bins.entry(bin_index).or_insert(0) += 1;
+    }
+
+    /// Estimates the value at quantile `q`, which must be in [0;1]. Returns `None` if the sketch is empty
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = (q.max(0.0).min(1.0) * (self.count - 1) as f64).floor() as u64;
+
+        let mut cumulative_count = self.zero_count;
+        if rank < cumulative_count {
+            return Some(0.0);
+        }
+        for (&bin_index, &bin_count) in self.bins.iter() {
+            cumulative_count += bin_count;
+            if rank < cumulative_count {
+                // The midpoint of the bin in terms of relative error, 2 * gamma^i / (gamma + 1)
+                let gamma = self.ln_gamma.exp();
+                return Some(2.0 * (bin_index as f64 * self.ln_gamma).exp() / (gamma + 1.0));
+            }
+        }
+        unreachable!("The rank is always smaller than the number of values in the sketch")
+    }
+}
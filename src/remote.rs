@@ -0,0 +1,52 @@
+//! Reading the headers of LAS/LAZ files over HTTP(S), without downloading the whole files
+use std::path::Path;
+
+#[cfg(feature = "http")]
+use anyhow::{anyhow, Context, Result};
+#[cfg(feature = "http")]
+use std::io::Read;
+
+/// Size of the header of a LAS 1.4 file in bytes, which is the largest header of all LAS versions
+#[cfg(feature = "http")]
+const MAX_HEADER_SIZE: u64 = 375;
+
+/// Returns the URL if `path` is an HTTP or HTTPS URL instead of a path in the file system
+pub fn as_url(path: &Path) -> Option<&str> {
+    let path = path.to_str()?;
+    if path.starts_with("http://") || path.starts_with("https://") {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Reads the bytes of the LAS header of the file at `url` with an HTTP range request, returning them together with
+/// the size of the whole file in bytes. Servers that don't support range requests send the whole file, of which
+/// only the header is read
+#[cfg(feature = "http")]
+pub fn read_header_bytes(url: &str) -> Result<(Vec<u8>, u64)> {
+    let mut response = ureq::get(url)
+        .header("Range", &format!("bytes=0-{}", MAX_HEADER_SIZE - 1))
+        .call()
+        .with_context(|| format!("Could not request {}", url))?;
+
+    // 'Content-Range: bytes 0-374/12345' for partial responses, 'Content-Length: 12345' if the server ignored the range
+    let file_size = match response.headers().get("Content-Range") {
+        Some(content_range) => content_range
+            .to_str()
+            .ok()
+            .and_then(|content_range| content_range.rsplit('/').next())
+            .and_then(|file_size| file_size.parse().ok()),
+        None => response.body().content_length(),
+    }
+    .ok_or_else(|| anyhow!("Could not determine the file size of {}", url))?;
+
+    let mut bytes = vec![];
+    response
+        .body_mut()
+        .as_reader()
+        .take(MAX_HEADER_SIZE)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Could not read the header of {}", url))?;
+    Ok((bytes, file_size))
+}
@@ -0,0 +1,126 @@
+use crate::analyzer::AnalyzerResult;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Contents of the status file
+#[derive(Serialize)]
+struct Status<'a> {
+    /// 'running', 'finished', 'timed_out' or 'failed'
+    state: &'static str,
+    /// Start and last update of the run in RFC 3339 format
+    started: &'a str,
+    updated: String,
+    /// Percentage of the files that were read so far, if the analysis reports its progress
+    percentage: Option<f64>,
+    eta_seconds: Option<f64>,
+    error: Option<String>,
+    /// All results that are available so far
+    results: &'a [serde_json::Value],
+}
+
+#[derive(Debug, Default)]
+struct StatusState {
+    last_write: Option<Instant>,
+    percentage: Option<f64>,
+    eta_seconds: Option<f64>,
+    results: Vec<serde_json::Value>,
+    /// Set after the first failed write, so that a failing status file is only reported once
+    write_failed: bool,
+}
+
+/// Status of a running analysis that is persisted to a JSON file, for monitoring long runs from outside, e.g. by a
+/// dashboard that polls the file. The file contains the state of the run, the progress and all results that are
+/// available so far. It is replaced atomically by writing a temporary file next to it and renaming it, so readers
+/// never see a partially written file
+#[derive(Debug)]
+pub struct StatusFile {
+    path: PathBuf,
+    /// Minimum time between two writes because of progress updates. New results are always written immediately
+    interval: Duration,
+    started: String,
+    state: Mutex<StatusState>,
+}
+
+impl StatusFile {
+    pub fn new<P: AsRef<Path>>(path: P, interval: Duration) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+            interval,
+            started: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+            state: Mutex::new(StatusState::default()),
+        }
+    }
+
+    /// Records the current progress and writes the file if the last write was at least `interval` ago
+    pub fn set_progress(&self, percentage: f64, eta_seconds: Option<f64>) {
+        let mut state = self.state.lock().unwrap();
+        state.percentage = Some(percentage);
+        state.eta_seconds = eta_seconds;
+        let due = state
+            .last_write
+            .map_or(true, |last_write| last_write.elapsed() >= self.interval);
+        if due {
+            self.write_running(&mut state);
+        }
+    }
+
+    /// Adds a result and writes the file
+    pub fn add_result(&self, result: &AnalyzerResult) {
+        let mut state = self.state.lock().unwrap();
+        match serde_json::to_value(result) {
+            Ok(value) => state.results.push(value),
+            Err(why) => eprintln!("Could not add a result to the status file: {}", why),
+        }
+        self.write_running(&mut state);
+    }
+
+    /// Writes the final status after the analysis has finished, timed out or failed with `error`
+    pub fn finish(&self, error: Option<&anyhow::Error>, timed_out: bool) -> Result<()> {
+        let state = self.state.lock().unwrap();
+        let run_state = match (error, timed_out) {
+            (Some(_), _) => "failed",
+            (None, true) => "timed_out",
+            (None, false) => "finished",
+        };
+        self.write(&state, run_state, error.map(|error| format!("{:#}", error)))
+    }
+
+    /// Writes the file while the analysis is still running. Failing to write must never abort the analysis, so
+    /// errors are only printed
+    fn write_running(&self, state: &mut StatusState) {
+        state.last_write = Some(Instant::now());
+        if let Err(why) = self.write(state, "running", None) {
+            if !state.write_failed {
+                eprintln!("{:#}", why);
+                state.write_failed = true;
+            }
+        }
+    }
+
+    fn write(
+        &self,
+        state: &StatusState,
+        run_state: &'static str,
+        error: Option<String>,
+    ) -> Result<()> {
+        let status = Status {
+            state: run_state,
+            started: &self.started,
+            updated: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+            percentage: state.percentage,
+            eta_seconds: state.eta_seconds,
+            error,
+            results: &state.results,
+        };
+        let mut temp_name = self.path.file_name().unwrap_or_default().to_owned();
+        temp_name.push(".tmp");
+        let temp_path = self.path.with_file_name(temp_name);
+        std::fs::write(&temp_path, serde_json::to_vec_pretty(&status)?)
+            .with_context(|| format!("Could not write status file {}", temp_path.display()))?;
+        std::fs::rename(&temp_path, &self.path)
+            .with_context(|| format!("Could not replace status file {}", self.path.display()))
+    }
+}
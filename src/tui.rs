@@ -0,0 +1,212 @@
+use crate::analyzer::{format_bucket_end, format_number, AnalyzerResult, Histogram, NodeInfo};
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Paragraph, Row, Table, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+
+const TAB_TITLES: [&str; 3] = ["Summary", "Histogram", "Largest nodes"];
+/// Number of lines that PageUp and PageDown scroll
+const PAGE_SIZE: usize = 10;
+
+/// Shows the given results in an interactive terminal view with three tabs: all results as text, the point count
+/// histogram as a bar chart and the nodes sorted by their point count. Left/Right (or Tab) switch between the
+/// tabs, Up/Down and PageUp/PageDown scroll within a tab and `q` or Esc exits
+pub fn show(results: &[AnalyzerResult], raw_numbers: bool) -> Result<()> {
+    let mut view = ResultsView::new(results, raw_numbers);
+    let mut terminal = ratatui::try_init()?;
+    let outcome = view.run(&mut terminal);
+    // Always give the terminal back, even if drawing failed
+    ratatui::restore();
+    outcome
+}
+
+struct ResultsView<'a> {
+    summary: Vec<String>,
+    histogram: Option<&'a Histogram>,
+    /// All nodes, largest first
+    nodes: Vec<&'a NodeInfo>,
+    raw_numbers: bool,
+    tab: usize,
+    /// Scroll offset of each tab
+    offsets: [usize; 3],
+}
+
+impl<'a> ResultsView<'a> {
+    fn new(results: &'a [AnalyzerResult], raw_numbers: bool) -> Self {
+        let mut summary = vec![];
+        let mut histogram = None;
+        let mut nodes = vec![];
+        for result in results.iter() {
+            // Without the notes of `--explain`, so that e.g. an explained histogram is still shown as a bar chart
+            match result.inner() {
+                AnalyzerResult::Nodes(all_nodes) => nodes.extend(all_nodes.iter()),
+                other => {
+                    if let AnalyzerResult::Histogram(point_histogram) = other {
+                        histogram = histogram.or(Some(point_histogram));
+                    }
+                    let text = if raw_numbers {
+                        format!("{:#}", result)
+                    } else {
+                        format!("{}", result)
+                    };
+                    summary.extend(text.lines().map(str::to_owned));
+                }
+            }
+        }
+        nodes.sort_by(|a, b| b.point_count.cmp(&a.point_count));
+
+        Self {
+            summary,
+            histogram,
+            nodes,
+            raw_numbers,
+            tab: 0,
+            offsets: [0; 3],
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let key = match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => key,
+                _ => continue,
+            };
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Right | KeyCode::Tab => self.tab = (self.tab + 1) % TAB_TITLES.len(),
+                KeyCode::Left | KeyCode::BackTab => {
+                    self.tab = (self.tab + TAB_TITLES.len() - 1) % TAB_TITLES.len()
+                }
+                KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.scroll_by(1),
+                KeyCode::PageUp => self.scroll_by(-(PAGE_SIZE as isize)),
+                KeyCode::PageDown => self.scroll_by(PAGE_SIZE as isize),
+                KeyCode::Home => self.offsets[self.tab] = 0,
+                _ => {}
+            }
+        }
+    }
+
+    /// Number of lines of the current tab, which bounds its scroll offset
+    fn line_count(&self) -> usize {
+        match self.tab {
+            0 => self.summary.len(),
+            1 => self
+                .histogram
+                .map_or(0, |histogram| histogram.buckets().len()),
+            _ => self.nodes.len(),
+        }
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        let max_offset = self.line_count().saturating_sub(1) as isize;
+        let offset = (self.offsets[self.tab] as isize + delta).clamp(0, max_offset.max(0));
+        self.offsets[self.tab] = offset as usize;
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [tabs_area, content_area, help_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let tabs = Tabs::new(TAB_TITLES.to_vec())
+            .select(self.tab)
+            .block(Block::bordered().title("point-cloud-tiles-analyzer"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_widget(tabs, tabs_area);
+
+        let offset = self.offsets[self.tab];
+        match self.tab {
+            0 => {
+                let lines = self
+                    .summary
+                    .iter()
+                    .map(|line| Line::from(line.as_str()))
+                    .collect::<Vec<_>>();
+                let summary = Paragraph::new(lines)
+                    .scroll((offset.min(u16::MAX as usize) as u16, 0))
+                    .block(Block::bordered().title(TAB_TITLES[0]));
+                frame.render_widget(summary, content_area);
+            }
+            1 => match self.histogram {
+                Some(histogram) => {
+                    let bars = histogram
+                        .buckets()
+                        .iter()
+                        .skip(offset)
+                        .map(|bucket| {
+                            let range = bucket.range();
+                            Bar::default()
+                                .value(bucket.count() as u64)
+                                .text_value(format_number(bucket.count() as f64, self.raw_numbers))
+                                .label(Line::from(format!(
+                                    "[{};{})",
+                                    format_number(range.start as f64, self.raw_numbers),
+                                    format_bucket_end(range.end, self.raw_numbers)
+                                )))
+                        })
+                        .collect::<Vec<_>>();
+                    let chart = BarChart::default()
+                        .direction(Direction::Horizontal)
+                        .bar_width(1)
+                        .bar_gap(0)
+                        .data(BarGroup::default().bars(&bars))
+                        .block(Block::bordered().title("Number of nodes per point count range"));
+                    frame.render_widget(chart, content_area);
+                }
+                None => frame.render_widget(
+                    Paragraph::new("No histogram was calculated, run with --histogram")
+                        .block(Block::bordered().title(TAB_TITLES[1])),
+                    content_area,
+                ),
+            },
+            _ => {
+                if self.nodes.is_empty() {
+                    frame.render_widget(
+                        Paragraph::new("No nodes were collected")
+                            .block(Block::bordered().title(TAB_TITLES[2])),
+                        content_area,
+                    );
+                } else {
+                    let rows = self.nodes.iter().skip(offset).map(|node| {
+                        Row::new(vec![
+                            node.id.clone(),
+                            format_number(node.point_count as f64, self.raw_numbers),
+                            format_number(node.file_size as f64, self.raw_numbers),
+                        ])
+                    });
+                    let table = Table::new(
+                        rows,
+                        [
+                            Constraint::Min(20),
+                            Constraint::Length(12),
+                            Constraint::Length(12),
+                        ],
+                    )
+                    .header(
+                        Row::new(vec!["Node", "Points", "Bytes"])
+                            .style(Style::default().add_modifier(Modifier::BOLD)),
+                    )
+                    .block(Block::bordered().title(format!(
+                        "{} ({} nodes)",
+                        TAB_TITLES[2],
+                        self.nodes.len()
+                    )));
+                    frame.render_widget(table, content_area);
+                }
+            }
+        }
+
+        frame.render_widget(
+            Paragraph::new("←/→ switch tabs · ↑/↓ PgUp/PgDn scroll · q quit"),
+            help_area,
+        );
+    }
+}
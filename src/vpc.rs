@@ -0,0 +1,105 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// Asset of a VPC feature, i.e. a link to the point cloud file
+#[derive(Debug, Deserialize)]
+struct VpcAsset {
+    href: String,
+}
+
+/// The STAC properties of a VPC feature that are relevant for analyzing the dataset
+#[derive(Debug, Default, Deserialize)]
+struct VpcProperties {
+    #[serde(rename = "pc:count")]
+    point_count: Option<u64>,
+    #[serde(rename = "proj:epsg")]
+    epsg: Option<u32>,
+    /// Bounding box in the coordinate reference system of the file, in the same layout as `VpcFeature::bbox`
+    #[serde(rename = "proj:bbox")]
+    bbox: Option<Vec<f64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VpcFeature {
+    #[serde(default)]
+    id: Option<String>,
+    /// Bounding box as [min_x, min_y, min_z, max_x, max_y, max_z], or [min_x, min_y, max_x, max_y] for 2D bounds
+    #[serde(default)]
+    bbox: Vec<f64>,
+    #[serde(default)]
+    properties: VpcProperties,
+    assets: HashMap<String, VpcAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VpcFeatureCollection {
+    features: Vec<VpcFeature>,
+}
+
+/// A single point cloud file that is listed in a virtual point cloud
+#[derive(Debug, Clone)]
+pub struct VpcEntry {
+    /// Path of the point cloud file, relative paths in the VPC are resolved against the directory of the VPC
+    pub file: PathBuf,
+    /// Number of points in the file, if the VPC contains it
+    pub point_count: Option<u64>,
+    /// Bounds of the file as `min` and `max` coordinates, if the VPC contains them. If the VPC only has 2D bounds, the
+    /// z coordinate is unknown and its range is `[-∞;∞]`, so that it doesn't restrict e.g. the overlap checks
+    pub bounds: Option<([f64; 3], [f64; 3])>,
+    /// EPSG code of the coordinate reference system of the file, if the VPC contains it
+    pub epsg: Option<u32>,
+}
+
+/// Reads all entries of the virtual point cloud (.vpc) file at `path`. A VPC file is a STAC FeatureCollection in
+/// which each feature links to one point cloud file through its 'data' asset. The bounds are taken from 'proj:bbox',
+/// which is in the coordinate reference system of the files, or from 'bbox' if it is missing. A feature without any
+/// asset is an error, as skipping it would silently leave out the points of its file
+pub fn read_entries<P: AsRef<Path>>(path: P) -> Result<Vec<VpcEntry>> {
+    let reader = BufReader::new(
+        File::open(path.as_ref())
+            .with_context(|| format!("Could not open VPC file {}", path.as_ref().display()))?,
+    );
+    let collection: VpcFeatureCollection = serde_json::from_reader(reader)
+        .with_context(|| format!("Could not parse VPC file {}", path.as_ref().display()))?;
+    let base_dir = path.as_ref().parent().unwrap_or_else(|| Path::new(""));
+
+    collection
+        .features
+        .into_iter()
+        .enumerate()
+        .map(|(idx, feature)| {
+            let asset = feature
+                .assets
+                .get("data")
+                .or_else(|| feature.assets.values().next())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Feature {} of VPC file {} has no asset that links to a point cloud file!",
+                        feature.id.as_deref().unwrap_or(&idx.to_string()),
+                        path.as_ref().display()
+                    )
+                })?;
+            let bbox = feature.properties.bbox.as_deref().unwrap_or(&feature.bbox);
+            let bounds = match bbox {
+                [min_x, min_y, min_z, max_x, max_y, max_z] => {
+                    Some(([*min_x, *min_y, *min_z], [*max_x, *max_y, *max_z]))
+                }
+                [min_x, min_y, max_x, max_y] => Some((
+                    [*min_x, *min_y, f64::NEG_INFINITY],
+                    [*max_x, *max_y, f64::INFINITY],
+                )),
+                _ => None,
+            };
+            Ok(VpcEntry {
+                file: base_dir.join(&asset.href),
+                point_count: feature.properties.point_count,
+                bounds,
+                epsg: feature.properties.epsg,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,167 @@
+use crate::analyzer::AnalyzerResult;
+use crate::output::RunLabel;
+use anyhow::Result;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use serde_json::Value;
+use std::io::Write;
+
+/// Writes the results as an XML document. The element tree follows the structure of the results, and the element
+/// names are stable:
+///
+/// - The root element is `<report>`. If the run has a label, it has `label` and `timestamp` attributes
+/// - Each result is an element named after the result kind in camel case, e.g. `<nodeCount>` or `<histogram>`. Notes
+///   of `--explain` are written as an `explanation` attribute of this element
+/// - Plain values are written as text, e.g. `<nodeCount>12345</nodeCount>`
+/// - Fields with plain values are written as attributes in camel case, fields with nested values as child elements,
+///   e.g. `<bucket count="42"><range end="1000" start="0"/></bucket>`
+/// - Lists whose name is a plural are written as one element per entry, named after the singular, e.g. the buckets of
+///   a histogram as `<bucket>` elements directly below `<histogram>`. Entries of all other lists, including the
+///   values of tuples, are written as `<entry>` elements below an element named after the list
+pub fn write_report(
+    writer: &mut dyn Write,
+    results: &[AnalyzerResult],
+    label: Option<&RunLabel>,
+) -> Result<()> {
+    let mut writer = Writer::new_with_indent(writer, b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+
+    let mut report = BytesStart::new("report");
+    if let Some(label) = label {
+        report.push_attribute(("label", label.label.as_str()));
+        report.push_attribute(("timestamp", label.timestamp.as_str()));
+    }
+    writer.write_event(Event::Start(report))?;
+    for result in results.iter() {
+        let (result, explanation) = match result {
+            AnalyzerResult::Explained {
+                result,
+                explanation,
+            } => (result.as_ref(), Some(explanation.as_str())),
+            _ => (result, None),
+        };
+
+        // Results are serialized as a map with the name of the result kind as the only key, e.g. {"NodeCount": 12}
+        if let Value::Object(map) = serde_json::to_value(result)? {
+            for (kind, value) in map.iter() {
+                write_element(&mut writer, &camel_case(kind), value, explanation)?;
+            }
+        }
+    }
+    writer.write_event(Event::End(BytesEnd::new("report")))?;
+    writeln!(writer.into_inner())?;
+    Ok(())
+}
+
+fn write_element<W: Write>(
+    writer: &mut Writer<W>,
+    name: &str,
+    value: &Value,
+    explanation: Option<&str>,
+) -> Result<()> {
+    let mut start = BytesStart::new(name);
+    if let Some(explanation) = explanation {
+        start.push_attribute(("explanation", explanation));
+    }
+
+    match value {
+        Value::Object(map) => {
+            for (field, value) in map.iter() {
+                if let Some(text) = as_text(value) {
+                    start.push_attribute((camel_case(field).as_str(), text.as_str()));
+                }
+            }
+            let children = map
+                .iter()
+                .filter(|(_, value)| value.is_object() || value.is_array())
+                .collect::<Vec<_>>();
+            if children.is_empty() {
+                writer.write_event(Event::Empty(start))?;
+                return Ok(());
+            }
+
+            writer.write_event(Event::Start(start))?;
+            for (field, value) in children {
+                let field = camel_case(field);
+                match (value, singular(&field)) {
+                    (Value::Array(entries), Some(entry_name)) => {
+                        for entry in entries.iter() {
+                            write_element(writer, &entry_name, entry, None)?;
+                        }
+                    }
+                    _ => write_element(writer, &field, value, None)?,
+                }
+            }
+            writer.write_event(Event::End(BytesEnd::new(name)))?;
+        }
+        Value::Array(entries) => {
+            if entries.is_empty() {
+                writer.write_event(Event::Empty(start))?;
+                return Ok(());
+            }
+
+            let entry_name = singular(name).unwrap_or_else(|| "entry".to_owned());
+            writer.write_event(Event::Start(start))?;
+            for entry in entries.iter() {
+                write_element(writer, &entry_name, entry, None)?;
+            }
+            writer.write_event(Event::End(BytesEnd::new(name)))?;
+        }
+        _ => match as_text(value) {
+            Some(text) => {
+                writer.write_event(Event::Start(start))?;
+                writer.write_event(Event::Text(BytesText::new(&text)))?;
+                writer.write_event(Event::End(BytesEnd::new(name)))?;
+            }
+            None => writer.write_event(Event::Empty(start))?,
+        },
+    }
+    Ok(())
+}
+
+/// Returns the text of a plain value, or `None` for null values and nested values
+fn as_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(string) => Some(string.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(boolean) => Some(boolean.to_string()),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+/// Converts the name of a result kind or a field to camel case, e.g. `NodeCount` to `nodeCount` and `point_count` to
+/// `pointCount`
+fn camel_case(name: &str) -> String {
+    let mut camel_case = String::with_capacity(name.len());
+    let mut uppercase_next = false;
+    for (index, c) in name.chars().enumerate() {
+        if c == '_' {
+            uppercase_next = true;
+        } else if index == 0 {
+            camel_case.extend(c.to_lowercase());
+        } else if uppercase_next {
+            camel_case.extend(c.to_uppercase());
+            uppercase_next = false;
+        } else {
+            camel_case.push(c);
+        }
+    }
+    camel_case
+}
+
+/// Returns the singular of a plural name in camel case, e.g. `bucket` for `buckets` or `anomaly` for `anomalies`, or
+/// `None` if the name is no plural
+fn singular(name: &str) -> Option<String> {
+    if let Some(stem) = name.strip_suffix("ies") {
+        return Some(format!("{}y", stem));
+    }
+    for suffix in ["ches", "shes", "sses", "xes"].iter() {
+        if name.ends_with(suffix) {
+            return Some(name[..name.len() - 2].to_owned());
+        }
+    }
+    if name.ends_with('s') && !name.ends_with("ss") && name.len() > 1 {
+        return Some(name[..name.len() - 1].to_owned());
+    }
+    None
+}
@@ -0,0 +1,966 @@
+use point_cloud_tiles_analyzer::analyzer::{
+    explain_results, AnalysisOptions, Analyzer, AnalyzerResult, DumpSort, EptHierarchyAnalyzer,
+    HistogramConfig, ManifestAnalyzer, MultiFileAnalyzer, PotreeV2FormatAnalyzer,
+    SchwarzwaldAnalyzer, VpcAnalyzer,
+};
+use point_cloud_tiles_analyzer::error::AnalyzerError;
+use point_cloud_tiles_analyzer::potree_v2::RecordLayout;
+use regex::Regex;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+
+mod common;
+use common::{Fixture, COUNT_FROM_NAME};
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("data")
+        .join(name)
+}
+
+fn count_and_histogram_options() -> AnalysisOptions {
+    AnalysisOptions {
+        count_nodes: true,
+        histogram_config: Some(HistogramConfig::Linear(4)),
+        ..Default::default()
+    }
+}
+
+fn node_count(results: &[AnalyzerResult]) -> usize {
+    results
+        .iter()
+        .find_map(|result| match result.inner() {
+            AnalyzerResult::NodeCount(count) => Some(*count),
+            _ => None,
+        })
+        .expect("results contain no node count")
+}
+
+fn histogram_sum(results: &[AnalyzerResult]) -> usize {
+    results
+        .iter()
+        .find_map(|result| match result {
+            AnalyzerResult::Histogram(histogram) => Some(
+                histogram
+                    .buckets()
+                    .iter()
+                    .map(|bucket| bucket.count())
+                    .sum(),
+            ),
+            _ => None,
+        })
+        .expect("results contain no histogram")
+}
+
+#[test]
+fn multi_file_analyzer_counts_las_files() {
+    let analyzer =
+        MultiFileAnalyzer::new(fixture_path("las"), count_and_histogram_options()).unwrap();
+    let results = analyzer.run().unwrap();
+
+    assert_eq!(node_count(&results), 3);
+    assert_eq!(histogram_sum(&results), 3);
+}
+
+#[test]
+fn multi_file_analyzer_does_not_count_repeated_roots_twice() {
+    let roots = [fixture_path("las"), fixture_path("las")];
+    let analyzer = MultiFileAnalyzer::from_roots(&roots, count_and_histogram_options()).unwrap();
+    let results = analyzer.run().unwrap();
+
+    assert_eq!(node_count(&results), 3);
+}
+
+#[test]
+fn trust_data_recovers_the_point_count_of_las_files_with_a_zero_header_count() {
+    let fixture = Fixture::new("trust_data");
+    // r1.las contains two points, but the header of the copy declares none: Zero the legacy point count at byte 107
+    // and the legacy point counts by return that follow it
+    let mut data = std::fs::read(fixture_path("las").join("r1.las")).unwrap();
+    for byte in data[107..131].iter_mut() {
+        *byte = 0;
+    }
+    let file = fixture.write("r1.las", &data);
+
+    let run = |trust_data: bool| {
+        let options = AnalysisOptions {
+            total_points: true,
+            trust_data,
+            ..Default::default()
+        };
+        MultiFileAnalyzer::new(fixture.path(), options)
+            .unwrap()
+            .run()
+            .unwrap()
+    };
+    let untrusted_results = run(false);
+    let trusted_results = run(true);
+
+    let total_points = |results: &[AnalyzerResult]| {
+        results
+            .iter()
+            .find_map(|result| match result {
+                AnalyzerResult::TotalPoints(total) => Some(*total),
+                _ => None,
+            })
+            .expect("results contain no total points")
+    };
+    assert_eq!(total_points(&untrusted_results), 0);
+    assert_eq!(total_points(&trusted_results), 2);
+    let recovered_files = trusted_results
+        .iter()
+        .find_map(|result| match result {
+            AnalyzerResult::RecoveredPointCounts { files } => Some(files.clone()),
+            _ => None,
+        })
+        .expect("results contain no recovered point counts");
+    assert_eq!(recovered_files, vec![(file.display().to_string(), 2)]);
+}
+
+#[test]
+fn multi_file_analyzer_builder_runs_in_its_own_thread_pool() {
+    let analyzer = MultiFileAnalyzer::builder()
+        .root_dir(fixture_path("las"))
+        .count_nodes(true)
+        .threads(2)
+        .build()
+        .unwrap();
+    let results = analyzer.run().unwrap();
+
+    assert_eq!(node_count(&results), 3);
+}
+
+#[test]
+fn analyzers_report_missing_inputs_as_distinct_errors() {
+    let missing_dir = fixture_path("does_not_exist");
+    assert!(matches!(
+        MultiFileAnalyzer::new(&missing_dir, AnalysisOptions::default()),
+        Err(AnalyzerError::RootDirNotFound(path)) if path == missing_dir
+    ));
+    assert!(matches!(
+        PotreeV2FormatAnalyzer::new(fixture_path("las"), AnalysisOptions::default()),
+        Err(AnalyzerError::FileNotFound(path)) if path.ends_with("hierarchy.bin")
+    ));
+}
+
+#[test]
+fn schwarzwald_analyzer_parses_node_levels_from_nested_files() {
+    let fixture = Fixture::new("schwarzwald");
+    fixture.write("metadata.json", b"{}");
+    fixture.write_empty(&["r.laz", "r0/r0.laz", "r0/r04.laz", "r0/readme.laz"]);
+    let options = AnalysisOptions {
+        count_nodes: true,
+        ..Default::default()
+    };
+    let analyzer = SchwarzwaldAnalyzer::new(fixture.path(), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    assert_eq!(node_count(&results), 4);
+    let mut levels = analyzer
+        .nodes()
+        .iter()
+        .map(|node| (node.name.as_str(), node.level))
+        .collect::<Vec<_>>();
+    levels.sort();
+    assert_eq!(levels, vec![("r", 0), ("r0", 1), ("r04", 2)]);
+}
+
+#[test]
+fn multi_file_analyzer_builder_requires_an_input() {
+    assert!(MultiFileAnalyzer::builder()
+        .count_nodes(true)
+        .build()
+        .is_err());
+    assert!(MultiFileAnalyzer::builder()
+        .root_dir(fixture_path("las"))
+        .threads(0)
+        .build()
+        .is_err());
+}
+
+#[test]
+fn multi_file_analyzer_flags_partially_rewritten_datasets() {
+    let fixture = Fixture::new("mtime_stats");
+    fixture.write_empty(&["r0.laz"]);
+    let old_file = std::fs::File::create(fixture.join("r1.laz")).unwrap();
+    old_file
+        .set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(7200))
+        .unwrap();
+    drop(old_file);
+
+    let options = AnalysisOptions {
+        mtime_stats: Some(3600),
+        ..Default::default()
+    };
+    let analyzer = MultiFileAnalyzer::new(fixture.path(), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    match &results[..] {
+        [AnalyzerResult::MtimeStats {
+            files,
+            oldest,
+            newest,
+            partial_rewrite,
+            ..
+        }] => {
+            assert_eq!(*files, 2);
+            assert!(newest - oldest >= 7199);
+            assert!(partial_rewrite);
+        }
+        _ => panic!("Expected a single MtimeStats result, got {:?}", results),
+    }
+}
+
+#[test]
+fn multi_file_analyzer_finds_duplicate_files() {
+    let fixture = Fixture::new("find_duplicates");
+    // Longer than the fingerprint, so that the file that only differs in its last byte is told apart by the hash of
+    // the full content
+    let content = vec![7u8; 2000];
+    let mut last_byte_differs = content.clone();
+    last_byte_differs[1999] = 8;
+    fixture.write("r0.laz", &content);
+    fixture.write("r1.laz", &content);
+    fixture.write("r2.laz", &last_byte_differs);
+    fixture.write("r3.laz", &content[..1500]);
+
+    let options = AnalysisOptions {
+        find_duplicates: true,
+        ..Default::default()
+    };
+    let analyzer = MultiFileAnalyzer::new(fixture.path(), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    match &results[..] {
+        [AnalyzerResult::DuplicateGroups(groups)] => {
+            assert_eq!(groups.len(), 1);
+            assert_eq!(groups[0].file_size, 2000);
+            assert_eq!(groups[0].wasted_bytes(), 2000);
+            let names = groups[0]
+                .files
+                .iter()
+                .map(|file| std::path::Path::new(file).file_name().unwrap().to_owned())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["r0.laz", "r1.laz"]);
+        }
+        _ => panic!(
+            "Expected a single DuplicateGroups result, got {:?}",
+            results
+        ),
+    }
+}
+
+#[test]
+fn multi_file_analyzer_reads_files_in_batches() {
+    let fixture = Fixture::new("batch_size");
+    fixture
+        .write_empty((0..5).map(|index| format!("node{}_count{}.las", index, 100 * (index + 1))));
+    let options = || AnalysisOptions {
+        count_from_name: Some(Regex::new(COUNT_FROM_NAME).unwrap()),
+        batch_size: Some(2),
+        ..count_and_histogram_options()
+    };
+
+    let results = MultiFileAnalyzer::new(fixture.path(), options())
+        .unwrap()
+        .run()
+        .unwrap();
+    let dump_nodes = MultiFileAnalyzer::new(
+        fixture.path(),
+        AnalysisOptions {
+            dump_nodes: true,
+            ..options()
+        },
+    )
+    .unwrap()
+    .run();
+
+    assert_eq!(node_count(&results), 5);
+    assert_eq!(histogram_sum(&results), 5);
+    assert!(dump_nodes.is_err());
+}
+
+#[test]
+fn multi_file_analyzer_counts_las_laz_pairs_once() {
+    let fixture = Fixture::new("dedup_extensions");
+    fixture.write_empty(&["r0.las", "r0.laz", "r1.laz", "r2.las"]);
+    let options = AnalysisOptions {
+        count_nodes: true,
+        dedup_extensions: true,
+        ..Default::default()
+    };
+    let results = MultiFileAnalyzer::new(fixture.path(), options)
+        .unwrap()
+        .run()
+        .unwrap();
+
+    assert!(results
+        .iter()
+        .any(|result| matches!(result, AnalyzerResult::NodeCount(3))));
+    let collapsed = results.iter().find_map(|result| match result {
+        AnalyzerResult::CollapsedExtensionPairs { pairs, examples } => Some((*pairs, examples)),
+        _ => None,
+    });
+    match collapsed {
+        Some((1, examples)) => assert!(examples[0].ends_with("r0.las")),
+        _ => panic!("Expected a single collapsed pair, got {:?}", results),
+    }
+}
+
+#[test]
+fn multi_file_analyzer_dumps_only_the_smallest_and_largest_nodes() {
+    let fixture = Fixture::new("dump_head_tail");
+    fixture.write_empty(
+        [300, 100, 500, 200, 400]
+            .iter()
+            .enumerate()
+            .map(|(index, point_count)| format!("node{}_count{}.las", index, point_count)),
+    );
+    let options = AnalysisOptions {
+        count_from_name: Some(Regex::new(COUNT_FROM_NAME).unwrap()),
+        dump_nodes: true,
+        dump_head: Some(2),
+        dump_tail: Some(1),
+        dump_sort: DumpSort::PointCount,
+        ..Default::default()
+    };
+    let results = MultiFileAnalyzer::new(fixture.path(), options)
+        .unwrap()
+        .run()
+        .unwrap();
+
+    match &results[..] {
+        [AnalyzerResult::Nodes(nodes)] => {
+            let point_counts = nodes
+                .iter()
+                .map(|node| node.point_count)
+                .collect::<Vec<_>>();
+            assert_eq!(point_counts, vec![100, 200, 500]);
+        }
+        _ => panic!("Expected a single Nodes result, got {:?}", results),
+    }
+}
+
+#[test]
+fn multi_file_analyzer_reads_point_counts_from_file_names() {
+    let fixture = Fixture::new("count_from_name");
+    fixture.write_empty(&["node_0_count100.las", "node_1_count250.las"]);
+
+    let options = AnalysisOptions {
+        count_from_name: Some(Regex::new(COUNT_FROM_NAME).unwrap()),
+        ..count_and_histogram_options()
+    };
+    let analyzer = MultiFileAnalyzer::new(fixture.path(), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    assert_eq!(node_count(&results), 2);
+    assert_eq!(histogram_sum(&results), 2);
+}
+
+#[test]
+fn size_histogram_has_its_own_number_of_buckets() {
+    let fixture = Fixture::new("size_histogram");
+    fixture.write("node_0_count100.las", vec![0; 10]);
+    fixture.write("node_1_count250.las", vec![0; 500]);
+    fixture.write("node_2_count400.las", vec![0; 1000]);
+
+    let options = AnalysisOptions {
+        count_from_name: Some(Regex::new(COUNT_FROM_NAME).unwrap()),
+        histogram_config: Some(HistogramConfig::Linear(2)),
+        size_histogram_config: Some(HistogramConfig::Linear(3)),
+        ..Default::default()
+    };
+    let analyzer = MultiFileAnalyzer::new(fixture.path(), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    let histogram_sizes = |find: fn(&AnalyzerResult) -> bool| {
+        let histogram = results
+            .iter()
+            .find(|result| find(result))
+            .expect("results contain no matching histogram");
+        match histogram {
+            AnalyzerResult::Histogram(histogram) | AnalyzerResult::SizeHistogram(histogram) => (
+                histogram.buckets().len(),
+                histogram
+                    .buckets()
+                    .iter()
+                    .map(|bucket| bucket.count())
+                    .sum::<usize>(),
+            ),
+            _ => unreachable!(),
+        }
+    };
+    assert_eq!(
+        histogram_sizes(|result| matches!(result, AnalyzerResult::Histogram(_))),
+        (2, 3)
+    );
+    assert_eq!(
+        histogram_sizes(|result| matches!(result, AnalyzerResult::SizeHistogram(_))),
+        (3, 3)
+    );
+}
+
+#[test]
+fn potree_v2_analyzer_decodes_hierarchy() {
+    let analyzer =
+        PotreeV2FormatAnalyzer::new(fixture_path("potree_v2"), count_and_histogram_options())
+            .unwrap();
+    let results = analyzer.run().unwrap();
+
+    assert_eq!(node_count(&results), 3);
+    assert_eq!(histogram_sum(&results), 3);
+}
+
+#[test]
+fn potree_v2_analyzer_respects_max_depth() {
+    let options = AnalysisOptions {
+        max_depth: Some(0),
+        ..count_and_histogram_options()
+    };
+    let analyzer = PotreeV2FormatAnalyzer::new(fixture_path("potree_v2"), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    assert_eq!(node_count(&results), 1);
+    assert_eq!(histogram_sum(&results), 1);
+}
+
+#[test]
+fn potree_v2_analyzer_uses_custom_record_layout() {
+    assert!(RecordLayout::custom(22, 8, 4).is_err());
+    assert!(RecordLayout::custom(22, 2, 8).is_err());
+
+    let options = AnalysisOptions {
+        record_layout: Some(RecordLayout::custom(22, 2, 4).unwrap()),
+        ..count_and_histogram_options()
+    };
+    let analyzer = PotreeV2FormatAnalyzer::new(fixture_path("potree_v2"), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    assert_eq!(node_count(&results), 3);
+}
+
+#[test]
+fn potree_v2_analyzer_computes_leaf_point_ratio() {
+    let options = AnalysisOptions {
+        leaf_point_ratio: true,
+        ..Default::default()
+    };
+    let analyzer = PotreeV2FormatAnalyzer::new(fixture_path("potree_v2"), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    match results.as_slice() {
+        [AnalyzerResult::LeafPointRatio {
+            leaf_points,
+            inner_points,
+        }] => {
+            assert_eq!(*leaf_points, 1000);
+            assert_eq!(*inner_points, 1000);
+        }
+        other => panic!("unexpected results {:?}", other),
+    }
+}
+
+#[test]
+fn potree_v2_analyzer_summarizes_levels() {
+    let options = AnalysisOptions {
+        level_summary: true,
+        ..Default::default()
+    };
+    let analyzer = PotreeV2FormatAnalyzer::new(fixture_path("potree_v2"), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    match results.as_slice() {
+        [AnalyzerResult::LevelSummary(levels)] => {
+            let rows = levels
+                .iter()
+                .map(|row| (row.level, row.node_count, row.point_count))
+                .collect::<Vec<_>>();
+            assert_eq!(rows, vec![(0, 1, 1000), (1, 2, 1000)]);
+            assert_eq!(levels[1].mean_points_per_node, 500.0);
+            assert_eq!(levels[1].point_share, 0.5);
+        }
+        other => panic!("unexpected results {:?}", other),
+    }
+}
+
+#[test]
+fn potree_v2_analyzer_computes_lod_curve() {
+    let options = AnalysisOptions {
+        lod_curve: true,
+        ..Default::default()
+    };
+    let analyzer = PotreeV2FormatAnalyzer::new(fixture_path("potree_v2"), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    match results.as_slice() {
+        [AnalyzerResult::LodCurve(curve)] => assert_eq!(curve, &vec![(0, 1000), (1, 2000)]),
+        other => panic!("unexpected results {:?}", other),
+    }
+}
+
+#[test]
+fn potree_v2_analyzer_counts_levels() {
+    let options = AnalysisOptions {
+        level_count: true,
+        ..Default::default()
+    };
+    let analyzer = PotreeV2FormatAnalyzer::new(fixture_path("potree_v2"), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    match results.as_slice() {
+        [AnalyzerResult::LevelCount(level_count)] => assert_eq!(*level_count, 2),
+        other => panic!("unexpected results {:?}", other),
+    }
+}
+
+#[test]
+fn potree_v2_analyzer_cross_checks_node_count() {
+    let options = AnalysisOptions {
+        round_trip: true,
+        ..Default::default()
+    };
+    let analyzer = PotreeV2FormatAnalyzer::new(fixture_path("potree_v2"), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    match results.as_slice() {
+        [AnalyzerResult::CountCrossCheck { flat, walked }] => {
+            assert_eq!(*flat, 3);
+            assert_eq!(*walked, 3);
+        }
+        other => panic!("unexpected results {:?}", other),
+    }
+}
+
+#[test]
+fn count_cross_check_marks_differing_node_counts() {
+    let agreeing = AnalyzerResult::CountCrossCheck { flat: 3, walked: 3 }.to_string();
+    let differing = AnalyzerResult::CountCrossCheck { flat: 5, walked: 3 }.to_string();
+    assert!(agreeing.ends_with("Node counts agree\n"));
+    assert!(differing.contains("Warning: Node counts differ by 2"));
+}
+
+#[test]
+fn potree_v2_analyzer_checks_attributes() {
+    let options = AnalysisOptions {
+        check_attributes: true,
+        ..Default::default()
+    };
+    let analyzer = PotreeV2FormatAnalyzer::new(fixture_path("potree_v2"), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    match results.as_slice() {
+        [AnalyzerResult::AttributeMismatches {
+            bytes_per_point,
+            mismatches,
+        }] => {
+            assert_eq!(*bytes_per_point, 15);
+            assert!(mismatches.is_empty());
+        }
+        other => panic!("unexpected results {:?}", other),
+    }
+}
+
+#[test]
+fn potree_v2_analyzer_sums_disk_usage() {
+    let options = AnalysisOptions {
+        disk_usage: true,
+        ..Default::default()
+    };
+    let root_dir = fixture_path("potree_v2");
+    let analyzer = PotreeV2FormatAnalyzer::new(&root_dir, options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    let file_size = |name: &str| std::fs::metadata(root_dir.join(name)).unwrap().len();
+    match results.as_slice() {
+        [AnalyzerResult::DiskUsage {
+            data_bytes,
+            metadata_bytes,
+        }] => {
+            // The fixture has no octree.bin
+            assert_eq!(*data_bytes, 0);
+            assert_eq!(
+                *metadata_bytes,
+                file_size("hierarchy.bin") + file_size("metadata.json")
+            );
+        }
+        other => panic!("unexpected results {:?}", other),
+    }
+}
+
+#[test]
+fn manifest_analyzer_reads_point_counts() {
+    let analyzer =
+        ManifestAnalyzer::new(fixture_path("manifest.csv"), count_and_histogram_options()).unwrap();
+    let results = analyzer.run().unwrap();
+
+    assert_eq!(node_count(&results), 3);
+    assert_eq!(histogram_sum(&results), 3);
+}
+
+#[test]
+fn manifest_analyzer_restricts_histogram_to_count_range() {
+    let options = AnalysisOptions {
+        count_range: Some((500, 1000)),
+        ..count_and_histogram_options()
+    };
+    let analyzer = ManifestAnalyzer::new(fixture_path("manifest.csv"), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    // The node count is not affected by the count range, only the analyses of the point counts are
+    assert_eq!(node_count(&results), 3);
+    assert_eq!(histogram_sum(&results), 2);
+}
+
+#[test]
+fn manifest_analyzer_calculates_statistics_unless_there_are_no_nodes() {
+    let options = AnalysisOptions {
+        stats: true,
+        ..Default::default()
+    };
+    let results = ManifestAnalyzer::new(fixture_path("manifest.csv"), options)
+        .unwrap()
+        .run()
+        .unwrap();
+    match &results[..] {
+        [AnalyzerResult::Statistics {
+            mean,
+            std_dev,
+            count,
+        }] => {
+            assert_eq!(*count, 3);
+            assert!((mean - 2000.0 / 3.0).abs() < 1e-9);
+            assert!((std_dev - 249.444).abs() < 1e-3);
+        }
+        _ => panic!("Expected a single Statistics result, got {:?}", results),
+    }
+
+    let empty_range = AnalysisOptions {
+        stats: true,
+        count_range: Some((2000, 3000)),
+        ..Default::default()
+    };
+    let results = ManifestAnalyzer::new(fixture_path("manifest.csv"), empty_range)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert!(results.is_empty());
+}
+
+#[test]
+fn manifest_analyzer_sums_the_points_of_all_nodes_regardless_of_the_count_range() {
+    let options = AnalysisOptions {
+        total_points: true,
+        count_range: Some((2000, 3000)),
+        ..Default::default()
+    };
+    let results = ManifestAnalyzer::new(fixture_path("manifest.csv"), options)
+        .unwrap()
+        .run()
+        .unwrap();
+    match &results[..] {
+        [AnalyzerResult::TotalPoints(total)] => assert_eq!(*total, 2000),
+        _ => panic!("Expected a single TotalPoints result, got {:?}", results),
+    }
+}
+
+#[test]
+fn statistics_below_one_are_formatted_without_a_metric_prefix() {
+    let fixture = Fixture::new("small_statistics");
+    let manifest = fixture.write("manifest.csv", "node,points\nr,0\nr0,1\n");
+    let options = AnalysisOptions {
+        stats: true,
+        ..Default::default()
+    };
+    let results = ManifestAnalyzer::new(&manifest, options)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert_eq!(
+        results[0].to_string(),
+        "Mean points/node: 0.500, Std dev: 0.500 (over 2 nodes)\n"
+    );
+}
+
+#[test]
+fn manifest_analyzer_interpolates_percentiles() {
+    let options = AnalysisOptions {
+        percentiles: vec![0.0, 50.0, 90.0, 100.0],
+        ..Default::default()
+    };
+    let results = ManifestAnalyzer::new(fixture_path("manifest.csv"), options)
+        .unwrap()
+        .run()
+        .unwrap();
+    match &results[..] {
+        [AnalyzerResult::Percentiles(percentiles)] => assert_eq!(
+            percentiles,
+            &vec![(0.0, 400), (50.0, 600), (90.0, 920), (100.0, 1000)]
+        ),
+        _ => panic!("Expected a single Percentiles result, got {:?}", results),
+    }
+}
+
+#[test]
+fn manifest_analyzer_recommends_downsampling() {
+    let options = AnalysisOptions {
+        downsample_target: Some(500),
+        ..Default::default()
+    };
+    let analyzer = ManifestAnalyzer::new(fixture_path("manifest.csv"), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    match results.as_slice() {
+        [AnalyzerResult::DownsampleRecommendation {
+            count,
+            mean_reduction,
+            max_reduction,
+            ..
+        }] => {
+            // 600 and 1000 points are above the target
+            assert_eq!(*count, 2);
+            assert!((mean_reduction - 1.6).abs() < 1e-9);
+            assert_eq!(*max_reduction, 2.0);
+        }
+        other => panic!("unexpected results {:?}", other),
+    }
+}
+
+#[test]
+fn manifest_analyzer_renders_sparkline() {
+    let options = AnalysisOptions {
+        sparkline: true,
+        ..count_and_histogram_options()
+    };
+    let analyzer = ManifestAnalyzer::new(fixture_path("manifest.csv"), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    let sparkline = results
+        .iter()
+        .find_map(|result| match result {
+            AnalyzerResult::Sparkline(sparkline) => Some(sparkline.as_str()),
+            _ => None,
+        })
+        .expect("results contain no sparkline");
+    assert_eq!(sparkline, "▁███");
+}
+
+#[test]
+fn index_round_trips_point_counts() {
+    let fixture = Fixture::new("index");
+    let index_file = fixture.join("index.bin");
+    let options = AnalysisOptions {
+        write_index: Some(index_file.clone()),
+        ..Default::default()
+    };
+    PotreeV2FormatAnalyzer::new(fixture_path("potree_v2"), options)
+        .unwrap()
+        .run()
+        .unwrap();
+
+    let analyzer =
+        ManifestAnalyzer::from_index(&index_file, count_and_histogram_options()).unwrap();
+    let results = analyzer.run().unwrap();
+
+    assert_eq!(node_count(&results), 3);
+    assert_eq!(histogram_sum(&results), 3);
+}
+
+#[test]
+fn ept_hierarchy_analyzer_follows_hierarchy_files() {
+    let analyzer =
+        EptHierarchyAnalyzer::new(fixture_path("ept_chunked"), count_and_histogram_options())
+            .unwrap();
+    let results = analyzer.run().unwrap();
+
+    // 1-0-1-0 has no points, 1-1-0-0 and 2-2-0-0 are stored in a separate hierarchy file
+    assert_eq!(node_count(&results), 4);
+    assert_eq!(histogram_sum(&results), 4);
+}
+
+#[test]
+fn index_with_an_overflowing_node_count_is_rejected() {
+    let fixture = Fixture::new("overflowing_index");
+    let mut bytes = b"PCTI".to_vec();
+    bytes.push(point_cloud_tiles_analyzer::index::VERSION);
+    bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+    bytes.extend_from_slice(&100u64.to_le_bytes());
+    let index_file = fixture.write("index.bin", bytes);
+
+    let error = ManifestAnalyzer::from_index(&index_file, count_and_histogram_options())
+        .err()
+        .expect("index with an overflowing node count was accepted");
+    assert!(
+        error.to_string().contains("size doesn't match"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn vpc_analyzer_reads_point_counts_from_index() {
+    let options = AnalysisOptions {
+        report_epsg: true,
+        ..count_and_histogram_options()
+    };
+    let analyzer = VpcAnalyzer::new(fixture_path("vpc/dataset.vpc"), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    assert_eq!(node_count(&results), 3);
+    assert_eq!(histogram_sum(&results), 3);
+    assert!(results
+        .iter()
+        .any(|result| matches!(result, AnalyzerResult::Epsg(25832))));
+}
+
+#[test]
+fn explained_results_keep_their_title_and_order() {
+    let analyzer =
+        PotreeV2FormatAnalyzer::new(fixture_path("potree_v2"), count_and_histogram_options())
+            .unwrap();
+    let results = explain_results(analyzer.run().unwrap(), &analyzer.provenance());
+
+    assert!(results
+        .iter()
+        .all(|result| matches!(result, AnalyzerResult::Explained { .. })));
+    assert_eq!(node_count(&results), 3);
+    assert_eq!(results[0].title(), "Node count");
+    assert!(results[0].to_string().contains("hierarchy.bin"));
+}
+
+#[test]
+fn cancelled_analysis_is_only_incomplete_if_it_skipped_work() {
+    let fixture = Fixture::new("cancelled");
+    fixture.write_empty(&["r_count10.las"]);
+    let options = || AnalysisOptions {
+        total_points: true,
+        count_from_name: Some(Regex::new(COUNT_FROM_NAME).unwrap()),
+        quiet: true,
+        ..Default::default()
+    };
+
+    let finished = options();
+    let (cancelled, incomplete) = (finished.cancelled.clone(), finished.incomplete.clone());
+    let results = MultiFileAnalyzer::new(fixture.path(), finished)
+        .unwrap()
+        .run()
+        .unwrap();
+    // Cancelling after all work is done leaves the results complete
+    cancelled.store(true, Ordering::Relaxed);
+    assert!(matches!(results[..], [AnalyzerResult::TotalPoints(10)]));
+    assert!(!incomplete.load(Ordering::Relaxed));
+
+    // Cancelling after the discovery skips reading the file
+    let after_discovery = options();
+    let (cancelled, incomplete) = (
+        after_discovery.cancelled.clone(),
+        after_discovery.incomplete.clone(),
+    );
+    let analyzer = MultiFileAnalyzer::new(fixture.path(), after_discovery).unwrap();
+    cancelled.store(true, Ordering::Relaxed);
+    let results = analyzer.run().unwrap();
+    assert!(matches!(results[..], [AnalyzerResult::TotalPoints(0)]));
+    assert!(incomplete.load(Ordering::Relaxed));
+
+    // Cancelling before the discovery finds no files, which is not an error
+    let before_discovery = options();
+    before_discovery.cancelled.store(true, Ordering::Relaxed);
+    let incomplete = before_discovery.incomplete.clone();
+    let results = MultiFileAnalyzer::new(fixture.path(), before_discovery)
+        .unwrap()
+        .run()
+        .unwrap();
+    assert!(results.is_empty());
+    assert!(incomplete.load(Ordering::Relaxed));
+}
+
+#[test]
+fn all_analyzers_pass_their_results_in_order() {
+    // Enables analyses whose results come before the node count in the canonical order, but finish before it
+    let options = || AnalysisOptions {
+        total_points: true,
+        gini: true,
+        stats: true,
+        sparkline: true,
+        warn_above: Some(1),
+        level_count: true,
+        disk_usage: true,
+        size_histogram_config: Some(HistogramConfig::Linear(2)),
+        approx_quantiles: Some(0.01),
+        quiet: true,
+        ..count_and_histogram_options()
+    };
+    let fixture = Fixture::new("results_in_order");
+    fixture.write_empty(&["r_count10.las", "r0_count20.las"]);
+    let potree_v2 = fixture_path("potree_v2");
+    let analyzers: Vec<(&str, Box<dyn Analyzer>)> = vec![
+        (
+            "files",
+            Box::new(
+                MultiFileAnalyzer::new(
+                    fixture.path(),
+                    AnalysisOptions {
+                        count_from_name: Some(Regex::new(COUNT_FROM_NAME).unwrap()),
+                        mtime_stats: Some(3600),
+                        ..options()
+                    },
+                )
+                .unwrap(),
+            ),
+        ),
+        (
+            "manifest",
+            Box::new(ManifestAnalyzer::new(fixture_path("manifest.csv"), options()).unwrap()),
+        ),
+        (
+            "ept-hierarchy",
+            Box::new(EptHierarchyAnalyzer::new(fixture_path("ept_chunked"), options()).unwrap()),
+        ),
+        (
+            "vpc",
+            Box::new(
+                VpcAnalyzer::new(
+                    fixture_path("vpc/dataset.vpc"),
+                    AnalysisOptions {
+                        report_epsg: true,
+                        ..options()
+                    },
+                )
+                .unwrap(),
+            ),
+        ),
+        (
+            "potree-v2",
+            Box::new(
+                PotreeV2FormatAnalyzer::new(
+                    &potree_v2,
+                    AnalysisOptions {
+                        infer_record_size: true,
+                        leaf_point_ratio: true,
+                        mean_fanout: true,
+                        ..options()
+                    },
+                )
+                .unwrap(),
+            ),
+        ),
+    ];
+
+    for (name, analyzer) in analyzers.iter() {
+        let results = analyzer.run().unwrap();
+        assert!(
+            matches!(results.first(), Some(AnalyzerResult::NodeCount(_))),
+            "{}: {:?}",
+            name,
+            results
+        );
+        assert!(
+            results
+                .windows(2)
+                .all(|pair| pair[0].order_key() <= pair[1].order_key()),
+            "{}: {:?}",
+            name,
+            results
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+mod common;
+use common::{Fixture, COUNT_FROM_NAME};
+
+fn manifest_path() -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("data")
+        .join("manifest.csv")
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_point-cloud-tiles-analyzer"))
+        .arg("--quiet")
+        .args(args)
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn histogram_step_generates_evenly_spaced_buckets() {
+    let output = run(&[
+        "--manifest",
+        &manifest_path(),
+        "--histogram-step",
+        "0:1000:250",
+        "--raw-numbers",
+    ]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with(
+        "Buckets:\n0 in [0;250)\n1 in [250;500)\n1 in [500;750)\n0 in [750;1000)\n1 in [1000;∞)\n"
+    ));
+}
+
+#[test]
+fn histogram_step_rejects_invalid_ranges() {
+    for histogram_step in ["0:1000:0", "1000:1000:10", "1000:0:10", "0:1000", "a:b:c"].iter() {
+        let output = run(&[
+            "--manifest",
+            &manifest_path(),
+            "--histogram-step",
+            histogram_step,
+        ]);
+        assert!(!output.status.success(), "{} was accepted", histogram_step);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("histogram-step"), "{}", stderr);
+    }
+}
+
+#[test]
+fn size_histogram_inherits_the_number_of_buckets_unless_overridden() {
+    // The bucket counts themselves are tested on the analyzer, this only checks how the arguments are combined
+    let fixture = Fixture::new("cli_size_histogram");
+    fixture.write("r_count100.las", vec![0; 100]);
+    let num_size_buckets = |histogram_size: &str| {
+        let output = run(&[
+            "--data-roots",
+            &fixture.path().to_string_lossy(),
+            "--count-from-name",
+            COUNT_FROM_NAME,
+            "--histogram-lin",
+            "2",
+            histogram_size,
+        ]);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let size_histogram = &stdout[stdout.find("Node sizes in bytes:").unwrap()..];
+        size_histogram
+            .lines()
+            .filter(|line| line.contains(" in ["))
+            .count()
+    };
+
+    let inherited = num_size_buckets("--histogram-size");
+    let overridden = num_size_buckets("--histogram-size=3");
+
+    assert_eq!(inherited, 2);
+    assert_eq!(overridden, 3);
+}
+
+#[test]
+fn batch_size_rejects_outputs_that_need_all_node_information() {
+    let fixture = Fixture::new("cli_batch_size");
+    fixture.write("r_count100.las", vec![0; 100]);
+    let output_file = fixture.join("nodes.parquet");
+    let data_roots = fixture.path().to_string_lossy().into_owned();
+    let output_file = output_file.to_string_lossy();
+    for extra_args in [
+        &["--tui"][..],
+        &["--format", "parquet", "--output", &output_file][..],
+    ]
+    .iter()
+    {
+        let mut args = vec![
+            "--data-roots",
+            &data_roots,
+            "--count-from-name",
+            COUNT_FROM_NAME,
+            "--batch-size",
+            "2",
+        ];
+        args.extend_from_slice(extra_args);
+        let output = run(&args);
+        assert!(!output.status.success(), "{:?} was accepted", extra_args);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("batch-size"), "{}", stderr);
+    }
+}
+
+#[test]
+fn unsupported_options_print_one_note_each_unless_quiet() {
+    let args = [
+        "--manifest",
+        &manifest_path(),
+        "--disk-usage",
+        "--check-copc",
+    ];
+    let output = Command::new(env!("CARGO_BIN_EXE_point-cloud-tiles-analyzer"))
+        .args(&args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let notes = stderr
+        .lines()
+        .filter(|line| {
+            line.contains("is not supported when analyzing point counts from a manifest")
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        notes,
+        vec![
+            "Measuring the disk usage is not supported when analyzing point counts from a manifest, skipping",
+            "Checking COPC hierarchies is not supported when analyzing point counts from a manifest, skipping",
+        ]
+    );
+
+    let quiet = run(&args);
+    assert!(quiet.status.success());
+    assert!(quiet.stderr.is_empty());
+}
@@ -0,0 +1,62 @@
+//! Helpers shared by the integration tests
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+/// Regex for `--count-from-name` that reads the point count of a node from file names like `r_count10.las`
+pub const COUNT_FROM_NAME: &str = "count(?P<count>[0-9]+)";
+
+/// Directory for the files of a single test. Its name contains the process ID, so that concurrent test runs don't
+/// share it, and it is removed when the fixture is dropped, also if the test fails
+pub struct Fixture {
+    path: PathBuf,
+}
+
+impl Fixture {
+    /// Creates an empty fixture directory for the test `name`
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "point_cloud_tiles_analyzer_test_{}_{}",
+            name,
+            std::process::id()
+        ));
+        // Left behind by an earlier process with the same ID that was killed
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Path of `file` within the fixture directory
+    pub fn join<P: AsRef<Path>>(&self, file: P) -> PathBuf {
+        self.path.join(file)
+    }
+
+    /// Writes `contents` to `file` within the fixture directory, creating its parent directories, and returns the path
+    /// of the file
+    pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(&self, file: P, contents: C) -> PathBuf {
+        let path = self.path.join(file);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// Writes empty files. Files that are never opened, e.g. because their point count is read from their name with
+    /// `COUNT_FROM_NAME`, don't need to be valid LAS/LAZ files
+    pub fn write_empty<I: IntoIterator<Item = S>, S: AsRef<Path>>(&self, files: I) {
+        for file in files {
+            self.write(file, b"");
+        }
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
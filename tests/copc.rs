@@ -0,0 +1,99 @@
+use point_cloud_tiles_analyzer::analyzer::{
+    AnalysisOptions, Analyzer, AnalyzerResult, MultiFileAnalyzer,
+};
+use point_cloud_tiles_analyzer::copc::{read_point_counts, CopcPointCounts};
+use std::io::Cursor;
+
+mod common;
+use common::Fixture;
+
+/// Appends a hierarchy entry for the node with the given key
+fn hierarchy_entry(bytes: &mut Vec<u8>, key: [i32; 4], offset: u64, byte_size: i32, points: i32) {
+    for value in key.iter() {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes.extend_from_slice(&offset.to_le_bytes());
+    bytes.extend_from_slice(&byte_size.to_le_bytes());
+    bytes.extend_from_slice(&points.to_le_bytes());
+}
+
+/// Builds a COPC file without point data whose header declares `header_points` points. The root hierarchy page
+/// contains the root node and a reference to a second page, which contains a single child node
+fn copc_file(header_points: u64, root_points: i32, child_points: i32) -> Vec<u8> {
+    let mut bytes = vec![0u8; 375];
+    bytes[0..4].copy_from_slice(b"LASF");
+    bytes[24] = 1;
+    bytes[25] = 4;
+    bytes[247..255].copy_from_slice(&header_points.to_le_bytes());
+
+    // VLR header: reserved, user ID, record ID, record length, description
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    let mut user_id = [0u8; 16];
+    user_id[..4].copy_from_slice(b"copc");
+    bytes.extend_from_slice(&user_id);
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&160u16.to_le_bytes());
+    bytes.extend_from_slice(&[0u8; 32]);
+
+    let root_page_offset = (bytes.len() + 160) as u64;
+    let child_page_offset = root_page_offset + 64;
+    let mut info = vec![0u8; 160];
+    info[40..48].copy_from_slice(&root_page_offset.to_le_bytes());
+    info[48..56].copy_from_slice(&64u64.to_le_bytes());
+    bytes.extend_from_slice(&info);
+
+    hierarchy_entry(&mut bytes, [0, 0, 0, 0], 0, 0, root_points);
+    hierarchy_entry(&mut bytes, [1, 0, 0, 0], child_page_offset, 32, -1);
+    hierarchy_entry(&mut bytes, [1, 0, 0, 0], 0, 0, child_points);
+    bytes
+}
+
+#[test]
+fn read_point_counts_sums_all_hierarchy_pages() {
+    let counts = read_point_counts(&mut Cursor::new(copc_file(15, 10, 5)))
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        counts,
+        CopcPointCounts {
+            header: 15,
+            hierarchy: 15,
+            nodes: 2,
+        }
+    );
+    assert!(counts.matches());
+
+    let not_copc = vec![0u8; 1000];
+    assert_eq!(read_point_counts(&mut Cursor::new(not_copc)).unwrap(), None);
+}
+
+#[test]
+fn multi_file_analyzer_reports_copc_point_count_mismatches() {
+    let fixture = Fixture::new("check_copc");
+    fixture.write("valid.copc.laz", copc_file(15, 10, 5));
+    fixture.write("broken.copc.laz", copc_file(20, 10, 5));
+    fixture.write_empty(&["plain.laz"]);
+
+    let options = AnalysisOptions {
+        check_copc: true,
+        ..Default::default()
+    };
+    let analyzer = MultiFileAnalyzer::new(fixture.path(), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    match &results[..] {
+        [AnalyzerResult::CopcPointCountMismatches {
+            copc_files,
+            mismatches,
+        }] => {
+            assert_eq!(*copc_files, 2);
+            assert_eq!(mismatches.len(), 1);
+            assert!(mismatches[0].0.ends_with("broken.copc.laz"));
+            assert_eq!((mismatches[0].1, mismatches[0].2), (20, 15));
+        }
+        _ => panic!(
+            "Expected a single CopcPointCountMismatches result, got {:?}",
+            results
+        ),
+    }
+}
@@ -0,0 +1,13 @@
+use point_cloud_tiles_analyzer::crs::epsg_from_wkt;
+
+#[test]
+fn epsg_from_wkt_does_not_mistake_ellipsoid_for_an_identifier() {
+    let wkt = r#"GEOGCRS["WGS 84",ID["EPSG",4326],DATUM["World Geodetic System 1984",ELLIPSOID["WGS 84",6378137,298.257223563]]]"#;
+    assert_eq!(epsg_from_wkt(wkt), Some(4326));
+
+    let wkt = r#"PROJCRS["ETRS89 / UTM zone 32N",BASEGEOGCRS["ETRS89",DATUM["European Terrestrial Reference System 1989",ELLIPSOID["GRS 1980",6378137,298.257222101,ID["EPSG",7019]]],ID["EPSG",4258]],CONVERSION["UTM zone 32N",METHOD["Transverse Mercator"]],ID["EPSG",25832]]"#;
+    assert_eq!(epsg_from_wkt(wkt), Some(25832));
+
+    let wkt = r#"GEOGCRS["Unknown",DATUM["Unknown",ELLIPSOID["GRS 1980",6378137,298.257222101]]]"#;
+    assert_eq!(epsg_from_wkt(wkt), None);
+}
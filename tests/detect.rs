@@ -0,0 +1,43 @@
+use point_cloud_tiles_analyzer::analyzer::AnalysisOptions;
+use point_cloud_tiles_analyzer::detect::{detect_format, make_analyzer, DatasetFormat};
+use point_cloud_tiles_analyzer::error::AnalyzerError;
+
+mod common;
+use common::Fixture;
+
+#[test]
+fn detect_format_prefers_the_more_confident_check() {
+    let fixture = Fixture::new("detect_entwine");
+    fixture.write_empty(&["ept-data/0-0-0-0.laz"]);
+    fixture.write("ept.json", r#"{"dataType": "laszip"}"#);
+    assert_eq!(
+        detect_format(fixture.path()).unwrap(),
+        Some(DatasetFormat::Entwine)
+    );
+
+    // Binary node files only refine the check for the ept-data directory, which matches as well
+    fixture.write("ept.json", r#"{"dataType": "binary"}"#);
+    assert_eq!(
+        detect_format(fixture.path()).unwrap(),
+        Some(DatasetFormat::ChunkedEntwine)
+    );
+}
+
+#[test]
+fn make_analyzer_reports_unrecognized_formats() {
+    let fixture = Fixture::new("unrecognized_format");
+    assert_eq!(detect_format(fixture.path()).unwrap(), None);
+    assert!(matches!(
+        make_analyzer(fixture.path().to_owned(), AnalysisOptions::default()),
+        Err(AnalyzerError::FormatNotRecognized { hint: None })
+    ));
+
+    fixture.write_empty(&["hierarchy.bin"]);
+    match make_analyzer(fixture.path().to_owned(), AnalysisOptions::default()) {
+        Err(AnalyzerError::FormatNotRecognized { hint: Some(hint) }) => {
+            assert!(hint.contains("octree.bin"), "{}", hint)
+        }
+        Err(error) => panic!("Expected FormatNotRecognized, got {}", error),
+        Ok(_) => panic!("Expected FormatNotRecognized, got an analyzer"),
+    }
+}
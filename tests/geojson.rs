@@ -0,0 +1,63 @@
+use las::{Bounds, Vector};
+use point_cloud_tiles_analyzer::analyzer::NodeInfo;
+use point_cloud_tiles_analyzer::geojson::write_coverage;
+
+mod common;
+use common::Fixture;
+
+fn node(id: &str, point_count: u64, bounds: Option<Bounds>) -> NodeInfo {
+    NodeInfo {
+        id: id.to_owned(),
+        point_count,
+        file_size: 0,
+        bounds,
+        point_record_length: None,
+        point_format: None,
+        header_size: None,
+        point_data_offset: None,
+    }
+}
+
+#[test]
+fn write_coverage_writes_one_polygon_per_node_with_bounds() {
+    let bounds = Bounds {
+        min: Vector {
+            x: 10.0,
+            y: 20.0,
+            z: 0.0,
+        },
+        max: Vector {
+            x: 30.0,
+            y: 40.0,
+            z: 5.0,
+        },
+    };
+    let nodes = vec![
+        node("r0.laz", 1000, Some(bounds)),
+        node("r1.laz", 500, None),
+    ];
+    let fixture = Fixture::new("geojson");
+    let output_file = fixture.join("coverage.geojson");
+    let feature_count = write_coverage(&output_file, &nodes, Some(25832)).unwrap();
+    let output: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&output_file).unwrap()).unwrap();
+
+    assert_eq!(feature_count, 1);
+    assert_eq!(
+        output["crs"]["properties"]["name"],
+        "urn:ogc:def:crs:EPSG::25832"
+    );
+    let feature = &output["features"][0];
+    assert_eq!(feature["properties"]["id"], "r0.laz");
+    assert_eq!(feature["properties"]["point_count"], 1000);
+    assert_eq!(
+        feature["geometry"]["coordinates"],
+        serde_json::json!([[
+            [10.0, 20.0],
+            [30.0, 20.0],
+            [30.0, 40.0],
+            [10.0, 40.0],
+            [10.0, 20.0]
+        ]])
+    );
+}
@@ -0,0 +1,108 @@
+use point_cloud_tiles_analyzer::analyzer::{
+    custom_histogram, quantile_histogram, Histogram, HistogramBucket,
+};
+
+fn histogram(ranges: &[std::ops::Range<usize>]) -> Histogram {
+    Histogram::new(
+        ranges
+            .iter()
+            .map(|range| HistogramBucket::new(1, range.clone()))
+            .collect(),
+        0.0,
+        0.0,
+    )
+}
+
+#[test]
+fn contiguous_buckets_are_valid() {
+    assert!(histogram(&[0..10, 10..20, 20..100]).validate().is_ok());
+    // Rounding can produce empty buckets, which are still contiguous
+    assert!(histogram(&[0..1, 1..1, 1..2]).validate().is_ok());
+    assert!(histogram(&[]).validate().is_ok());
+}
+
+#[test]
+fn gap_between_buckets_is_invalid() {
+    assert!(histogram(&[0..10, 11..20]).validate().is_err());
+}
+
+#[test]
+fn overlapping_buckets_are_invalid() {
+    assert!(histogram(&[0..10, 9..20]).validate().is_err());
+}
+
+#[test]
+fn buckets_not_starting_at_zero_are_invalid() {
+    assert!(histogram(&[1..10, 10..20]).validate().is_err());
+}
+
+#[test]
+fn reversed_bucket_is_invalid() {
+    let reversed = std::ops::Range { start: 10, end: 5 };
+    assert!(histogram(&[0..10, reversed, 5..20]).validate().is_err());
+}
+
+#[test]
+fn dominant_bucket_has_the_most_entries() {
+    let histogram = Histogram::new(
+        vec![
+            HistogramBucket::new(1, 0..10),
+            HistogramBucket::new(3, 10..20),
+            HistogramBucket::new(0, 20..30),
+        ],
+        0.0,
+        0.0,
+    );
+    let (bucket, share) = histogram.dominant_bucket().unwrap();
+    assert_eq!(bucket.range(), &(10..20));
+    assert_eq!(share, 0.75);
+
+    assert!(
+        Histogram::new(vec![HistogramBucket::new(0, 0..10)], 0.0, 0.0)
+            .dominant_bucket()
+            .is_none()
+    );
+}
+
+#[test]
+fn custom_histogram_has_an_open_ended_overflow_bucket() {
+    let histogram = custom_histogram(&[0, 500, 1000, 5000, 200_000], &[0, 1000, 10000, 100_000]);
+    let buckets = histogram
+        .buckets()
+        .iter()
+        .map(|bucket| (bucket.range().clone(), bucket.count()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        buckets,
+        vec![
+            (0..1000, 2),
+            (1000..10000, 2),
+            (10000..100_000, 0),
+            (100_000..usize::MAX, 1),
+        ]
+    );
+    assert!(histogram.validate().is_ok());
+    assert_eq!(format!("{:#}", histogram.buckets()[3]), "1 in [100000;∞)");
+}
+
+#[test]
+fn quantile_histogram_merges_buckets_with_equal_boundaries() {
+    let ranges_and_counts = |histogram: &Histogram| {
+        histogram
+            .buckets()
+            .iter()
+            .map(|bucket| (bucket.range().clone(), bucket.count()))
+            .collect::<Vec<_>>()
+    };
+
+    let histogram = quantile_histogram(&[1, 2, 3, 4, 10, 20, 100, 1000], 4);
+    assert_eq!(
+        ranges_and_counts(&histogram),
+        vec![(0..3, 2), (3..10, 2), (10..100, 2), (100..1001, 2)]
+    );
+
+    // The splits at indices 2 and 4 both have the minimum value 5, so the first three buckets are merged
+    let histogram = quantile_histogram(&[5, 5, 5, 5, 5, 7, 8, 9], 4);
+    assert_eq!(ranges_and_counts(&histogram), vec![(0..8, 6), (8..10, 2)]);
+    assert!(histogram.validate().is_ok());
+}
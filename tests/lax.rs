@@ -0,0 +1,99 @@
+use point_cloud_tiles_analyzer::analyzer::{
+    AnalysisOptions, Analyzer, AnalyzerResult, MultiFileAnalyzer,
+};
+use point_cloud_tiles_analyzer::lax::{read_lax, LaxCell};
+
+mod common;
+use common::Fixture;
+
+/// Builds a `.lax` index with the given cells as pairs of cell index and point count, and one interval per cell
+fn lax_index(cells: &[(i32, u32)]) -> Vec<u8> {
+    let mut bytes = vec![];
+    bytes.extend_from_slice(b"LASX");
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(b"LASS");
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(b"LASQ");
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    // Levels, level index, implicit levels
+    for value in [2u32, 0, 0].iter() {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    // Min x, max x, min y, max y
+    for value in [0.0f32, 100.0, 0.0, 50.0].iter() {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes.extend_from_slice(b"LASV");
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(&(cells.len() as u32).to_le_bytes());
+    let mut start = 0u32;
+    for (index, point_count) in cells.iter() {
+        bytes.extend_from_slice(&index.to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&point_count.to_le_bytes());
+        bytes.extend_from_slice(&start.to_le_bytes());
+        bytes.extend_from_slice(&(start + point_count - 1).to_le_bytes());
+        start += point_count;
+    }
+    bytes
+}
+
+#[test]
+fn read_lax_parses_cells_and_bounds() {
+    let index = read_lax(&lax_index(&[(5, 100), (6, 250)])).unwrap();
+
+    assert_eq!(index.levels, 2);
+    assert_eq!(index.bounds, [0.0, 0.0, 100.0, 50.0]);
+    assert_eq!(
+        index.cells,
+        vec![
+            LaxCell {
+                index: 5,
+                point_count: 100
+            },
+            LaxCell {
+                index: 6,
+                point_count: 250
+            }
+        ]
+    );
+    assert_eq!(index.point_count(), 350);
+}
+
+#[test]
+fn read_lax_rejects_truncated_index() {
+    let bytes = lax_index(&[(5, 100)]);
+    assert!(read_lax(&bytes[..bytes.len() - 4]).is_err());
+}
+
+#[test]
+fn multi_file_analyzer_reads_lax_cells() {
+    let fixture = Fixture::new("use_lax");
+    fixture.write_empty(&["indexed.laz", "unindexed.laz"]);
+    fixture.write("indexed.lax", lax_index(&[(5, 100), (6, 250), (7, 10)]));
+
+    let options = AnalysisOptions {
+        use_lax: true,
+        ..Default::default()
+    };
+    let analyzer = MultiFileAnalyzer::new(fixture.path(), options).unwrap();
+    let results = analyzer.run().unwrap();
+
+    match &results[..] {
+        [AnalyzerResult::LaxCells {
+            indexed_files,
+            unindexed_files,
+            cells,
+            histogram,
+        }] => {
+            assert_eq!((*indexed_files, *unindexed_files, *cells), (1, 1, 3));
+            let total: usize = histogram
+                .buckets()
+                .iter()
+                .map(|bucket| bucket.count())
+                .sum();
+            assert_eq!(total, 3);
+        }
+        _ => panic!("Expected a single LaxCells result, got {:?}", results),
+    }
+}
@@ -0,0 +1,47 @@
+use point_cloud_tiles_analyzer::math::{rounded_percentages, skewness_and_kurtosis};
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn symmetric_distribution_has_zero_skewness() {
+    let (skewness, kurtosis) = skewness_and_kurtosis(&[1, 2, 3, 4, 5]).unwrap();
+    assert_close(skewness, 0.0);
+    // Discrete uniform distribution: 6.8 / 2^2 - 3
+    assert_close(kurtosis, -1.3);
+}
+
+#[test]
+fn bernoulli_distribution_matches_closed_form() {
+    // Bernoulli distribution with p = 0.25: skewness (1 - 2p) / sqrt(pq), excess kurtosis (1 - 6pq) / pq
+    let (skewness, kurtosis) = skewness_and_kurtosis(&[0, 0, 0, 1]).unwrap();
+    let (p, q) = (0.25_f64, 0.75_f64);
+    assert_close(skewness, (1.0 - 2.0 * p) / (p * q).sqrt());
+    assert_close(kurtosis, (1.0 - 6.0 * p * q) / (p * q));
+}
+
+#[test]
+fn heavy_tail_has_positive_skewness() {
+    let (skewness, _) = skewness_and_kurtosis(&[1, 1, 1, 1, 1, 1, 1, 100]).unwrap();
+    assert!(skewness > 0.0);
+}
+
+#[test]
+fn constant_and_empty_data() {
+    assert_eq!(skewness_and_kurtosis(&[7, 7, 7]), Some((0.0, 0.0)));
+    assert_eq!(skewness_and_kurtosis(&[]), None);
+}
+
+#[test]
+fn rounded_percentages_sum_to_100() {
+    // Rounded on their own, these are 33.3% each and only sum to 99.9%
+    assert_eq!(rounded_percentages(&[1, 1, 1], 1), vec![33.4, 33.3, 33.3]);
+    assert_eq!(rounded_percentages(&[1, 2, 997], 1), vec![0.1, 0.2, 99.7]);
+    assert_eq!(rounded_percentages(&[0, 0], 1), vec![0.0, 0.0]);
+}
@@ -0,0 +1,402 @@
+use point_cloud_tiles_analyzer::analyzer::{
+    AnalysisOptions, Analyzer, AnalyzerResult, Histogram, HistogramBucket, HistogramConfig,
+    LevelRow, ManifestAnalyzer,
+};
+use point_cloud_tiles_analyzer::output::{write_results, OutputFormat, RunLabel};
+
+mod common;
+use common::Fixture;
+
+#[test]
+fn flat_output_writes_one_line_per_value() {
+    let results = vec![
+        AnalyzerResult::NodeCount(3),
+        AnalyzerResult::Histogram(Histogram::new(
+            vec![
+                HistogramBucket::new(2, 0..10),
+                HistogramBucket::new(1, 10..20),
+            ],
+            7.5,
+            2.5,
+        )),
+    ];
+    let fixture = Fixture::new("flat");
+    let output_file = fixture.join("output.txt");
+    write_results(
+        &results,
+        &OutputFormat::Flat,
+        Some(&output_file),
+        false,
+        None,
+        "files",
+    )
+    .unwrap();
+    let output = std::fs::read_to_string(&output_file).unwrap();
+
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "node_count=3");
+    assert!(lines.contains(&"histogram.buckets.0.count=2"));
+    assert!(lines.contains(&"histogram.buckets.1.range_start=10"));
+    assert!(lines.contains(&"histogram.mean=7.5"));
+}
+
+#[test]
+fn json_output_is_compact_unless_pretty() {
+    let results = vec![AnalyzerResult::NodeCount(3), AnalyzerResult::Gini(0.25)];
+    let fixture = Fixture::new("json_output");
+    let write = |format: &OutputFormat, name: &str| {
+        let output_file = fixture.join(name);
+        write_results(&results, format, Some(&output_file), false, None, "files").unwrap();
+        let output = std::fs::read_to_string(&output_file).unwrap();
+        output
+    };
+    let compact = write(&OutputFormat::Json, "compact.json");
+    let pretty = write(&OutputFormat::JsonPretty, "pretty.json");
+
+    assert_eq!(compact.lines().count(), 1);
+    assert!(pretty.lines().count() > 1);
+    let compact: serde_json::Value = serde_json::from_str(&compact).unwrap();
+    let pretty: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+    assert_eq!(compact, pretty);
+    assert_eq!(compact[0]["NodeCount"], 3);
+}
+
+#[test]
+fn json_output_writes_flat_histogram_buckets() {
+    let results = vec![AnalyzerResult::Histogram(Histogram::new(
+        vec![
+            HistogramBucket::new(2, 0..10),
+            HistogramBucket::new(1, 10..20),
+        ],
+        8.0,
+        2.0,
+    ))];
+    let fixture = Fixture::new("histogram");
+    let output_file = fixture.join("output.json");
+    write_results(
+        &results,
+        &OutputFormat::Json,
+        Some(&output_file),
+        false,
+        None,
+        "files",
+    )
+    .unwrap();
+    let output = std::fs::read_to_string(&output_file).unwrap();
+
+    let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(
+        output[0]["Histogram"]["buckets"][1],
+        serde_json::json!({"count": 1, "range_start": 10, "range_end": 20})
+    );
+}
+
+#[test]
+fn html_chart_labels_are_formatted_like_the_bucket_ranges() {
+    let results = vec![AnalyzerResult::Histogram(Histogram::new(
+        vec![
+            HistogramBucket::new(2, 0..2000),
+            HistogramBucket::new(1, 2000..4000),
+        ],
+        1500.0,
+        500.0,
+    ))];
+    let fixture = Fixture::new("report");
+    let write = |raw_numbers: bool| {
+        let output_file = fixture.join("report.html");
+        write_results(
+            &results,
+            &OutputFormat::Html,
+            Some(&output_file),
+            raw_numbers,
+            None,
+            "files",
+        )
+        .unwrap();
+        let output = std::fs::read_to_string(&output_file).unwrap();
+        output
+    };
+
+    for raw_numbers in [false, true].iter() {
+        let output = write(*raw_numbers);
+        let lines = output.lines().collect::<Vec<_>>();
+        let mut num_labels = 0;
+        for (bar, label) in lines.iter().zip(lines.iter().skip(1)) {
+            if !bar.starts_with("<rect") {
+                continue;
+            }
+            let start = &bar[bar.find('[').unwrap() + 1..bar.find(';').unwrap()];
+            assert!(label.ends_with(&format!(">{}</text>", start)));
+            num_labels += 1;
+        }
+        assert_eq!(2, num_labels);
+    }
+}
+
+#[test]
+fn influx_output_writes_a_single_line() {
+    let results = vec![
+        AnalyzerResult::NodeCount(3),
+        AnalyzerResult::Histogram(Histogram::new(
+            vec![HistogramBucket::new(3, 0..10)],
+            7.5,
+            2.5,
+        )),
+    ];
+    let label = RunLabel {
+        label: "nightly build".to_owned(),
+        timestamp: "2024-03-01T12:00:00Z".to_owned(),
+    };
+    let fixture = Fixture::new("influx");
+    let output_file = fixture.join("output.txt");
+    write_results(
+        &results,
+        &OutputFormat::Influx,
+        Some(&output_file),
+        false,
+        Some(&label),
+        "potree-v2",
+    )
+    .unwrap();
+    let output = std::fs::read_to_string(&output_file).unwrap();
+
+    assert_eq!(
+        output,
+        "pointcloud,label=nightly\\ build,format=potree-v2 node_count=3i,mean_points=7.5 1709294400000000000\n"
+    );
+}
+
+#[cfg(feature = "xml")]
+#[test]
+fn xml_output_writes_one_element_per_result() {
+    let results = vec![
+        AnalyzerResult::NodeCount(3),
+        AnalyzerResult::Histogram(Histogram::new(
+            vec![HistogramBucket::new(3, 0..10)],
+            7.5,
+            2.5,
+        )),
+    ];
+    let fixture = Fixture::new("xml");
+    let output_file = fixture.join("output.xml");
+    write_results(
+        &results,
+        &OutputFormat::Xml,
+        Some(&output_file),
+        false,
+        None,
+        "files",
+    )
+    .unwrap();
+    let output = std::fs::read_to_string(&output_file).unwrap();
+
+    assert!(output.contains("<report>"));
+    assert!(output.contains("<nodeCount>3</nodeCount>"));
+    assert!(output.contains(r#"<histogram mean="7.5" stddev="2.5">"#));
+    assert!(output.contains(r#"<bucket count="3">"#));
+    assert!(output.contains(r#"<range end="10" start="0"/>"#));
+}
+
+#[test]
+fn csv_wide_output_appends_rows_below_a_single_header() {
+    let results = vec![
+        AnalyzerResult::NodeCount(3),
+        AnalyzerResult::Histogram(Histogram::new(
+            vec![HistogramBucket::new(3, 0..10)],
+            7.5,
+            2.5,
+        )),
+        AnalyzerResult::ApproxQuantiles {
+            relative_accuracy: 0.01,
+            quantiles: vec![(0.5, 7.0), (0.99, 9.0)],
+        },
+    ];
+    let fixture = Fixture::new("csv_wide");
+    let output_file = fixture.join("output.csv");
+    for _ in 0..2 {
+        write_results(
+            &results,
+            &OutputFormat::CsvWide,
+            Some(&output_file),
+            false,
+            None,
+            "potree-v2",
+        )
+        .unwrap();
+    }
+    let output = std::fs::read_to_string(&output_file).unwrap();
+
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        vec![
+            "format,node_count,histogram.mean,histogram.stddev,approx_quantiles.p50,approx_quantiles.p99",
+            "potree-v2,3,7.5,2.5,7,9",
+            "potree-v2,3,7.5,2.5,7,9",
+        ]
+    );
+}
+
+#[test]
+fn csv_wide_output_starts_with_the_label_and_timestamp() {
+    let results = vec![AnalyzerResult::NodeCount(3)];
+    let label = RunLabel {
+        label: "flight_2024_03, north".to_owned(),
+        timestamp: "2024-03-01T12:00:00Z".to_owned(),
+    };
+    let fixture = Fixture::new("csv_wide_label");
+    let output_file = fixture.join("output.csv");
+    write_results(
+        &results,
+        &OutputFormat::CsvWide,
+        Some(&output_file),
+        false,
+        Some(&label),
+        "files",
+    )
+    .unwrap();
+    let output = std::fs::read_to_string(&output_file).unwrap();
+
+    assert_eq!(
+        output.lines().collect::<Vec<_>>(),
+        vec![
+            "label,timestamp,format,node_count",
+            "\"flight_2024_03, north\",2024-03-01T12:00:00Z,files,3",
+        ]
+    );
+}
+
+#[test]
+fn csv_levels_output_writes_one_row_per_level() {
+    let results = vec![
+        AnalyzerResult::NodeCount(3),
+        AnalyzerResult::LevelSummary(vec![
+            LevelRow {
+                level: 0,
+                node_count: 1,
+                point_count: 1000,
+                mean_points_per_node: 1000.0,
+                point_share: 0.25,
+            },
+            LevelRow {
+                level: 1,
+                node_count: 2,
+                point_count: 3000,
+                mean_points_per_node: 1500.0,
+                point_share: 0.75,
+            },
+        ]),
+    ];
+    let fixture = Fixture::new("csv_levels");
+    let output_file = fixture.join("output.csv");
+    write_results(
+        &results,
+        &OutputFormat::CsvLevels,
+        Some(&output_file),
+        false,
+        None,
+        "potree-v2",
+    )
+    .unwrap();
+    let output = std::fs::read_to_string(&output_file).unwrap();
+
+    assert_eq!(
+        output.lines().collect::<Vec<_>>(),
+        vec![
+            "level,node_count,total_points,mean_points,point_share",
+            "0,1,1000,1000,0.25",
+            "1,2,3000,1500,0.75",
+        ]
+    );
+}
+
+#[test]
+fn csv_histogram_matrix_output_adds_one_column_per_dataset() {
+    let histogram = |buckets: Vec<HistogramBucket>| {
+        vec![AnalyzerResult::Histogram(Histogram::new(buckets, 0.0, 0.0))]
+    };
+    let label = |name: &str| RunLabel {
+        label: name.to_owned(),
+        timestamp: "2024-03-01T12:00:00Z".to_owned(),
+    };
+    let fixture = Fixture::new("histogram_matrix");
+    let output_file = fixture.join("output.csv");
+    let write = |results: &[AnalyzerResult], name: &str| {
+        write_results(
+            results,
+            &OutputFormat::CsvHistogramMatrix,
+            Some(&output_file),
+            false,
+            Some(&label(name)),
+            "files",
+        )
+    };
+
+    write(
+        &histogram(vec![
+            HistogramBucket::new(2, 0..10),
+            HistogramBucket::new(1, 10..20),
+            HistogramBucket::new(1, 20..25),
+        ]),
+        "small",
+    )
+    .unwrap();
+    write(
+        &histogram(vec![
+            HistogramBucket::new(1, 0..10),
+            HistogramBucket::new(0, 10..20),
+            HistogramBucket::new(4, 20..35),
+        ]),
+        "large, dense",
+    )
+    .unwrap();
+    let overlapping = write(&histogram(vec![HistogramBucket::new(1, 0..15)]), "other");
+    let output = std::fs::read_to_string(&output_file).unwrap();
+
+    assert!(overlapping.is_err());
+    assert_eq!(
+        output.lines().collect::<Vec<_>>(),
+        vec![
+            "bucket_start,bucket_end,small,\"large, dense\"",
+            "0,10,2,1",
+            "10,20,1,0",
+            "20,35,1,4",
+        ]
+    );
+}
+
+#[test]
+fn csv_histogram_matrix_output_skips_empty_bucket_ranges() {
+    let fixture = Fixture::new("histogram_matrix_empty_ranges");
+    let manifest = fixture.write("manifest.csv", "node,points\nr,1\nr0,3\nr1,3\n");
+    let options = AnalysisOptions {
+        histogram_config: Some(HistogramConfig::Linear(10)),
+        ..Default::default()
+    };
+    let results = ManifestAnalyzer::new(manifest, options)
+        .unwrap()
+        .run()
+        .unwrap();
+    let output_file = fixture.join("output.csv");
+    write_results(
+        &results,
+        &OutputFormat::CsvHistogramMatrix,
+        Some(&output_file),
+        false,
+        None,
+        "manifest",
+    )
+    .unwrap();
+    let output = std::fs::read_to_string(&output_file).unwrap();
+
+    assert_eq!(
+        output.lines().collect::<Vec<_>>(),
+        vec![
+            "bucket_start,bucket_end,manifest",
+            "0,1,0",
+            "1,2,1",
+            "2,3,0",
+            "3,4,2",
+        ]
+    );
+}
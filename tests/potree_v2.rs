@@ -0,0 +1,92 @@
+use point_cloud_tiles_analyzer::error::AnalyzerError;
+use point_cloud_tiles_analyzer::potree_v2::{
+    infer_record_layout, walk_hierarchy, walk_hierarchy_lenient, RecordLayout,
+};
+
+/// Encodes a node record with 32-bit point counts, as written by PotreeConverter v2
+fn record(
+    node_type: u8,
+    child_mask: u8,
+    num_points: u32,
+    byte_offset: u64,
+    byte_size: u64,
+) -> Vec<u8> {
+    let mut bytes = vec![node_type, child_mask];
+    bytes.extend_from_slice(&num_points.to_le_bytes());
+    bytes.extend_from_slice(&byte_offset.to_le_bytes());
+    bytes.extend_from_slice(&byte_size.to_le_bytes());
+    bytes
+}
+
+/// A hierarchy whose node r0 is stored in a second chunk, referenced by a proxy node in the first chunk
+fn two_chunk_hierarchy(second_chunk_size: u64) -> Vec<u8> {
+    [
+        record(0, 0b11, 100, 0, 0),
+        record(2, 0, 0, 66, second_chunk_size),
+        record(1, 0, 10, 0, 0),
+        record(0, 0b1, 50, 0, 0),
+        record(1, 0, 5, 0, 0),
+    ]
+    .concat()
+}
+
+#[test]
+fn walk_follows_proxy_nodes_into_other_chunks() {
+    let nodes = walk_hierarchy(&two_chunk_hierarchy(44), RecordLayout::default(), None).unwrap();
+
+    let mut names = nodes
+        .iter()
+        .map(|node| (node.name.as_str(), node.record.num_points))
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec![("r", 100), ("r0", 50), ("r00", 5), ("r1", 10)]);
+}
+
+#[test]
+fn walk_rejects_nodes_outside_of_their_chunk() {
+    let error =
+        walk_hierarchy(&two_chunk_hierarchy(22), RecordLayout::default(), None).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<AnalyzerError>(),
+        Some(AnalyzerError::CorruptHierarchy(_))
+    ));
+}
+
+#[test]
+fn lenient_walk_reports_missing_children() {
+    let walk =
+        walk_hierarchy_lenient(&two_chunk_hierarchy(22), RecordLayout::default(), None).unwrap();
+
+    assert_eq!(walk.nodes.len(), 3);
+    assert_eq!(walk.missing, vec!["r00".to_owned()]);
+}
+
+/// Encodes a node record with 64-bit point counts
+fn record_u64(node_type: u8, child_mask: u8, num_points: u64, byte_offset: u64) -> Vec<u8> {
+    let mut bytes = vec![node_type, child_mask];
+    bytes.extend_from_slice(&num_points.to_le_bytes());
+    bytes.extend_from_slice(&byte_offset.to_le_bytes());
+    bytes.extend_from_slice(&(num_points * 15).to_le_bytes());
+    bytes
+}
+
+#[test]
+fn infer_record_layout_picks_the_layout_under_which_the_hierarchy_is_sane() {
+    let (layout, _) = infer_record_layout(&two_chunk_hierarchy(44)).unwrap();
+    assert_eq!(layout, RecordLayout::U32_POINT_COUNTS);
+
+    // 11 records of 26 bytes are also a multiple of 22 bytes, so the size alone can't tell the layouts apart
+    let mut records = vec![record_u64(0, 0xff, 20_000, 0)];
+    records.push(record_u64(0, 0b11, 12_000, 300_000));
+    for child in 1..8 {
+        records.push(record_u64(1, 0, 9_000 + child, 480_000 + child * 200_000));
+    }
+    records.push(record_u64(1, 0, 4_000, 2_000_000));
+    records.push(record_u64(1, 0, 3_500, 2_060_000));
+    let hierarchy = records.concat();
+    assert_eq!(hierarchy.len() % 22, 0);
+
+    let (layout, candidates) = infer_record_layout(&hierarchy).unwrap();
+    assert_eq!(layout, RecordLayout::U64_POINT_COUNTS);
+    assert!(candidates[0].rejection.is_some());
+}
@@ -0,0 +1,16 @@
+use point_cloud_tiles_analyzer::progress::{ProgressTracker, ProgressUpdateCondition};
+
+#[test]
+fn throughput_window_limits_the_number_of_samples() {
+    let mut tracker = ProgressTracker::new(100.0, ProgressUpdateCondition::OnProgressChanged(1.0))
+        .with_throughput_window(4)
+        .silent();
+    for samples in 1..=4 {
+        tracker.inc_progress(1.0);
+        assert_eq!(tracker.throughput_samples(), samples);
+    }
+    for _ in 0..10 {
+        tracker.inc_progress(1.0);
+        assert_eq!(tracker.throughput_samples(), 4);
+    }
+}
@@ -0,0 +1,35 @@
+use point_cloud_tiles_analyzer::quantiles::QuantileSketch;
+
+#[test]
+fn estimates_are_within_relative_accuracy() {
+    let relative_accuracy = 0.01;
+    let mut sketch = QuantileSketch::new(relative_accuracy);
+    let values = (1..=10_000_u64).collect::<Vec<_>>();
+    for &value in values.iter() {
+        sketch.insert(value);
+    }
+
+    assert_eq!(sketch.count(), values.len() as u64);
+    for &q in [0.0, 0.5, 0.9, 0.99, 1.0].iter() {
+        let exact = values[(q * (values.len() - 1) as f64).floor() as usize] as f64;
+        let estimate = sketch.quantile(q).unwrap();
+        assert!(
+            (estimate - exact).abs() <= relative_accuracy * exact,
+            "quantile {}: expected {}, got {}",
+            q,
+            exact,
+            estimate
+        );
+    }
+}
+
+#[test]
+fn zeros_and_empty_sketch() {
+    let mut sketch = QuantileSketch::new(0.01);
+    assert_eq!(sketch.quantile(0.5), None);
+
+    sketch.insert(0);
+    sketch.insert(0);
+    sketch.insert(100);
+    assert_eq!(sketch.quantile(0.5), Some(0.0));
+}
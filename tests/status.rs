@@ -0,0 +1,59 @@
+use point_cloud_tiles_analyzer::analyzer::{
+    AnalysisOptions, Analyzer, AnalyzerResult, HistogramConfig, MultiFileAnalyzer,
+};
+use point_cloud_tiles_analyzer::status::StatusFile;
+use regex::Regex;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod common;
+use common::{Fixture, COUNT_FROM_NAME};
+
+#[test]
+fn status_file_contains_the_results_so_far_and_the_final_state() {
+    let fixture = Fixture::new("status");
+    let path = fixture.join("status.json");
+    let status_file = StatusFile::new(&path, Duration::from_secs(3600));
+
+    status_file.add_result(&AnalyzerResult::NodeCount(3));
+    let status: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(status["state"], "running");
+    assert_eq!(status["results"][0]["NodeCount"], 3);
+
+    status_file.set_progress(50.0, Some(10.0));
+    status_file.finish(None, false).unwrap();
+    let status: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(status["state"], "finished");
+    // The progress update came within the interval of the last write, so it only shows up in the final status
+    assert_eq!(status["percentage"], 50.0);
+    assert_eq!(status["results"].as_array().unwrap().len(), 1);
+    assert!(!fixture.join("status.json.tmp").exists());
+}
+
+#[test]
+fn progress_of_a_single_file_reaches_100_percent() {
+    let fixture = Fixture::new("single_file_progress");
+    fixture.write_empty(&["r_count10.las"]);
+    let path = fixture.join("status.json");
+    // Every progress update is written to the status file
+    let status_file = Arc::new(StatusFile::new(&path, Duration::from_secs(0)));
+
+    let options = AnalysisOptions {
+        histogram_config: Some(HistogramConfig::Linear(2)),
+        count_from_name: Some(Regex::new(COUNT_FROM_NAME).unwrap()),
+        status_file: Some(status_file),
+        quiet: true,
+        ..Default::default()
+    };
+    MultiFileAnalyzer::new(fixture.path(), options)
+        .unwrap()
+        .run()
+        .unwrap();
+    let status: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+
+    // A NaN percentage would be written as null
+    assert_eq!(status["percentage"], 100.0);
+}
@@ -0,0 +1,60 @@
+use point_cloud_tiles_analyzer::vpc::{read_entries, VpcEntry};
+
+mod common;
+use common::Fixture;
+
+/// Writes a VPC file with the given features to the fixture directory `name` and reads its entries
+fn read_vpc(name: &str, features: &str) -> anyhow::Result<Vec<VpcEntry>> {
+    let fixture = Fixture::new(name);
+    let path = fixture.write(
+        "dataset.vpc",
+        format!(
+            r#"{{"type": "FeatureCollection", "features": [{}]}}"#,
+            features
+        ),
+    );
+    read_entries(&path)
+}
+
+#[test]
+fn read_entries_prefers_proj_bbox_and_leaves_the_height_of_2d_bounds_unknown() {
+    let entries = read_vpc(
+        "vpc_bounds",
+        r#"
+        {
+            "bbox": [7.0, 50.0, 7.1, 50.1],
+            "properties": { "pc:count": 5, "proj:bbox": [1000.0, 2000.0, 10.0, 1100.0, 2100.0, 20.0] },
+            "assets": { "data": { "href": "a.las" } }
+        },
+        {
+            "bbox": [1100.0, 2000.0, 1200.0, 2100.0],
+            "properties": { "pc:count": 3 },
+            "assets": { "data": { "href": "b.las" } }
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        entries[0].bounds,
+        Some(([1000.0, 2000.0, 10.0], [1100.0, 2100.0, 20.0]))
+    );
+    assert_eq!(
+        entries[1].bounds,
+        Some((
+            [1100.0, 2000.0, f64::NEG_INFINITY],
+            [1200.0, 2100.0, f64::INFINITY]
+        ))
+    );
+}
+
+#[test]
+fn read_entries_rejects_features_without_an_asset() {
+    let error = read_vpc(
+        "vpc_missing_asset",
+        r#"
+        { "id": "a", "properties": { "pc:count": 5 }, "assets": { "data": { "href": "a.las" } } },
+        { "id": "b", "properties": { "pc:count": 3 }, "assets": {} }"#,
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("Feature b"), "{}", error);
+}